@@ -0,0 +1,185 @@
+//! Metrics recorded from an earlier run, so that only what got worse since fails a check.
+//!
+//! Thresholds adopted on a codebase that already goes past them fail on everything at once. A
+//! baseline holds each file's score and each line's metrics as they were, and a violation stands
+//! only where its metric is now above what was recorded, or where nothing was.
+//!
+//! Lines are matched by their text rather than their number, so lines added or removed above one
+//! do not make it new; of several lines with the same text, the one recorded nearest is taken.
+//! Files are recorded by their path from the baseline's directory, so a baseline holds whichever
+//! directory lintric runs in and however the paths analyzed are written.
+
+use crate::paths;
+use crate::thresholds::Violation;
+use lintric_core::models::OverallAnalysisReport;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where `--write-baseline` writes when not given a file.
+pub const DEFAULT_BASELINE_FILE: &str = "lintric-baseline.json";
+
+/// A value this close to the recorded one is the same, whatever float formatting did to it.
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub average_complexity_score: f64,
+    pub files: BTreeMap<String, FileBaseline>,
+    /// The directory of the baseline's file, which the paths of its files are relative to.
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileBaseline {
+    pub overall_complexity_score: f64,
+    /// The lines that depend on something, in line order. A line reported only for what depends
+    /// on it has none of the metrics recorded here, so it is left out.
+    pub lines: Vec<LineBaseline>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineBaseline {
+    pub line: usize,
+    /// The line's source, trimmed.
+    pub text: String,
+    pub total_dependencies: usize,
+    pub depth: usize,
+    pub transitive_dependencies: usize,
+}
+
+impl Baseline {
+    /// The baseline of a report, to be saved at `path`.
+    pub fn from_report(report: &OverallAnalysisReport, path: &Path) -> Self {
+        let dir = directory_of(path);
+        let mut sources = Sources::default();
+        let files = report
+            .results
+            .iter()
+            .map(|result| {
+                let lines = result
+                    .line_metrics
+                    .iter()
+                    .filter(|metrics| metrics.total_dependencies > 0)
+                    .map(|metrics| LineBaseline {
+                        line: metrics.line_number,
+                        text: sources.line(&result.file_path, metrics.line_number),
+                        total_dependencies: metrics.total_dependencies,
+                        depth: metrics.depth,
+                        transitive_dependencies: metrics.transitive_dependencies,
+                    })
+                    .collect();
+                (
+                    file_key(&dir, &result.file_path),
+                    FileBaseline {
+                        overall_complexity_score: result.overall_complexity_score,
+                        lines,
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            average_complexity_score: report.average_complexity_score,
+            files,
+            dir,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        let baseline: Self = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        Ok(Self {
+            dir: directory_of(path),
+            ..baseline
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize baseline: {e}"))?;
+
+        fs::write(path, format!("{content}\n"))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// The violations whose metric is above what was recorded for it, or that nothing was
+    /// recorded for.
+    pub fn new_violations(&self, violations: Vec<Violation>) -> Vec<Violation> {
+        let mut sources = Sources::default();
+        violations
+            .into_iter()
+            .filter(|violation| {
+                self.recorded(violation, &mut sources)
+                    .is_none_or(|recorded| violation.value > recorded + TOLERANCE)
+            })
+            .collect()
+    }
+
+    fn recorded(&self, violation: &Violation, sources: &mut Sources) -> Option<f64> {
+        let Some(file_path) = &violation.file_path else {
+            return Some(self.average_complexity_score);
+        };
+        let file = self.files.get(&file_key(&self.dir, file_path))?;
+        let Some(line) = violation.line else {
+            return Some(file.overall_complexity_score);
+        };
+
+        let text = sources.line(file_path, line);
+        let recorded = file
+            .lines
+            .iter()
+            .filter(|recorded| recorded.text == text)
+            .min_by_key(|recorded| recorded.line.abs_diff(line))?;
+        let value = match violation.metric {
+            "total_dependencies" => recorded.total_dependencies,
+            "depth" => recorded.depth,
+            "transitive_dependencies" => recorded.transitive_dependencies,
+            _ => return None,
+        };
+        Some(value as f64)
+    }
+}
+
+/// The directory a baseline's file is in, made absolute.
+fn directory_of(path: &Path) -> PathBuf {
+    let path = paths::normalized(path);
+    path.parent().map(Path::to_path_buf).unwrap_or(path)
+}
+
+/// The path a file is recorded under: its path from the baseline's directory, the same on every
+/// platform.
+fn file_key(dir: &Path, file_path: &str) -> String {
+    paths::relative_to(dir, Path::new(file_path))
+}
+
+/// The lines of the files analyzed, read once each.
+#[derive(Default)]
+struct Sources {
+    files: HashMap<String, Vec<String>>,
+}
+
+impl Sources {
+    /// A line's source, trimmed, or nothing if the file cannot be read.
+    fn line(&mut self, file_path: &str, line: usize) -> String {
+        let lines = self.files.entry(file_path.to_string()).or_insert_with(|| {
+            fs::read_to_string(file_path)
+                .map(|content| {
+                    content
+                        .lines()
+                        .map(|line| line.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        line.checked_sub(1)
+            .and_then(|index| lines.get(index))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
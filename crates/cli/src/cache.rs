@@ -0,0 +1,137 @@
+//! Analyses kept on disk between runs, so a file that has not changed is not analyzed again.
+//!
+//! A file's analysis depends on nothing but its content, its language and the version of lintric
+//! analyzing it, so those are the key, with the format of the entries; the path is not, and a file
+//! moved or copied unchanged is still found. Only analysis of one file at a time is kept: analyzing
+//! a project follows imports into other files, and its result for one file depends on theirs.
+
+use lintric_core::models::{AnalysisResult, IntermediateRepresentation, Language};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Where analyses are kept unless `--cache-dir` says otherwise, relative to the project.
+pub const DEFAULT_CACHE_DIR: &str = ".lintric/cache";
+
+/// The format of the entries, part of every key. Raised whenever an entry would be read back
+/// differently, so that entries written before are not found rather than misread, whether or not
+/// the version of lintric changed with it.
+const FORMAT: &str = "2";
+
+/// Distinguishes the temporary files of writes happening at once, which `--jobs` allows.
+static PENDING_WRITES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    ir: IntermediateRepresentation,
+    result: AnalysisResult,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache of a project whose `lintric.toml` is in `config_dir`, if it has one, and which is
+    /// analyzed from `paths`: `DEFAULT_CACHE_DIR` in the config's directory, or without one in the
+    /// first path, or the directory of that file, so it is the same whichever directory lintric
+    /// runs in.
+    pub fn for_project(config_dir: &Path, paths: &[String]) -> Self {
+        let root = if config_dir.as_os_str().is_empty() {
+            paths.first().map_or_else(PathBuf::new, |path| {
+                let path = Path::new(path);
+                if path.is_file() {
+                    path.parent().unwrap_or(Path::new("")).to_path_buf()
+                } else {
+                    path.to_path_buf()
+                }
+            })
+        } else {
+            config_dir.to_path_buf()
+        };
+        Self::new(root.join(DEFAULT_CACHE_DIR))
+    }
+
+    /// The key of a file's analysis: a hash of the entry format, the lintric version, the language
+    /// and the content.
+    pub fn key(content: &str, language: &Language) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            FORMAT,
+            lintric_core::VERSION,
+            &language.to_string(),
+            content,
+        ] {
+            hasher.update(part.len().to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// The analysis kept under `key`, as if of the file at `file_path`.
+    ///
+    /// An entry that cannot be read, from an interrupted write or an older format, is as good as
+    /// none: the file is analyzed again and the entry replaced.
+    pub fn get(
+        &self,
+        key: &str,
+        file_path: &str,
+    ) -> Option<(IntermediateRepresentation, AnalysisResult)> {
+        let text = fs::read_to_string(self.entry_path(key)).ok()?;
+        let Entry { mut ir, mut result } = serde_json::from_str(&text).ok()?;
+        ir.file_path = file_path.to_string();
+        result.file_path = file_path.to_string();
+        Some((ir, result))
+    }
+
+    /// Keep an analysis under `key`.
+    ///
+    /// The entry is written beside its final name and then renamed, so a reader never sees half
+    /// of it, even with another run reading the same cache.
+    pub fn put(
+        &self,
+        key: &str,
+        ir: &IntermediateRepresentation,
+        result: &AnalysisResult,
+    ) -> Result<(), String> {
+        let entry_path = self.entry_path(key);
+        let dir = entry_path.parent().unwrap_or(Path::new(""));
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Error creating cache directory {}: {}", dir.display(), e))?;
+
+        let entry = Entry {
+            ir: ir.clone(),
+            result: result.clone(),
+        };
+        let text = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        let pending = dir.join(format!(
+            "{key}.{}.{}.tmp",
+            std::process::id(),
+            PENDING_WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&pending, text)
+            .and_then(|()| fs::rename(&pending, &entry_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&pending);
+                format!("Error writing cache entry {}: {}", entry_path.display(), e)
+            })
+    }
+
+    /// Entries are spread over directories named by the key's first two characters, so no one
+    /// directory holds every file of a large project.
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let (prefix, rest) = key.split_at(2.min(key.len()));
+        self.dir.join(prefix).join(format!("{rest}.json"))
+    }
+}
@@ -0,0 +1,269 @@
+//! The crates of a Cargo package or workspace, and the targets each builds.
+//!
+//! A directory of Rust code is not one program: a workspace holds several packages, and a package
+//! builds a library, binaries, tests, examples and benches, each a crate of its own rooted in one
+//! file. Reading them off the manifests the way Cargo does lets each be analyzed as the unit it is
+//! compiled as, and leaves out what Cargo never compiles into one, such as `target/` or `build.rs`.
+
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE_NAME: &str = "Cargo.toml";
+
+/// A package of the workspace, with the targets it builds.
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    /// In the order Cargo lists them: the library, then binaries, tests, examples and benches.
+    pub targets: Vec<Target>,
+}
+
+/// One crate a package builds, rooted in one file.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub kind: TargetKind,
+    pub name: String,
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Test,
+    Example,
+    Bench,
+}
+
+impl TargetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetKind::Lib => "lib",
+            TargetKind::Bin => "bin",
+            TargetKind::Test => "test",
+            TargetKind::Example => "example",
+            TargetKind::Bench => "bench",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<PackageSection>,
+    workspace: Option<WorkspaceSection>,
+    lib: Option<TargetSection>,
+    #[serde(default)]
+    bin: Vec<TargetSection>,
+    #[serde(default)]
+    test: Vec<TargetSection>,
+    #[serde(default)]
+    example: Vec<TargetSection>,
+    #[serde(default)]
+    bench: Vec<TargetSection>,
+}
+
+#[derive(Deserialize)]
+struct PackageSection {
+    name: String,
+    autolib: Option<bool>,
+    autobins: Option<bool>,
+    autotests: Option<bool>,
+    autoexamples: Option<bool>,
+    autobenches: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct WorkspaceSection {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TargetSection {
+    name: Option<String>,
+    path: Option<String>,
+}
+
+/// The manifest a path names: a `Cargo.toml` itself, or the one in a directory.
+pub fn manifest_for(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return (path.file_name()? == MANIFEST_FILE_NAME).then(|| path.to_path_buf());
+    }
+    let manifest = path.join(MANIFEST_FILE_NAME);
+    manifest.is_file().then_some(manifest)
+}
+
+/// Every package of the manifest's workspace, or the manifest's own package, in name order.
+///
+/// A workspace root that is also a package is one of its members, as in Cargo.
+pub fn discover(manifest_path: &Path) -> Result<Vec<Package>, String> {
+    let manifest = read_manifest(manifest_path)?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new(""));
+
+    let mut packages = Vec::new();
+    if let Some(workspace) = &manifest.workspace {
+        for member_dir in member_dirs(manifest_dir, workspace)? {
+            let member_manifest = member_dir.join(MANIFEST_FILE_NAME);
+            if member_manifest == manifest_path {
+                continue;
+            }
+            let member = read_manifest(&member_manifest)?;
+            packages.extend(package(&member_dir, &member));
+        }
+    }
+    packages.extend(package(manifest_dir, &manifest));
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+fn read_manifest(manifest_path: &Path) -> Result<Manifest, String> {
+    let text = fs::read_to_string(manifest_path)
+        .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?;
+    toml::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", manifest_path.display(), e))
+}
+
+/// The directories `members` names, globs expanded, less those `exclude` names.
+fn member_dirs(root: &Path, workspace: &WorkspaceSection) -> Result<Vec<PathBuf>, String> {
+    let excluded: Vec<PathBuf> = workspace
+        .exclude
+        .iter()
+        .map(|exclude| root.join(exclude))
+        .collect();
+
+    let mut dirs = Vec::new();
+    for member in &workspace.members {
+        let pattern = root.join(member);
+        let matches = glob::glob(&pattern.to_string_lossy())
+            .map_err(|e| format!("Invalid workspace member {member}: {e}"))?;
+        dirs.extend(matches.flatten().filter(|dir| {
+            dir.join(MANIFEST_FILE_NAME).is_file()
+                && !excluded.iter().any(|excluded| dir.starts_with(excluded))
+        }));
+    }
+    dirs.sort();
+    dirs.dedup();
+    Ok(dirs)
+}
+
+/// The package a manifest declares, if it declares one rather than only a workspace.
+fn package(manifest_dir: &Path, manifest: &Manifest) -> Option<Package> {
+    let section = manifest.package.as_ref()?;
+    let name = section.name.clone();
+    let mut targets = Vec::new();
+
+    let lib_root = manifest
+        .lib
+        .as_ref()
+        .and_then(|lib| lib.path.as_deref())
+        .map(|path| manifest_dir.join(path))
+        .or_else(|| {
+            let default = manifest_dir.join("src/lib.rs");
+            (section.autolib != Some(false) && default.is_file()).then_some(default)
+        });
+    if let Some(root) = lib_root {
+        let lib_name = manifest
+            .lib
+            .as_ref()
+            .and_then(|lib| lib.name.clone())
+            .unwrap_or_else(|| name.replace('-', "_"));
+        targets.push(Target {
+            kind: TargetKind::Lib,
+            name: lib_name,
+            root,
+        });
+    }
+
+    let mut auto_bins = auto_targets(manifest_dir, "src/bin");
+    let main = manifest_dir.join("src/main.rs");
+    if main.is_file() {
+        auto_bins.insert(name.clone(), main);
+    }
+    let kinds = [
+        (TargetKind::Bin, &manifest.bin, section.autobins, auto_bins),
+        (
+            TargetKind::Test,
+            &manifest.test,
+            section.autotests,
+            auto_targets(manifest_dir, "tests"),
+        ),
+        (
+            TargetKind::Example,
+            &manifest.example,
+            section.autoexamples,
+            auto_targets(manifest_dir, "examples"),
+        ),
+        (
+            TargetKind::Bench,
+            &manifest.bench,
+            section.autobenches,
+            auto_targets(manifest_dir, "benches"),
+        ),
+    ];
+
+    for (kind, declared, auto, discovered) in kinds {
+        let mut by_name = if auto == Some(false) {
+            BTreeMap::new()
+        } else {
+            discovered.clone()
+        };
+        // A target the manifest declares replaces the one discovered under the same name, and
+        // without a `path` is where discovery would have found it. It claims its root file, so no
+        // target discovered under another name is rooted there too.
+        for section in declared {
+            let root = match (&section.path, &section.name) {
+                (Some(path), _) => manifest_dir.join(path),
+                (None, Some(target_name)) => match discovered.get(target_name) {
+                    Some(root) => root.clone(),
+                    None => continue,
+                },
+                (None, None) => continue,
+            };
+            let target_name = section.name.clone().unwrap_or_else(|| stem(&root));
+            let claimed = paths::normalized(&root);
+            by_name.retain(|_, discovered_root| paths::normalized(discovered_root) != claimed);
+            by_name.insert(target_name, root);
+        }
+        targets.extend(
+            by_name
+                .into_iter()
+                .map(|(name, root)| Target { kind, name, root }),
+        );
+    }
+
+    Some(Package { name, targets })
+}
+
+/// The targets Cargo finds in a directory by itself: each `name.rs`, and each `name/main.rs`.
+fn auto_targets(manifest_dir: &Path, dir: &str) -> BTreeMap<String, PathBuf> {
+    let Ok(entries) = fs::read_dir(manifest_dir.join(dir)) else {
+        return BTreeMap::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter_map(|path| {
+            if path.is_dir() {
+                let main = path.join("main.rs");
+                main.is_file().then(|| (stem(&path), main))
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                Some((stem(&path), path))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
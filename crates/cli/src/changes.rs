@@ -0,0 +1,147 @@
+//! The files and lines a change touched, read from `git diff`.
+//!
+//! Feedback on a pull request is about what the pull request changed. Files it left alone are not
+//! analyzed at all, and of those it changed, only the lines it touched are reported, with the lines
+//! that depend on them, directly or through other lines, since their metrics change with them.
+
+use lintric_core::AnalysisResult;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What `--changed-since` takes to read the diff from standard input instead of running git.
+pub const STDIN: &str = "-";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Changes {
+    /// The lines of each changed file, as numbered after the change, by absolute path.
+    files: BTreeMap<PathBuf, BTreeSet<usize>>,
+}
+
+impl Changes {
+    /// The changes since a revision: those `git diff <revision>` shows, run from the working
+    /// directory, or those of a diff given on standard input when the revision is `-`.
+    pub fn since(revision: &str) -> Result<Self, String> {
+        let diff = if revision == STDIN {
+            let mut diff = String::new();
+            std::io::stdin()
+                .read_to_string(&mut diff)
+                .map_err(|e| format!("Failed to read diff from standard input: {e}"))?;
+            diff
+        } else {
+            git_diff(revision)?
+        };
+        let dir = std::env::current_dir()
+            .map_err(|e| format!("Failed to read working directory: {e}"))?;
+        Ok(Self::parse(&diff, &dir))
+    }
+
+    /// The changes of a unified diff whose paths are relative to `dir`.
+    ///
+    /// Only the new side of each file matters: a deleted file is not there to analyze, and a hunk
+    /// that only deletes lines touches none that remain.
+    pub fn parse(diff: &str, dir: &Path) -> Self {
+        let mut files: BTreeMap<PathBuf, BTreeSet<usize>> = BTreeMap::new();
+        let mut current: Option<PathBuf> = None;
+
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                current = match path.trim_end() {
+                    "/dev/null" => None,
+                    path => {
+                        let path = path.strip_prefix("b/").unwrap_or(path);
+                        Some(absolute(&dir.join(path)))
+                    }
+                };
+                if let Some(path) = &current {
+                    files.entry(path.clone()).or_default();
+                }
+            } else if line.starts_with("@@") {
+                let (Some(path), Some((start, count))) = (&current, new_range(line)) else {
+                    continue;
+                };
+                files
+                    .entry(path.clone())
+                    .or_default()
+                    .extend(start..start + count);
+            }
+        }
+
+        Self { files }
+    }
+
+    /// Whether the change touched a file.
+    pub fn touches(&self, file: &Path) -> bool {
+        self.files.contains_key(&absolute(file))
+    }
+
+    /// A changed file's result, reporting only the lines the change touched and the lines that
+    /// depend on them; or none for a file the change did not touch.
+    ///
+    /// The file's score is still that of the whole file.
+    pub fn restrict(&self, mut result: AnalysisResult) -> Option<AnalysisResult> {
+        let changed = self.files.get(&absolute(Path::new(&result.file_path)))?;
+
+        let mut affected = changed.clone();
+        loop {
+            let before = affected.len();
+            for metrics in &result.line_metrics {
+                if metrics
+                    .dependent_lines
+                    .iter()
+                    .any(|line| affected.contains(line))
+                {
+                    affected.insert(metrics.line_number);
+                }
+            }
+            if affected.len() == before {
+                break;
+            }
+        }
+
+        result
+            .line_metrics
+            .retain(|metrics| affected.contains(&metrics.line_number));
+        Some(result)
+    }
+}
+
+fn git_diff(revision: &str) -> Result<String, String> {
+    let output = Command::new("git")
+        .args([
+            "diff",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            "--relative",
+            revision,
+            "--",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git diff {} failed: {}",
+            revision,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The first line and the number of lines of a hunk's new side, from `@@ -a,b +c,d @@`.
+fn new_range(header: &str) -> Option<(usize, usize)> {
+    let range = header
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match range.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (range.parse().ok()?, 1),
+    };
+    Some((start, count))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,323 @@
+//! Settings read from a `lintric.toml`, so a project's choices live beside its code rather than on
+//! every command line.
+//!
+//! The file is found as Cargo finds a manifest: in the working directory or the nearest directory
+//! above it. What the command line says takes precedence over it, and `[[overrides]]` sections
+//! let one directory be treated differently from the rest, the later section winning where two
+//! name the same directory.
+
+use crate::thresholds::Thresholds;
+use lintric_core::{DependencyType, Granularity, Language, MeasureOptions, ScoreWeights};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = "lintric.toml";
+
+/// How the report is written when no `--json`, `--verbose`, `--html` or `--sarif` flag says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Summary,
+    Verbose,
+    Json,
+    Html,
+    Sarif,
+}
+
+/// A loaded `lintric.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// The directory the file is in, which its paths and globs are relative to.
+    pub dir: PathBuf,
+    /// Globs files must match, as well as those of `--include`.
+    pub include: Vec<String>,
+    /// Globs leaving files out, as well as those of `--exclude`.
+    pub exclude: Vec<String>,
+    /// Kinds of dependency added to those of `--exclude-dependency-type`.
+    pub exclude_dependency_types: Vec<DependencyType>,
+    pub granularity: Option<Granularity>,
+    pub format: Option<OutputFormat>,
+    /// The language of files by extension, where it is not the one the extension says.
+    languages: BTreeMap<String, Language>,
+    weights: WeightSettings,
+    thresholds: Thresholds,
+    overrides: Vec<Override>,
+}
+
+/// Settings for the files under one directory.
+#[derive(Debug, Clone)]
+struct Override {
+    dir: PathBuf,
+    languages: BTreeMap<String, Language>,
+    weights: WeightSettings,
+    thresholds: Thresholds,
+}
+
+/// The score weights a `[weights]` table or `--weight` flags set, leaving the others as they were.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeightSettings {
+    #[serde(default, deserialize_with = "weight")]
+    total_dependencies: Option<f64>,
+    #[serde(default, deserialize_with = "weight")]
+    dependency_distance_cost: Option<f64>,
+    #[serde(default, deserialize_with = "weight")]
+    depth: Option<f64>,
+    #[serde(default, deserialize_with = "weight")]
+    transitive_dependencies: Option<f64>,
+    #[serde(default, deserialize_with = "weight")]
+    fan_in: Option<f64>,
+    #[serde(default, deserialize_with = "weight")]
+    blast_radius: Option<f64>,
+}
+
+/// A weight of `[weights]`, which must be one `--weight` would accept.
+fn weight<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let value = f64::deserialize(deserializer)?;
+    valid_weight(value)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// A weight scales its metric's share of the score, so a negative one would make more of the
+/// metric lower the score, and one that is not finite would leave no score at all.
+fn valid_weight(value: f64) -> Result<f64, String> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(format!(
+            "weight must be a finite number no less than 0, got {value}"
+        ))
+    }
+}
+
+impl WeightSettings {
+    /// Weights given as `--weight NAME=VALUE` flags, named as the metrics are.
+    pub fn from_flags(flags: &[String]) -> Result<Self, String> {
+        let mut settings = Self::default();
+        for flag in flags {
+            let (name, value) = flag
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid --weight {flag}: expected NAME=VALUE"))?;
+            let value: f64 = value
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid --weight {flag}: {e}"))
+                .and_then(|value| {
+                    valid_weight(value).map_err(|e| format!("Invalid --weight {flag}: {e}"))
+                })?;
+            let weight = match name.trim() {
+                "total_dependencies" => &mut settings.total_dependencies,
+                "dependency_distance_cost" => &mut settings.dependency_distance_cost,
+                "depth" => &mut settings.depth,
+                "transitive_dependencies" => &mut settings.transitive_dependencies,
+                "fan_in" => &mut settings.fan_in,
+                "blast_radius" => &mut settings.blast_radius,
+                _ => return Err(format!("Invalid --weight {flag}: unknown metric {name}")),
+            };
+            *weight = Some(value);
+        }
+        Ok(settings)
+    }
+
+    pub fn apply(&self, weights: &mut ScoreWeights) {
+        let settings = [
+            (self.total_dependencies, &mut weights.total_dependencies),
+            (
+                self.dependency_distance_cost,
+                &mut weights.dependency_distance_cost,
+            ),
+            (self.depth, &mut weights.depth),
+            (
+                self.transitive_dependencies,
+                &mut weights.transitive_dependencies,
+            ),
+            (self.fan_in, &mut weights.fan_in),
+            (self.blast_radius, &mut weights.blast_radius),
+        ];
+        for (setting, weight) in settings {
+            if let Some(setting) = setting {
+                *weight = setting;
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    exclude_dependency_types: Vec<String>,
+    granularity: Option<Granularity>,
+    format: Option<OutputFormat>,
+    #[serde(default)]
+    languages: BTreeMap<String, String>,
+    #[serde(default)]
+    weights: WeightSettings,
+    #[serde(default)]
+    thresholds: Thresholds,
+    #[serde(default)]
+    overrides: Vec<OverrideSection>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OverrideSection {
+    /// The directory, relative to the one `lintric.toml` is in.
+    path: String,
+    #[serde(default)]
+    languages: BTreeMap<String, String>,
+    #[serde(default)]
+    weights: WeightSettings,
+    #[serde(default)]
+    thresholds: Thresholds,
+}
+
+/// The `lintric.toml` in `start` or the nearest directory above it.
+pub fn find(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|config| config.is_file())
+}
+
+pub fn load(config_path: &Path) -> Result<Config, String> {
+    let text = fs::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read {}: {}", config_path.display(), e))?;
+    let file: ConfigFile = toml::from_str(&text)
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+
+    let config_dir = std::path::absolute(config_path)
+        .map_err(|e| format!("Failed to resolve {}: {}", config_path.display(), e))?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    let overrides = file
+        .overrides
+        .into_iter()
+        .map(|section| {
+            Ok(Override {
+                dir: config_dir.join(&section.path),
+                languages: languages(section.languages)?,
+                weights: section.weights,
+                thresholds: section.thresholds,
+            })
+        })
+        .collect::<Result<_, String>>()
+        .map_err(|e| format!("{}: {}", config_path.display(), e))?;
+
+    Ok(Config {
+        dir: config_dir,
+        include: file.include,
+        exclude: file.exclude,
+        exclude_dependency_types: file
+            .exclude_dependency_types
+            .iter()
+            .map(|name| name.parse())
+            .collect::<Result<_, String>>()
+            .map_err(|e| format!("{}: {}", config_path.display(), e))?,
+        granularity: file.granularity,
+        format: file.format,
+        languages: languages(file.languages)
+            .map_err(|e| format!("{}: {}", config_path.display(), e))?,
+        weights: file.weights,
+        thresholds: file.thresholds,
+        overrides,
+    })
+}
+
+impl Config {
+    /// The language a file is analyzed as, if any: as configured for its extension, or else as
+    /// the extension says.
+    pub fn language(&self, file: &Path) -> Option<Language> {
+        let configured = file
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| {
+                self.overrides_for(file)
+                    .find_map(|section| section.languages.get(extension))
+                    .or_else(|| self.languages.get(extension))
+            });
+        configured
+            .cloned()
+            .or_else(|| Language::from_extension(file))
+    }
+
+    /// How metrics are measured, as far as the config says.
+    pub fn measure_options(&self) -> MeasureOptions {
+        MeasureOptions {
+            granularity: self.granularity.unwrap_or_default(),
+            excluded_dependency_types: self.exclude_dependency_types.clone(),
+            // Scored file by file, as the overrides for each say, once measured.
+            ..Default::default()
+        }
+    }
+
+    /// The weights of files outside every override that sets them.
+    pub fn weights(&self) -> ScoreWeights {
+        let mut weights = ScoreWeights::default();
+        self.weights.apply(&mut weights);
+        weights
+    }
+
+    /// The weights a file is scored with: those of `[weights]`, then those of every override for
+    /// the directories it is in, in the order they are written.
+    pub fn weights_for(&self, file: &Path) -> ScoreWeights {
+        let mut weights = self.weights();
+        let overrides: Vec<&Override> = self.overrides_for(file).collect();
+        for section in overrides.into_iter().rev() {
+            section.weights.apply(&mut weights);
+        }
+        weights
+    }
+
+    /// The limits of `[thresholds]`, which the report's average is held to.
+    pub fn thresholds(&self) -> Thresholds {
+        self.thresholds
+    }
+
+    /// The limits a file is held to: those of `[thresholds]`, then those of every override for
+    /// the directories it is in, in the order they are written.
+    pub fn thresholds_for(&self, file: &Path) -> Thresholds {
+        let overrides: Vec<&Override> = self.overrides_for(file).collect();
+        overrides
+            .into_iter()
+            .rev()
+            .fold(self.thresholds, |thresholds, section| {
+                thresholds.overridden_by(&section.thresholds)
+            })
+    }
+
+    /// The overrides for the directories a file is in, the one that wins first.
+    fn overrides_for<'a>(&'a self, file: &Path) -> impl Iterator<Item = &'a Override> {
+        let file = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+        self.overrides
+            .iter()
+            .rev()
+            .filter(move |section| file.starts_with(&section.dir))
+    }
+}
+
+/// A `[languages]` table, keyed by extension with or without its dot.
+fn languages(table: BTreeMap<String, String>) -> Result<BTreeMap<String, Language>, String> {
+    table
+        .into_iter()
+        .map(|(extension, name)| {
+            let extension = extension.trim_start_matches('.').to_string();
+            let language = match name.to_lowercase().as_str() {
+                "rust" => Language::Rust,
+                "typescript" | "javascript" => Language::TypeScript,
+                "tsx" | "jsx" => Language::TSX,
+                _ => return Err(format!("Unknown language {name} for .{extension}")),
+            };
+            Ok((extension, language))
+        })
+        .collect()
+}
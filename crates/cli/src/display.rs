@@ -1,12 +1,19 @@
+use crate::file_processor::{CrateResults, TargetResults};
 use crate::logger::Logger;
+use crate::thresholds::Violation;
+use crate::tree_diff::{DependencyChange, FileDiff, TreeDiff};
 use comfy_table::presets::UTF8_FULL_CONDENSED;
 use comfy_table::{Cell, Row, Table};
-use lintric_core::models::OverallAnalysisReport;
+use lintric_core::models::{AnalysisResult, OverallAnalysisReport, ScoreWeights};
+use lintric_core::DefinitionGraph;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// Display the analysis results in JSON format
 pub fn display_json(
     overall_report: &OverallAnalysisReport,
+    crates: &[CrateResults],
+    violations: &[Violation],
     base_paths: &[String],
     logger: &dyn Logger,
 ) {
@@ -16,6 +23,27 @@ pub fn display_json(
         total_files_analyzed: usize,
         total_overall_complexity_score: f64,
         average_complexity_score: f64,
+        weights: ScoreWeights,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        crates: Vec<JsonCrate>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        violations: Vec<Violation>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct JsonCrate {
+        name: String,
+        targets: Vec<JsonTarget>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct JsonTarget {
+        kind: crate::cargo_workspace::TargetKind,
+        name: String,
+        root: String,
+        files: Vec<String>,
+        total_overall_complexity_score: f64,
+        average_complexity_score: f64,
     }
 
     let report_for_json = JsonReport {
@@ -31,6 +59,45 @@ pub fn display_json(
         total_files_analyzed: overall_report.total_files_analyzed,
         total_overall_complexity_score: overall_report.total_overall_complexity_score,
         average_complexity_score: overall_report.average_complexity_score,
+        weights: overall_report.weights,
+        crates: crates
+            .iter()
+            .map(|krate| JsonCrate {
+                name: krate.name.clone(),
+                targets: krate
+                    .targets
+                    .iter()
+                    .map(|target| {
+                        let (total, average) = target_scores(target);
+                        JsonTarget {
+                            kind: target.kind,
+                            name: target.name.clone(),
+                            root: format_file_path_for_display(
+                                &target.root.to_string_lossy(),
+                                base_paths,
+                            ),
+                            files: target
+                                .results
+                                .iter()
+                                .map(|r| format_file_path_for_display(&r.file_path, base_paths))
+                                .collect(),
+                            total_overall_complexity_score: total,
+                            average_complexity_score: average,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect(),
+        violations: violations
+            .iter()
+            .map(|violation| Violation {
+                file_path: violation
+                    .file_path
+                    .as_ref()
+                    .map(|file_path| format_file_path_for_display(file_path, base_paths)),
+                ..violation.clone()
+            })
+            .collect(),
     };
 
     logger.info(&serde_json::to_string_pretty(&report_for_json).unwrap());
@@ -58,6 +125,8 @@ pub fn display_verbose(
             "Dist Cost",
             "Depth",
             "Transitive Deps",
+            "Fan-in",
+            "Blast Radius",
         ]);
         for metrics in &result.line_metrics {
             table.add_row(Row::from(vec![
@@ -66,6 +135,8 @@ pub fn display_verbose(
                 Cell::new(metrics.dependency_distance_cost),
                 Cell::new(metrics.depth),
                 Cell::new(metrics.transitive_dependencies),
+                Cell::new(metrics.fan_in),
+                Cell::new(metrics.blast_radius),
             ]));
         }
         logger.info(&format!("{table}"));
@@ -73,11 +144,50 @@ pub fn display_verbose(
             "Overall Complexity Score: {:.2}",
             result.overall_complexity_score
         ));
+        display_dependency_types(result, logger);
+        display_cycles(result, logger);
     }
 
     display_summary(overall_report, base_paths, logger);
 }
 
+/// Display how many dependencies a file has of each kind, if it has any
+fn display_dependency_types(result: &AnalysisResult, logger: &dyn Logger) {
+    if result.dependency_types.is_empty() {
+        return;
+    }
+    let counts: Vec<String> = result
+        .dependency_types
+        .iter()
+        .map(|(type_name, count)| format!("{type_name} {count}"))
+        .collect();
+    logger.info(&format!("Dependencies by Type: {}", counts.join(", ")));
+}
+
+/// Display the cycles of a file's lines and items, each with the symbols they depend on each other
+/// through, if there are any
+fn display_cycles(result: &AnalysisResult, logger: &dyn Logger) {
+    if result.cycle_count == 0 {
+        return;
+    }
+    logger.info("Cycles:");
+    for cycle in &result.line_cycles {
+        let lines: Vec<String> = cycle.lines.iter().map(|line| line.to_string()).collect();
+        logger.info(&format!(
+            "  Lines {} through {}",
+            lines.join(", "),
+            cycle.symbols.join(", ")
+        ));
+    }
+    for cycle in &result.definition_cycles {
+        logger.info(&format!(
+            "  Items {} through {}",
+            cycle.items.join(", "),
+            cycle.symbols.join(", ")
+        ));
+    }
+}
+
 /// Display summary analysis results with file-level metrics
 pub fn display_summary(
     overall_report: &OverallAnalysisReport,
@@ -86,7 +196,12 @@ pub fn display_summary(
 ) {
     let mut table = Table::new();
     table.load_style(UTF8_FULL_CONDENSED);
-    table.set_header(vec!["File", "Overall Complexity Score"]);
+    table.set_header(vec![
+        "File",
+        "Overall Complexity Score",
+        "Cycles",
+        "Most Complex Item",
+    ]);
 
     let mut sorted_results = overall_report.results.clone();
     sorted_results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
@@ -95,6 +210,8 @@ pub fn display_summary(
         table.add_row(Row::from(vec![
             Cell::new(format_file_path_for_display(&result.file_path, base_paths)),
             Cell::new(format!("{:.2}", result.overall_complexity_score)),
+            Cell::new(result.cycle_count),
+            Cell::new(most_complex_item(result)),
         ]));
     }
     logger.info(&format!("{table}"));
@@ -102,6 +219,302 @@ pub fn display_summary(
     display_overall_summary(overall_report, logger);
 }
 
+/// Display the summary of a watch session, with how far each file's score has moved since the
+/// session started, and the files it started with that are gone
+pub fn display_watch_summary(
+    overall_report: &OverallAnalysisReport,
+    started: &BTreeMap<String, f64>,
+    base_paths: &[String],
+    logger: &dyn Logger,
+) {
+    let mut table = Table::new();
+    table.load_style(UTF8_FULL_CONDENSED);
+    table.set_header(vec![
+        "File",
+        "Overall Complexity Score",
+        "Change",
+        "Cycles",
+        "Most Complex Item",
+    ]);
+
+    let mut rows: BTreeMap<&str, Row> = BTreeMap::new();
+    for result in &overall_report.results {
+        let score = result.overall_complexity_score;
+        let change = match started.get(&result.file_path) {
+            Some(start) => format!("{:+.2}", score - start),
+            None => "new".to_string(),
+        };
+        rows.insert(
+            &result.file_path,
+            Row::from(vec![
+                Cell::new(format_file_path_for_display(&result.file_path, base_paths)),
+                Cell::new(format!("{score:.2}")),
+                Cell::new(change),
+                Cell::new(result.cycle_count),
+                Cell::new(most_complex_item(result)),
+            ]),
+        );
+    }
+    for (file_path, start) in started {
+        rows.entry(file_path).or_insert_with(|| {
+            Row::from(vec![
+                Cell::new(format_file_path_for_display(file_path, base_paths)),
+                Cell::new("-"),
+                Cell::new(format!("{:+.2} (removed)", -start)),
+                Cell::new(""),
+                Cell::new(""),
+            ])
+        });
+    }
+    for row in rows.into_values() {
+        table.add_row(row);
+    }
+    logger.info(&format!("{table}"));
+
+    display_overall_summary(overall_report, logger);
+}
+
+/// The highest scoring function, method or class of a file, with its score, if any scores above
+/// zero. Of a class and its method with the same score, the method, which says more.
+pub fn most_complex_item(result: &AnalysisResult) -> String {
+    result
+        .item_metrics
+        .iter()
+        .filter(|item| item.complexity_score > 0.0)
+        .max_by(|a, b| a.complexity_score.total_cmp(&b.complexity_score))
+        .map_or(String::new(), |item| {
+            format!("{} ({:.2})", item.name, item.complexity_score)
+        })
+}
+
+/// Display the scores of each crate's targets, for comparing the crates of a workspace
+pub fn display_crates(crates: &[CrateResults], base_paths: &[String], logger: &dyn Logger) {
+    if crates.is_empty() {
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_style(UTF8_FULL_CONDENSED);
+    table.set_header(vec![
+        "Crate",
+        "Target",
+        "Root",
+        "Files",
+        "Total Score",
+        "Average Score",
+    ]);
+
+    for krate in crates {
+        for target in &krate.targets {
+            let (total, average) = target_scores(target);
+            let root = format_file_path_for_display(&target.root.to_string_lossy(), base_paths);
+            table.add_row(Row::from(vec![
+                Cell::new(&krate.name),
+                Cell::new(format!("{} {}", target.kind.as_str(), target.name)),
+                Cell::new(root),
+                Cell::new(target.results.len()),
+                Cell::new(format!("{total:.2}")),
+                Cell::new(format!("{average:.2}")),
+            ]));
+        }
+    }
+
+    logger.info("\n--- Crates ---");
+    logger.info(&format!("{table}"));
+}
+
+/// The total and average score of the files a target's crate reached.
+fn target_scores(target: &TargetResults) -> (f64, f64) {
+    let total: f64 = target
+        .results
+        .iter()
+        .map(|result| result.overall_complexity_score)
+        .sum();
+    let average = if target.results.is_empty() {
+        0.0
+    } else {
+        total / target.results.len() as f64
+    };
+    (total, average)
+}
+
+/// Display the metrics that went past their thresholds, if any did
+pub fn display_violations(violations: &[Violation], base_paths: &[String], logger: &dyn Logger) {
+    if violations.is_empty() {
+        return;
+    }
+
+    logger.info("\n--- Threshold Violations ---");
+    let mut table = Table::new();
+    table.load_style(UTF8_FULL_CONDENSED);
+    table.set_header(vec!["File", "Line", "Metric", "Value", "Threshold"]);
+    for violation in violations {
+        table.add_row(Row::from(vec![
+            Cell::new(
+                violation
+                    .file_path
+                    .as_ref()
+                    .map(|file_path| format_file_path_for_display(file_path, base_paths))
+                    .unwrap_or_default(),
+            ),
+            Cell::new(
+                violation
+                    .line
+                    .map(|line| line.to_string())
+                    .unwrap_or_default(),
+            ),
+            Cell::new(violation.metric),
+            Cell::new(format!("{:.2}", violation.value)),
+            Cell::new(format!("{:.2}", violation.threshold)),
+        ]));
+    }
+    logger.info(&format!("{table}"));
+}
+
+/// Display the items of a file and their dependencies as JSON
+pub fn display_definition_graph_json(definition_graph: &DefinitionGraph, logger: &dyn Logger) {
+    logger.info(&serde_json::to_string_pretty(definition_graph).unwrap());
+}
+
+/// Display the items of a file in source order, each with how many items depend on it, how many it
+/// depends on and which
+pub fn display_definition_graph(definition_graph: &DefinitionGraph, logger: &dyn Logger) {
+    if definition_graph.items.is_empty() {
+        logger.info(&format!(
+            "No items found in {}.",
+            definition_graph.file_path
+        ));
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_style(UTF8_FULL_CONDENSED);
+    table.set_header(vec![
+        "Item",
+        "Kind",
+        "Lines",
+        "Fan-in",
+        "Fan-out",
+        "Depends On",
+    ]);
+    for (index, item) in definition_graph.items.iter().enumerate() {
+        let kind = format!("{:?}", item.definition_type);
+        let depends_on: Vec<&str> = definition_graph
+            .dependencies(index)
+            .into_iter()
+            .map(|dependency| dependency.qualified_name.as_str())
+            .collect();
+        table.add_row(Row::from(vec![
+            Cell::new(&item.qualified_name),
+            Cell::new(kind.trim_end_matches("Definition")),
+            Cell::new(format!("{}-{}", item.start_line, item.end_line)),
+            Cell::new(item.fan_in),
+            Cell::new(item.fan_out),
+            Cell::new(depends_on.join(", ")),
+        ]));
+    }
+    logger.info(&format!("{table}"));
+}
+
+/// Display how the metrics of two trees differ as JSON
+pub fn display_tree_diff_json(tree_diff: &TreeDiff, logger: &dyn Logger) {
+    logger.info(&serde_json::to_string_pretty(tree_diff).unwrap());
+}
+
+/// Display how the metrics of two trees differ: each file's score, then the dependencies added and
+/// removed, then the lines that got worse
+pub fn display_tree_diff(tree_diff: &TreeDiff, logger: &dyn Logger) {
+    if tree_diff.files.is_empty() {
+        logger.info(&format!(
+            "No metrics changed between {} and {}.",
+            tree_diff.old, tree_diff.new
+        ));
+        return;
+    }
+
+    let format_score = |score: Option<f64>| score.map_or("-".to_string(), |s| format!("{s:.2}"));
+    let mut table = Table::new();
+    table.load_style(UTF8_FULL_CONDENSED);
+    table.set_header(vec!["File", "Status", "Old Score", "New Score", "Change"]);
+    for file in &tree_diff.files {
+        table.add_row(Row::from(vec![
+            Cell::new(&file.file_path),
+            Cell::new(format!("{:?}", file.status)),
+            Cell::new(format_score(file.old_score)),
+            Cell::new(format_score(file.new_score)),
+            Cell::new(format!("{:+.2}", file.score_delta)),
+        ]));
+    }
+    logger.info(&format!("{table}"));
+
+    display_dependency_changes(
+        "Added Dependencies",
+        tree_diff
+            .files
+            .iter()
+            .flat_map(|file| file.added_dependencies.iter().map(move |d| (file, d))),
+        logger,
+    );
+    display_dependency_changes(
+        "Removed Dependencies",
+        tree_diff
+            .files
+            .iter()
+            .flat_map(|file| file.removed_dependencies.iter().map(move |d| (file, d))),
+        logger,
+    );
+
+    let format_change = |old: Option<usize>, new: usize| match old {
+        Some(old) if old != new => format!("{old} -> {new}"),
+        Some(_) => new.to_string(),
+        None => format!("- -> {new}"),
+    };
+    let mut table = Table::new();
+    table.load_style(UTF8_FULL_CONDENSED);
+    table.set_header(vec!["File", "Line", "Depth", "Transitive Dependencies"]);
+    for file in &tree_diff.files {
+        for line in &file.worse_lines {
+            table.add_row(Row::from(vec![
+                Cell::new(&file.file_path),
+                Cell::new(line.line),
+                Cell::new(format_change(line.old_depth, line.new_depth)),
+                Cell::new(format_change(
+                    line.old_transitive_dependencies,
+                    line.new_transitive_dependencies,
+                )),
+            ]));
+        }
+    }
+    if !table.is_empty() {
+        logger.info("\n--- Lines With Increased Dependencies ---");
+        logger.info(&format!("{table}"));
+    }
+}
+
+/// Display the dependencies a tree diff found on one side only, if there are any
+fn display_dependency_changes<'a>(
+    title: &str,
+    changes: impl Iterator<Item = (&'a FileDiff, &'a DependencyChange)>,
+    logger: &dyn Logger,
+) {
+    let mut table = Table::new();
+    table.load_style(UTF8_FULL_CONDENSED);
+    table.set_header(vec!["File", "Line", "Depends On", "Symbol", "Type"]);
+    for (file, change) in changes {
+        table.add_row(Row::from(vec![
+            Cell::new(&file.file_path),
+            Cell::new(change.source_line),
+            Cell::new(change.target_line),
+            Cell::new(&change.symbol),
+            Cell::new(format!("{:?}", change.dependency_type)),
+        ]));
+    }
+    if !table.is_empty() {
+        logger.info(&format!("\n--- {title} ---"));
+        logger.info(&format!("{table}"));
+    }
+}
+
 /// Display the overall summary of the analysis
 pub fn display_overall_summary(overall_report: &OverallAnalysisReport, logger: &dyn Logger) {
     logger.info("\n--- Overall Report ---");
@@ -117,9 +530,22 @@ pub fn display_overall_summary(overall_report: &OverallAnalysisReport, logger: &
         "Average Complexity Score: {:.2}",
         overall_report.average_complexity_score
     ));
+
+    let weights = &overall_report.weights;
+    if *weights != ScoreWeights::default() {
+        logger.info(&format!(
+            "Score Weights: total dependencies {}, distance cost {}, depth {}, transitive {}, fan-in {}, blast radius {}",
+            weights.total_dependencies,
+            weights.dependency_distance_cost,
+            weights.depth,
+            weights.transitive_dependencies,
+            weights.fan_in,
+            weights.blast_radius
+        ));
+    }
 }
 
-fn format_file_path_for_display(file_path: &str, base_paths: &[String]) -> String {
+pub fn format_file_path_for_display(file_path: &str, base_paths: &[String]) -> String {
     let normalized_path = file_path.replace('\\', "/");
     let original_path = Path::new(&normalized_path);
 
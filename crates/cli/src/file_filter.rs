@@ -0,0 +1,167 @@
+//! Which files under a directory are analyzed.
+//!
+//! Walking everything under a path reaches installed packages, build output and generated code,
+//! which are not the code being measured and drown out what is. The rules are those of git: a
+//! `.gitignore` in the directory or any above it, and a `.lintricignore` in the same syntax for
+//! what git keeps but analysis should not. `--exclude` and `--include` globs take precedence over
+//! both, matching as lines of a `.gitignore` would. The globs of `lintric.toml` match as lines of a
+//! `.gitignore` beside it would, whichever directory is walked. All of it decides which paths are
+//! walked, so an ignored file is never read or parsed.
+
+use crate::config::Config;
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+/// The name of the ignore file read alongside `.gitignore`.
+pub const IGNORE_FILE_NAME: &str = ".lintricignore";
+
+/// The `--include` and `--exclude` globs given on the command line, and those of `lintric.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    /// When any are given, only files matching one of them are analyzed.
+    pub include: Vec<String>,
+    /// Files, and directories with everything under them, that are not analyzed.
+    pub exclude: Vec<String>,
+    /// The directory `lintric.toml` is in, which its globs are relative to.
+    config_dir: PathBuf,
+    config_include: Vec<String>,
+    config_exclude: Vec<String>,
+}
+
+impl FileFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self {
+            include,
+            exclude,
+            ..Default::default()
+        }
+    }
+
+    /// The filter with the globs of a config as well, relative to the directory it is in.
+    pub fn with_config(self, config: &Config) -> Self {
+        Self {
+            config_dir: config.dir.clone(),
+            config_include: config.include.clone(),
+            config_exclude: config.exclude.clone(),
+            ..self
+        }
+    }
+
+    /// The files under `root` that are not ignored, in path order.
+    ///
+    /// Command-line globs are matched against paths relative to `root`, as a `.gitignore` there
+    /// would be. A file an `--include` matches is analyzed even if an ignore file leaves it out, but
+    /// not if its directory is left out, as that is never walked. A file must match an include glob
+    /// of the config, if it has any, as well as one of `--include`.
+    pub fn walk(&self, root: &Path) -> Result<Vec<PathBuf>, String> {
+        self.walk_as(root, root)
+    }
+
+    /// The files under `root`, as `walk` gives them, with the config's globs matched as though
+    /// `root` were `origin`, as for a git revision extracted elsewhere.
+    pub fn walk_as(&self, root: &Path, origin: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut overrides = OverrideBuilder::new(root);
+        for glob in &self.include {
+            overrides
+                .add(glob)
+                .map_err(|e| format!("Invalid --include glob {glob}: {e}"))?;
+        }
+        for glob in &self.exclude {
+            overrides
+                .add(&format!("!{glob}"))
+                .map_err(|e| format!("Invalid --exclude glob {glob}: {e}"))?;
+        }
+        let overrides = overrides
+            .build()
+            .map_err(|e| format!("Invalid glob: {e}"))?;
+        let config_globs = self.config_globs(true)?;
+        let (root_path, origin) = (root.to_path_buf(), origin.to_path_buf());
+
+        let walker = WalkBuilder::new(root)
+            .hidden(false)
+            .require_git(false)
+            .add_custom_ignore_filename(IGNORE_FILE_NAME)
+            .overrides(overrides)
+            .filter_entry(move |entry| {
+                entry.file_name() != ".git"
+                    && !config_globs.as_ref().is_some_and(|(dir, globs)| {
+                        let path = entry.path().strip_prefix(&root_path).map_or_else(
+                            |_| entry.path().to_path_buf(),
+                            |relative| origin.join(relative),
+                        );
+                        let is_dir = entry
+                            .file_type()
+                            .is_some_and(|file_type| file_type.is_dir());
+                        ignores(dir, globs, &path, is_dir)
+                    })
+            })
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+
+        let mut files = Vec::new();
+        for entry in walker {
+            let entry = entry.map_err(|e| format!("Error reading directory entry: {e}"))?;
+            if entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
+                files.push(entry.into_path());
+            }
+        }
+        Ok(files)
+    }
+
+    /// Whether a file found some other way than by walking, as a crate's root, is left out.
+    pub fn excludes(&self, root: &Path, file: &Path) -> bool {
+        let mut overrides = OverrideBuilder::new(root);
+        for glob in &self.exclude {
+            if overrides.add(&format!("!{glob}")).is_err() {
+                return false;
+            }
+        }
+        let excluded_by_flags = overrides
+            .build()
+            .is_ok_and(|overrides| overrides.matched(file, false).is_ignore());
+        let excluded_by_config = self
+            .config_globs(false)
+            .ok()
+            .flatten()
+            .is_some_and(|(dir, globs)| ignores(&dir, &globs, file, false));
+        excluded_by_flags || excluded_by_config
+    }
+
+    /// The config's globs, rooted at its directory, the include globs with them if asked for.
+    fn config_globs(&self, with_include: bool) -> Result<Option<(PathBuf, Override)>, String> {
+        let include = if with_include {
+            self.config_include.as_slice()
+        } else {
+            &[]
+        };
+        if include.is_empty() && self.config_exclude.is_empty() {
+            return Ok(None);
+        }
+
+        let mut overrides = OverrideBuilder::new(&self.config_dir);
+        for glob in include {
+            overrides
+                .add(glob)
+                .map_err(|e| format!("Invalid include glob {glob} in the config: {e}"))?;
+        }
+        for glob in &self.config_exclude {
+            overrides
+                .add(&format!("!{glob}"))
+                .map_err(|e| format!("Invalid exclude glob {glob} in the config: {e}"))?;
+        }
+        let overrides = overrides
+            .build()
+            .map_err(|e| format!("Invalid glob in the config: {e}"))?;
+        Ok(Some((self.config_dir.clone(), overrides)))
+    }
+}
+
+/// Whether globs rooted at `dir` leave a path out; a path outside `dir` they say nothing about.
+fn ignores(dir: &Path, globs: &Override, path: &Path, is_dir: bool) -> bool {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    path.starts_with(dir) && globs.matched(&path, is_dir).is_ignore()
+}
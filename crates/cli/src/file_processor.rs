@@ -1,65 +1,262 @@
+use crate::cache::Cache;
+use crate::cargo_workspace::{self, TargetKind};
+use crate::changes::Changes;
+use crate::config::{Config, WeightSettings};
+use crate::file_filter::FileFilter;
 use crate::logger::Logger;
-use lintric_core::{analyze_code, AnalysisResult, Language};
+use crate::parallel;
+use crate::paths;
+use lintric_core::{
+    analyze_content, analyze_crate, analyze_workspace, remeasure, AnalysisResult, CrateAnalysis,
+    IntermediateRepresentation, Language, MeasureOptions, ScoreWeights,
+};
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// How paths are processed, the same for every path given
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    /// Which files under a directory are analyzed
+    pub filter: FileFilter,
+    /// How many files or crates are analyzed at once
+    pub jobs: usize,
+    /// Where analyses of single files are kept between runs, unless caching is off
+    pub cache: Option<Cache>,
+    /// The settings of `lintric.toml`, or the defaults without one
+    pub config: Config,
+    /// Score weights given on the command line, over those of the config
+    pub weights: WeightSettings,
+    /// How the metrics are measured, from the config and the command line
+    pub measure: MeasureOptions,
+    /// With `--changed-since`, what changed; files it did not touch are not analyzed
+    pub changes: Option<Changes>,
+}
+
+impl ProcessOptions {
+    /// The weights of files no override sets weights for, which the report records
+    pub fn report_weights(&self) -> ScoreWeights {
+        let mut weights = self.config.weights();
+        self.weights.apply(&mut weights);
+        weights
+    }
+
+    /// Whether a file is to be analyzed as changed, as every file is without `--changed-since`
+    pub fn is_changed(&self, file_path: &Path) -> bool {
+        self.changes
+            .as_ref()
+            .is_none_or(|changes| changes.touches(file_path))
+    }
+
+    /// Measure a result again as `measure` says, where that is not how it was measured; the
+    /// file's source is read from its path unless given
+    pub fn remeasure(
+        &self,
+        ir: &IntermediateRepresentation,
+        content: Option<&str>,
+        result: &mut AnalysisResult,
+    ) -> Result<(), String> {
+        if self.measure.is_default() {
+            return Ok(());
+        }
+        let path = Path::new(&result.file_path);
+        let language = self
+            .config
+            .language(path)
+            .ok_or_else(|| format!("Unsupported file type for analysis: {}", path.display()))?;
+        let read;
+        let content = match content {
+            Some(content) => content,
+            None => {
+                read = fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
+                &read
+            }
+        };
+        *result = remeasure(ir, content, language, result, &self.measure)?;
+        Ok(())
+    }
+
+    /// Measure and score the results of a crate's files, reporting and counting each of its files
+    /// that could not be analyzed
+    fn measure_all(
+        &self,
+        analyzed: CrateAnalysis,
+        logger: &dyn Logger,
+        failures: &mut usize,
+    ) -> Result<Vec<AnalysisResult>, String> {
+        for (file_path, e) in &analyzed.failed_files {
+            logger.error(&format!("Error processing file {file_path}: {e}"));
+            *failures += 1;
+        }
+        analyzed
+            .results
+            .into_iter()
+            .map(|(ir, mut result)| {
+                self.remeasure(&ir, None, &mut result)?;
+                self.score(&mut result);
+                Ok(result)
+            })
+            .collect()
+    }
+
+    /// Score a result with the weights for its file, noting them on the result where they are not
+    /// the report's
+    pub fn score(&self, result: &mut AnalysisResult) {
+        let mut weights = self.config.weights_for(Path::new(&result.file_path));
+        self.weights.apply(&mut weights);
+        if weights != ScoreWeights::default() {
+            result.rescore(&weights);
+        }
+        if weights != self.report_weights() {
+            result.weights = Some(weights);
+        }
+    }
+}
+
+/// The results of the files a path reached, and how many files, workspaces, crates or paths it
+/// reached could not be analyzed, each of which was reported as an error
+#[derive(Debug, Clone, Default)]
+pub struct Processed {
+    pub results: Vec<AnalysisResult>,
+    pub failures: usize,
+}
+
+impl Processed {
+    fn extend(&mut self, other: Processed) {
+        self.results.extend(other.results);
+        self.failures += other.failures;
+    }
+}
+
 /// Process a single file and return its analysis result
-pub fn process_file(file_path: &Path) -> Result<AnalysisResult, String> {
-    let (_, result) = analyze_code(file_path.to_string_lossy().into_owned())?;
+///
+/// The file is analyzed as the language configured for it and scored with the weights configured for
+/// it. Its analysis is taken from the cache if its content was analyzed before; a cache that cannot
+/// be written to only means the file is analyzed again next time.
+pub fn process_file(file_path: &Path, options: &ProcessOptions) -> Result<AnalysisResult, String> {
+    let path = file_path.to_string_lossy().into_owned();
+    let language = options
+        .config
+        .language(file_path)
+        .ok_or_else(|| format!("Unsupported file type for analysis: {path}"))?;
+    let content =
+        fs::read_to_string(file_path).map_err(|e| format!("Failed to read file {path}: {e}"))?;
+
+    let key = Cache::key(&content, &language);
+    let cached = options
+        .cache
+        .as_ref()
+        .and_then(|cache| cache.get(&key, &path));
+    let (ir, mut result) = match cached {
+        Some(cached) => cached,
+        None => {
+            let (mut ir, mut result) = analyze_content(content.clone(), language)?;
+            ir.file_path = path.clone();
+            result.file_path = path;
+            if let Some(cache) = &options.cache {
+                let _ = cache.put(&key, &ir, &result);
+            }
+            (ir, result)
+        }
+    };
+    options.remeasure(&ir, Some(&content), &mut result)?;
+    options.score(&mut result);
     Ok(result)
 }
 
-/// Process a directory recursively and return analysis results for all supported files
-pub fn process_directory(path: &Path, logger: &dyn Logger) -> Result<Vec<AnalysisResult>, String> {
-    let mut results = Vec::new();
-    for entry in fs::read_dir(path)
-        .map_err(|e| format!("Error reading directory {}: {}", path.display(), e))?
-    {
-        let entry = entry.map_err(|e| format!("Error reading directory entry: {e}"))?;
-        let entry_path = entry.path();
-
-        if entry_path.is_file() {
-            if Language::from_extension(&entry_path).is_some() {
-                match process_file(&entry_path) {
-                    Ok(result) => results.push(result),
-                    Err(e) => logger.error(&format!(
-                        "Error processing file {}: {}",
-                        entry_path.display(),
-                        e
-                    )),
-                }
-            }
-        } else if entry_path.is_dir() {
-            match process_directory(&entry_path, logger) {
-                Ok(mut sub_results) => results.append(&mut sub_results),
-                Err(e) => logger.error(&format!(
-                    "Error processing subdirectory {}: {}",
-                    entry_path.display(),
+/// Process a directory recursively and return analysis results for all supported files it does
+/// not ignore, in path order
+///
+/// Files are analyzed on up to `jobs` threads at once, and a file whose analysis is in the cache is
+/// not analyzed again.
+pub fn process_directory(
+    path: &Path,
+    options: &ProcessOptions,
+    logger: &dyn Logger,
+) -> Result<Processed, String> {
+    let file_paths: Vec<PathBuf> = options
+        .filter
+        .walk(path)?
+        .into_iter()
+        .filter(|file_path| options.config.language(file_path).is_some())
+        .filter(|file_path| options.is_changed(file_path))
+        .collect();
+    Ok(process_files(file_paths, options, logger))
+}
+
+/// Process the files under a Cargo package or workspace's directory that none of its crates
+/// reached, as `build.rs` or a web front end beside the Rust code, each as `process_directory`
+/// would
+pub fn process_outside_crates(
+    path: &Path,
+    crates: &[CrateResults],
+    options: &ProcessOptions,
+    logger: &dyn Logger,
+) -> Result<Processed, String> {
+    let reached: HashSet<PathBuf> = crates
+        .iter()
+        .flat_map(|krate| &krate.targets)
+        .flat_map(|target| &target.results)
+        .map(|result| paths::normalized(Path::new(&result.file_path)))
+        .collect();
+    let file_paths: Vec<PathBuf> = options
+        .filter
+        .walk(path)?
+        .into_iter()
+        .filter(|file_path| options.config.language(file_path).is_some())
+        .filter(|file_path| options.is_changed(file_path))
+        .filter(|file_path| !reached.contains(&paths::normalized(file_path)))
+        .collect();
+    Ok(process_files(file_paths, options, logger))
+}
+
+/// Process files one by one, in path order
+fn process_files(
+    mut file_paths: Vec<PathBuf>,
+    options: &ProcessOptions,
+    logger: &dyn Logger,
+) -> Processed {
+    // As the summary sorts them, which is not the walk's directory-first order.
+    file_paths.sort_by_key(|file_path| file_path.to_string_lossy().into_owned());
+    let processed = parallel::map(&file_paths, options.jobs, |file_path| {
+        process_file(file_path, options)
+    });
+
+    let mut directory = Processed::default();
+    for (file_path, result) in file_paths.iter().zip(processed) {
+        match result {
+            Ok(result) => directory.results.push(result),
+            Err(e) => {
+                logger.error(&format!(
+                    "Error processing file {}: {}",
+                    file_path.display(),
                     e
-                )),
+                ));
+                directory.failures += 1;
             }
         }
     }
-    Ok(results)
+    directory
 }
 
 /// Process a path (file or directory) and return analysis results
-pub fn process_path(path_str: &str, logger: &dyn Logger) -> (Vec<AnalysisResult>, f64, usize) {
-    let mut all_results: Vec<AnalysisResult> = Vec::new();
-    let mut total_overall_complexity_score = 0.0;
-    let mut total_files_analyzed = 0;
-
+pub fn process_path(path_str: &str, options: &ProcessOptions, logger: &dyn Logger) -> Processed {
+    let mut processed = Processed::default();
     let path = PathBuf::from(path_str);
 
+    if path.is_file() && !options.is_changed(&path) {
+        return processed;
+    }
+
     if path.is_file() {
-        if Language::from_extension(&path).is_some() {
-            match process_file(&path) {
-                Ok(result) => {
-                    all_results.push(result.clone());
-                    total_overall_complexity_score += result.overall_complexity_score;
-                    total_files_analyzed += 1;
+        if options.config.language(&path).is_some() {
+            match process_file(&path, options) {
+                Ok(result) => processed.results.push(result),
+                Err(e) => {
+                    logger.error(&format!("Error processing file {}: {}", path.display(), e));
+                    processed.failures += 1;
                 }
-                Err(e) => logger.error(&format!("Error processing file {}: {}", path.display(), e)),
             }
         } else {
             logger.warn(&format!(
@@ -68,30 +265,238 @@ pub fn process_path(path_str: &str, logger: &dyn Logger) -> (Vec<AnalysisResult>
             ));
         }
     } else if path.is_dir() {
-        match process_directory(&path, logger) {
-            Ok(results) => {
-                for result in results {
-                    all_results.push(result.clone());
-                    total_overall_complexity_score += result.overall_complexity_score;
-                    total_files_analyzed += 1;
-                }
+        match process_directory(&path, options, logger) {
+            Ok(directory) => processed.extend(directory),
+            Err(e) => {
+                logger.error(&format!(
+                    "Error processing directory {}: {}",
+                    path.display(),
+                    e
+                ));
+                processed.failures += 1;
             }
-            Err(e) => logger.error(&format!(
-                "Error processing directory {}: {}",
-                path.display(),
-                e
-            )),
         }
     } else {
         logger.error(&format!(
             "Error: Path {} is neither a file nor a directory.",
             path.display()
         ));
+        processed.failures += 1;
     }
 
-    (
-        all_results,
-        total_overall_complexity_score,
-        total_files_analyzed,
-    )
+    processed
+}
+
+/// Where a Rust crate rooted at a directory starts, in the order Cargo looks for them.
+const CRATE_ROOTS: [&str; 4] = ["src/lib.rs", "src/main.rs", "lib.rs", "main.rs"];
+
+/// Process a path as a project and return analysis results for every file it reaches
+///
+/// A Rust file is taken as a crate root, and a directory as the crate rooted in it. A directory
+/// without a crate root is taken as a TypeScript workspace of the files under it. A path that is
+/// none of these is processed file by file, as without `--project`.
+pub fn process_project(path_str: &str, options: &ProcessOptions, logger: &dyn Logger) -> Processed {
+    let path = PathBuf::from(path_str);
+    let crate_roots: Vec<PathBuf> = if path.is_file() {
+        match Language::from_extension(&path) {
+            Some(Language::Rust) => vec![path.clone()],
+            _ => vec![],
+        }
+    } else {
+        CRATE_ROOTS
+            .iter()
+            .map(|root| path.join(root))
+            .filter(|root| root.is_file() && !options.filter.excludes(&path, root))
+            .collect()
+    };
+
+    let mut processed = Processed::default();
+    if crate_roots.is_empty() {
+        let workspace_files = if path.is_dir() {
+            match typescript_files(&path, &options.filter) {
+                Ok(files) => files,
+                Err(e) => {
+                    logger.error(&format!(
+                        "Error processing directory {}: {}",
+                        path.display(),
+                        e
+                    ));
+                    processed.failures += 1;
+                    vec![]
+                }
+            }
+        } else {
+            vec![]
+        };
+        if workspace_files.is_empty() && processed.failures == 0 {
+            return process_path(path_str, options, logger);
+        }
+
+        match analyze_workspace(workspace_files) {
+            Ok(analyzed) => {
+                for (config_file, e) in &analyzed.config_errors {
+                    logger.warn(&format!(
+                        "Warning: Could not read {config_file}, resolving the files below it \
+                         without its paths: {e}"
+                    ));
+                }
+                for (file_path, e) in &analyzed.failed_files {
+                    logger.error(&format!("Error processing file {file_path}: {e}"));
+                    processed.failures += 1;
+                }
+                for (ir, mut result) in analyzed.results {
+                    for unresolved in &ir.unresolved_modules {
+                        logger.warn(&format!(
+                            "Warning: Unresolved module '{}' imported at {}:{}",
+                            unresolved.specifier, ir.file_path, unresolved.line
+                        ));
+                    }
+                    match options.remeasure(&ir, None, &mut result) {
+                        Ok(()) => {
+                            options.score(&mut result);
+                            processed.results.push(result);
+                        }
+                        Err(e) => {
+                            logger.error(&format!("Error processing file {}: {}", ir.file_path, e));
+                            processed.failures += 1;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                logger.error(&format!(
+                    "Error processing workspace {}: {}",
+                    path.display(),
+                    e
+                ));
+                processed.failures += 1;
+            }
+        }
+    }
+
+    let analyzed_crates = parallel::map(&crate_roots, options.jobs, |crate_root| {
+        analyze_crate(crate_root.to_string_lossy().into_owned())
+    });
+    for (crate_root, analyzed) in crate_roots.iter().zip(analyzed_crates) {
+        match analyzed
+            .and_then(|analyzed| options.measure_all(analyzed, logger, &mut processed.failures))
+        {
+            Ok(results) => processed.results.extend(results),
+            Err(e) => {
+                logger.error(&format!(
+                    "Error processing crate {}: {}",
+                    crate_root.display(),
+                    e
+                ));
+                processed.failures += 1;
+            }
+        }
+    }
+
+    processed
+}
+
+/// The TypeScript files under a directory that are not ignored, leaving out installed packages.
+fn typescript_files(dir: &Path, filter: &FileFilter) -> Result<Vec<String>, String> {
+    Ok(filter
+        .walk(dir)?
+        .into_iter()
+        .filter(|path| {
+            !path
+                .components()
+                .any(|part| part.as_os_str() == "node_modules")
+        })
+        .filter(|path| {
+            matches!(
+                Language::from_extension(path),
+                Some(Language::TypeScript | Language::TSX)
+            )
+        })
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// The results of one Cargo package, grouped by the target whose crate reached each file.
+#[derive(Debug, Clone)]
+pub struct CrateResults {
+    pub name: String,
+    pub targets: Vec<TargetResults>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TargetResults {
+    pub kind: TargetKind,
+    pub name: String,
+    pub root: PathBuf,
+    pub results: Vec<AnalysisResult>,
+}
+
+/// Process every package of a Cargo manifest's workspace, each target as the crate it builds
+///
+/// A file two targets both reach, as a module shared by a binary and its tests can be, is in the
+/// results of each. A target whose root file `--exclude` matches is left out, relative to the
+/// manifest's directory; a crate follows its `mod` declarations, not the directory tree, so
+/// ignore files do not apply. How many targets, or the manifest itself, could not be analyzed is
+/// given alongside.
+pub fn process_cargo(
+    manifest_path: &Path,
+    options: &ProcessOptions,
+    logger: &dyn Logger,
+) -> (Vec<CrateResults>, usize) {
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let packages = match cargo_workspace::discover(manifest_path) {
+        Ok(packages) => packages,
+        Err(e) => {
+            logger.error(&format!(
+                "Error processing manifest {}: {}",
+                manifest_path.display(),
+                e
+            ));
+            return (vec![], 1);
+        }
+    };
+
+    // Every target of every package is one crate, and they are analyzed at once.
+    let targets: Vec<(usize, cargo_workspace::Target)> = packages
+        .iter()
+        .enumerate()
+        .flat_map(|(package, found)| found.targets.iter().map(move |target| (package, target)))
+        .filter(|(_, target)| !options.filter.excludes(manifest_dir, &target.root))
+        .map(|(package, target)| (package, target.clone()))
+        .collect();
+    let analyzed = parallel::map(&targets, options.jobs, |(_, target)| {
+        analyze_crate(target.root.to_string_lossy().into_owned())
+    });
+
+    let mut crates: Vec<CrateResults> = packages
+        .iter()
+        .map(|package| CrateResults {
+            name: package.name.clone(),
+            targets: vec![],
+        })
+        .collect();
+    let mut failures = 0;
+    for ((package, target), analyzed) in targets.into_iter().zip(analyzed) {
+        let results = match analyzed
+            .and_then(|analyzed| options.measure_all(analyzed, logger, &mut failures))
+        {
+            Ok(results) => results,
+            Err(e) => {
+                logger.error(&format!(
+                    "Error processing crate {}: {}",
+                    target.root.display(),
+                    e
+                ));
+                failures += 1;
+                vec![]
+            }
+        };
+        crates[package].targets.push(TargetResults {
+            kind: target.kind,
+            name: target.name,
+            root: target.root,
+            results,
+        });
+    }
+    (crates, failures)
 }
@@ -1,3 +1,4 @@
+use crate::display::most_complex_item;
 use crate::logger::Logger;
 use lintric_core::models::{AnalysisResult, OverallAnalysisReport};
 use std::fs;
@@ -32,6 +33,7 @@ pub fn generate_html_report(report: &OverallAnalysisReport, logger: &dyn Logger)
     let mut index_context = Context::new();
     index_context.insert("total_files_analyzed", &report.total_files_analyzed);
     index_context.insert("average_complexity_score", &report.average_complexity_score);
+    index_context.insert("weights", &report.weights);
 
     let mut results_for_template: Vec<serde_json::Value> = Vec::new();
 
@@ -41,6 +43,7 @@ pub fn generate_html_report(report: &OverallAnalysisReport, logger: &dyn Logger)
         // Prepare data for index template
         let mut file_data = serde_json::to_value(result).unwrap();
         file_data["html_file_name"] = serde_json::to_value(html_file_name.clone()).unwrap();
+        file_data["most_complex_item"] = serde_json::to_value(most_complex_item(result)).unwrap();
         results_for_template.push(file_data);
 
         // Generate individual file HTML
@@ -185,12 +188,16 @@ fn generate_file_html(
             "code": highlighted_code_line,
             "metrics_str": metrics_str,
             "dependent_lines": line_metrics.map_or(vec![], |m| m.dependent_lines.clone()),
+            "fan_in": line_metrics.map_or(0, |m| m.fan_in),
+            "blast_radius": line_metrics.map_or(0, |m| m.blast_radius),
         }));
     }
 
     let mut file_context = Context::new();
     file_context.insert("file_path", &result.file_path);
     file_context.insert("overall_complexity_score", &result.overall_complexity_score);
+    file_context.insert("dependency_types", &result.dependency_types);
+    file_context.insert("item_metrics", &result.item_metrics);
     file_context.insert("code_lines", &code_lines_for_template);
     file_context.insert("language_extension", &file_extension);
     file_context.insert("highlight_css", &css);
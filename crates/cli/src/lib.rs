@@ -1,11 +1,28 @@
 use crate::logger::Logger;
 use clap::{ArgAction, Parser, Subcommand};
+use lintric_core::graph_export::{export_graph, GraphFormat, GraphKind};
+use std::collections::HashSet;
 use std::ffi::OsString;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 
+mod baseline;
+mod cache;
+mod cargo_workspace;
+mod changes;
+mod config;
 mod display;
+mod file_filter;
 mod file_processor;
 mod html_output;
 pub mod logger;
+pub mod lsp;
+mod parallel;
+mod paths;
+mod sarif_output;
+mod thresholds;
+mod tree_diff;
+pub mod watch;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -13,7 +30,8 @@ struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Paths to the source code files or directories to analyze
+    /// Paths to the source code files or directories to analyze. A `Cargo.toml`, or a directory
+    /// holding one, is analyzed crate by crate
     #[arg(required = false, action = ArgAction::Append)]
     paths: Vec<String>,
 
@@ -28,6 +46,76 @@ struct Args {
     /// Output in HTML format
     #[arg(long, default_value_t = false)]
     html: bool,
+
+    /// Output threshold violations as a SARIF 2.1.0 log, for code scanning tools
+    #[arg(long, default_value_t = false)]
+    sarif: bool,
+
+    /// Analyze each path as a project, following imports into the files that declare them
+    #[arg(long, default_value_t = false)]
+    project: bool,
+
+    /// Leave out files and directories matching a gitignore-style glob (repeatable)
+    #[arg(long, value_name = "GLOB", action = ArgAction::Append)]
+    exclude: Vec<String>,
+
+    /// Analyze only files matching a gitignore-style glob (repeatable)
+    #[arg(long, value_name = "GLOB", action = ArgAction::Append)]
+    include: Vec<String>,
+
+    /// Analyze up to N files at once [default: the number of available cores]
+    #[arg(long, short = 'j', value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+
+    /// Where to keep analyses of unchanged files between runs [default: .lintric/cache in the
+    /// directory of lintric.toml, or without one in the first path]
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<String>,
+
+    /// Analyze every file again, neither reading nor writing the cache
+    #[arg(long, default_value_t = false)]
+    no_cache: bool,
+
+    /// Weigh a metric in the overall complexity score, as `depth=2.0` (repeatable)
+    #[arg(long = "weight", value_name = "METRIC=WEIGHT", action = ArgAction::Append)]
+    weights: Vec<String>,
+
+    /// Leave a kind of dependency, as `TypeReference`, out of the metrics and scores (repeatable)
+    #[arg(long = "exclude-dependency-type", value_name = "TYPE", action = ArgAction::Append)]
+    exclude_dependency_types: Vec<String>,
+
+    /// Measure each line, or each statement against its first line, so that formatting does not
+    /// change the metrics [default: line]
+    #[arg(long, value_name = "GRANULARITY", value_parser = ["line", "statement"])]
+    granularity: Option<String>,
+
+    /// Fail when a metric goes past a limit, as `depth=5` (repeatable)
+    #[arg(long = "threshold", value_name = "METRIC=LIMIT", action = ArgAction::Append)]
+    thresholds: Vec<String>,
+
+    /// Fail only on threshold violations worse than those recorded in this baseline file
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<String>,
+
+    /// Record the metrics of this run as a baseline, in FILE if given as `--write-baseline=FILE`
+    /// [default file: lintric-baseline.json]
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = baseline::DEFAULT_BASELINE_FILE
+    )]
+    write_baseline: Option<String>,
+
+    /// Analyze only files changed since a git revision, reporting the lines changed and the lines
+    /// depending on them; `-` reads a unified diff from standard input instead
+    #[arg(long, value_name = "REV")]
+    changed_since: Option<String>,
+
+    /// Read settings from this file rather than the nearest `lintric.toml`
+    #[arg(long, value_name = "FILE")]
+    config: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -37,6 +125,66 @@ enum Commands {
         #[command(subcommand)]
         command: DebugCommands,
     },
+    /// Outputs the dependency graph of a file as Graphviz DOT or Mermaid
+    Graph {
+        /// Path to the source code file to draw
+        #[arg(required = true)]
+        path: String,
+        /// The format to write the graph in
+        #[arg(long, default_value = "dot", value_parser = ["dot", "mermaid"])]
+        format: String,
+        /// Draw an edge for each symbol a line uses, labelled and coloured by the kind of dependency
+        #[arg(long, default_value_t = false)]
+        symbols: bool,
+        /// Read settings from FILE instead of the nearest lintric.toml
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+    },
+    /// Lists the items of a file, such as functions and structs, and the items each depends on
+    Definitions {
+        /// Path to the source code file to analyze
+        #[arg(required = true)]
+        path: String,
+        /// Output the items and their dependencies in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Read settings from FILE instead of the nearest lintric.toml
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+    },
+    /// Compares the metrics of two directories or git revisions
+    Diff {
+        /// The directory or git revision to compare against
+        #[arg(required = true)]
+        old: String,
+        /// The directory or git revision to compare
+        #[arg(required = true)]
+        new: String,
+        /// Output the differences in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Read settings from FILE instead of the nearest lintric.toml
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+    },
+    /// Runs a language server over stdio, for metrics and diagnostics in an editor
+    Lsp {
+        /// Read settings from FILE instead of the nearest lintric.toml
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+    },
+    /// Watches files and directories, reporting again whenever a file under them changes
+    Watch {
+        /// Paths to the source code files or directories to watch
+        #[arg(required = true, action = ArgAction::Append)]
+        paths: Vec<String>,
+        /// Regenerate the HTML report in place after every change
+        #[arg(long)]
+        html: bool,
+        /// Read settings from FILE instead of the nearest lintric.toml
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -56,14 +204,21 @@ enum DebugCommands {
     },
 }
 
-pub fn run() {
+/// The exit code of a run that found nothing wrong.
+pub const EXIT_SUCCESS: i32 = 0;
+/// The exit code of a run whose report goes past a threshold.
+pub const EXIT_THRESHOLD_EXCEEDED: i32 = 1;
+/// The exit code of a run that could not analyze what it was asked to.
+pub const EXIT_ERROR: i32 = 2;
+
+pub fn run() -> i32 {
     let logger = logger::StdIoLogger;
-    run_from_iter(std::env::args_os(), &logger);
+    run_from_iter(std::env::args_os(), &logger)
 }
 
-/// Execute CLI with provided arguments and logger.
+/// Execute CLI with provided arguments and logger, returning the exit code.
 /// The first arg should be the binary name (e.g., "lintric-cli").
-pub fn run_from_iter<I, T>(args: I, logger: &dyn Logger)
+pub fn run_from_iter<I, T>(args: I, logger: &dyn Logger) -> i32
 where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
@@ -75,9 +230,11 @@ where
             DebugCommands::Ast { path } => match lintric_core::get_s_expression(path) {
                 Ok(s_expr_output) => {
                     logger.info(&s_expr_output);
+                    EXIT_SUCCESS
                 }
                 Err(e) => {
                     logger.error(&format!("Error: {e}"));
+                    EXIT_ERROR
                 }
             },
             DebugCommands::IntermediateRepresentation { path } => {
@@ -87,45 +244,457 @@ where
                             &serde_json::to_string_pretty(&ir)
                                 .expect("Failed to serialize IR to JSON"),
                         );
+                        EXIT_SUCCESS
                     }
                     Err(e) => {
                         logger.error(&format!("Error: {e}"));
+                        EXIT_ERROR
                     }
                 }
             }
         },
-        None => {
-            let mut all_results: Vec<lintric_core::AnalysisResult> = Vec::new();
-            let mut total_overall_complexity_score = 0.0;
-            let mut total_files_analyzed = 0;
-
-            for path_str in &args.paths {
-                let (results, score, count) = file_processor::process_path(path_str, logger);
-                all_results.extend(results);
-                total_overall_complexity_score += score;
-                total_files_analyzed += count;
+        Some(Commands::Graph {
+            path,
+            format,
+            symbols,
+            config,
+        }) => graph(&path, &format, symbols, config.as_deref(), logger),
+        Some(Commands::Definitions { path, json, config }) => {
+            definitions(&path, json, config.as_deref(), logger)
+        }
+        Some(Commands::Diff {
+            old,
+            new,
+            json,
+            config,
+        }) => compare(&old, &new, json, config.as_deref(), logger),
+        Some(Commands::Lsp { config }) => language_server(config.as_deref(), logger),
+        Some(Commands::Watch {
+            paths,
+            html,
+            config,
+        }) => watch_paths(&paths, html, config.as_deref(), logger),
+        None => analyze(&args, logger),
+    }
+}
+
+/// Write the dependency graph of a file.
+fn graph(
+    path: &str,
+    format: &str,
+    symbols: bool,
+    config_path: Option<&str>,
+    logger: &dyn Logger,
+) -> i32 {
+    let format = match format {
+        "mermaid" => GraphFormat::Mermaid,
+        _ => GraphFormat::Dot,
+    };
+    let kind = if symbols {
+        GraphKind::Symbols
+    } else {
+        GraphKind::Lines
+    };
+    let drawn = load_config(config_path).and_then(|config| {
+        let language = config
+            .language(Path::new(path))
+            .ok_or_else(|| format!("Unsupported file type for analysis: {path}"))?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file {path}: {e}"))?;
+        let (ir, _) = lintric_core::analyze_content(content.clone(), language)?;
+        Ok(export_graph(&ir, &content, kind, format))
+    });
+
+    match drawn {
+        Ok(drawn) => {
+            logger.info(drawn.trim_end());
+            EXIT_SUCCESS
+        }
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Report the items of a file and how they depend on each other.
+fn definitions(path: &str, json: bool, config_path: Option<&str>, logger: &dyn Logger) -> i32 {
+    let definition_graph = load_config(config_path).and_then(|config| {
+        let language = config
+            .language(Path::new(path))
+            .ok_or_else(|| format!("Unsupported file type for analysis: {path}"))?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file {path}: {e}"))?;
+        let mut definition_graph =
+            lintric_core::get_definition_graph_from_content(content, language)?;
+        definition_graph.file_path = path.to_string();
+        Ok(definition_graph)
+    });
+
+    match definition_graph {
+        Ok(definition_graph) if json => {
+            display::display_definition_graph_json(&definition_graph, logger)
+        }
+        Ok(definition_graph) => display::display_definition_graph(&definition_graph, logger),
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    }
+    EXIT_SUCCESS
+}
+
+/// Serve an editor over stdio until it shuts the server down.
+fn language_server(config_path: Option<&str>, logger: &dyn Logger) -> i32 {
+    let (connection, io_threads) = lsp_server::Connection::stdio();
+    let served = lsp::serve(&connection, config_path);
+    drop(connection);
+    let joined = io_threads.join().map_err(|e| e.to_string());
+
+    match served.and(joined) {
+        Ok(()) => EXIT_SUCCESS,
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Report on the paths again whenever a file under them changes, until interrupted.
+fn watch_paths(
+    paths: &[String],
+    html: bool,
+    config_path: Option<&str>,
+    logger: &dyn Logger,
+) -> i32 {
+    let watched = watch::changes(paths)
+        .and_then(|(_watcher, changes)| watch::watch(paths, html, config_path, &changes, logger));
+
+    match watched {
+        Ok(()) => EXIT_SUCCESS,
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Report how the metrics of two trees differ.
+fn compare(
+    old: &str,
+    new: &str,
+    json: bool,
+    config_path: Option<&str>,
+    logger: &dyn Logger,
+) -> i32 {
+    let config = match load_config(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    };
+    let options = file_processor::ProcessOptions {
+        filter: file_filter::FileFilter::default().with_config(&config),
+        jobs: parallel::default_jobs(),
+        measure: config.measure_options(),
+        config,
+        ..Default::default()
+    };
+
+    let tree_diff = match tree_diff::diff(old, new, &options, logger) {
+        Ok(tree_diff) => tree_diff,
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    };
+    if json {
+        display::display_tree_diff_json(&tree_diff, logger);
+    } else {
+        display::display_tree_diff(&tree_diff, logger);
+    }
+
+    // A file that could not be analyzed on one side cannot be said to have changed or not.
+    let failures = tree_diff.failed_files.len();
+    if failures > 0 {
+        logger.error(&format!(
+            "Error: {} file{} could not be analyzed",
+            failures,
+            if failures == 1 { "" } else { "s" }
+        ));
+        return EXIT_ERROR;
+    }
+    EXIT_SUCCESS
+}
+
+/// Analyze the paths given and report on them as asked.
+fn analyze(args: &Args, logger: &dyn Logger) -> i32 {
+    let mut all_results: Vec<lintric_core::AnalysisResult> = Vec::new();
+    let mut total_overall_complexity_score = 0.0;
+    let mut total_files_analyzed = 0;
+    let mut failures = 0;
+    let mut crates: Vec<file_processor::CrateResults> = Vec::new();
+    let config = match load_config(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    };
+    let weights = match config::WeightSettings::from_flags(&args.weights) {
+        Ok(weights) => weights,
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    };
+    let mut measure = config.measure_options();
+    match args
+        .exclude_dependency_types
+        .iter()
+        .map(|name| {
+            name.parse::<lintric_core::DependencyType>()
+                .map_err(|e| format!("Invalid --exclude-dependency-type {name}: {e}"))
+        })
+        .collect::<Result<Vec<_>, String>>()
+    {
+        Ok(types) => measure.excluded_dependency_types.extend(types),
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    }
+    match args.granularity.as_deref() {
+        Some("statement") => measure.granularity = lintric_core::Granularity::Statement,
+        Some(_) => measure.granularity = lintric_core::Granularity::Line,
+        None => {}
+    }
+    let flag_thresholds = match thresholds::Thresholds::from_flags(&args.thresholds) {
+        Ok(thresholds) => thresholds,
+        Err(e) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    };
+    let changes = match args.changed_since.as_deref().map(changes::Changes::since) {
+        Some(Ok(changes)) => Some(changes),
+        Some(Err(e)) => {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+        None => None,
+    };
+    let format = if args.json {
+        config::OutputFormat::Json
+    } else if args.verbose {
+        config::OutputFormat::Verbose
+    } else if args.html {
+        config::OutputFormat::Html
+    } else if args.sarif {
+        config::OutputFormat::Sarif
+    } else {
+        config.format.unwrap_or(config::OutputFormat::Summary)
+    };
+    let options = file_processor::ProcessOptions {
+        filter: file_filter::FileFilter::new(args.include.clone(), args.exclude.clone())
+            .with_config(&config),
+        jobs: args
+            .jobs
+            .map_or_else(parallel::default_jobs, NonZeroUsize::get),
+        cache: (!args.no_cache).then(|| match &args.cache_dir {
+            Some(cache_dir) => cache::Cache::new(cache_dir),
+            None => cache::Cache::for_project(&config.dir, &args.paths),
+        }),
+        config,
+        weights,
+        measure,
+        changes,
+    };
+
+    for path_str in &args.paths {
+        if let Some(manifest) = cargo_workspace::manifest_for(Path::new(path_str)) {
+            let (processed, failed) = file_processor::process_cargo(&manifest, &options, logger);
+            failures += failed;
+            for result in unique_results(&processed) {
+                total_overall_complexity_score += result.overall_complexity_score;
+                total_files_analyzed += 1;
+                all_results.push(result);
+            }
+            // Given as a directory, what its crates do not reach is analyzed as well, file by file.
+            let path = Path::new(path_str);
+            if path.is_dir() {
+                match file_processor::process_outside_crates(path, &processed, &options, logger) {
+                    Ok(outside) => {
+                        total_overall_complexity_score += outside
+                            .results
+                            .iter()
+                            .map(|result| result.overall_complexity_score)
+                            .sum::<f64>();
+                        total_files_analyzed += outside.results.len();
+                        all_results.extend(outside.results);
+                        failures += outside.failures;
+                    }
+                    Err(e) => {
+                        logger.error(&format!(
+                            "Error processing directory {}: {}",
+                            path.display(),
+                            e
+                        ));
+                        failures += 1;
+                    }
+                }
             }
+            crates.extend(processed);
+            continue;
+        }
+
+        let processed = if args.project {
+            file_processor::process_project(path_str, &options, logger)
+        } else {
+            file_processor::process_path(path_str, &options, logger)
+        };
+        total_overall_complexity_score += processed
+            .results
+            .iter()
+            .map(|result| result.overall_complexity_score)
+            .sum::<f64>();
+        total_files_analyzed += processed.results.len();
+        all_results.extend(processed.results);
+        failures += processed.failures;
+    }
 
-            let overall_report = lintric_core::models::OverallAnalysisReport {
-                results: all_results,
-                total_files_analyzed,
-                total_overall_complexity_score,
-                average_complexity_score: if total_files_analyzed > 0 {
-                    total_overall_complexity_score / total_files_analyzed as f64
-                } else {
-                    0.0
-                },
-            };
-
-            if args.json {
-                display::display_json(&overall_report, &args.paths, logger);
-            } else if args.verbose {
-                display::display_verbose(&overall_report, &args.paths, logger);
-            } else if args.html {
-                html_output::generate_html_report(&overall_report, logger);
-            } else {
-                display::display_summary(&overall_report, &args.paths, logger);
+    // Whole crates and workspaces are analyzed, as changed files depend on the rest; only what
+    // changed is reported.
+    if let Some(changes) = &options.changes {
+        all_results = all_results
+            .into_iter()
+            .filter_map(|result| changes.restrict(result))
+            .collect();
+        for target in crates.iter_mut().flat_map(|krate| &mut krate.targets) {
+            target.results = std::mem::take(&mut target.results)
+                .into_iter()
+                .filter_map(|result| changes.restrict(result))
+                .collect();
+        }
+        total_overall_complexity_score = all_results
+            .iter()
+            .map(|result| result.overall_complexity_score)
+            .sum();
+        total_files_analyzed = all_results.len();
+    }
+
+    let overall_report = lintric_core::models::OverallAnalysisReport {
+        results: all_results,
+        total_files_analyzed,
+        total_overall_complexity_score,
+        average_complexity_score: if total_files_analyzed > 0 {
+            total_overall_complexity_score / total_files_analyzed as f64
+        } else {
+            0.0
+        },
+        weights: options.report_weights(),
+    };
+
+    let recorded = match &args.baseline {
+        Some(baseline_path) => match baseline::Baseline::load(Path::new(baseline_path)) {
+            Ok(recorded) => Some(recorded),
+            Err(e) => {
+                logger.error(&format!("Error: {e}"));
+                return EXIT_ERROR;
             }
+        },
+        None => None,
+    };
+    let mut violations = thresholds::check(
+        &overall_report,
+        &options.config.thresholds().overridden_by(&flag_thresholds),
+        |file_path| {
+            options
+                .config
+                .thresholds_for(Path::new(file_path))
+                .overridden_by(&flag_thresholds)
+        },
+    );
+    if let Some(recorded) = &recorded {
+        violations = recorded.new_violations(violations);
+    }
+    if let Some(baseline_path) = &args.write_baseline {
+        if let Err(e) = baseline::Baseline::from_report(&overall_report, Path::new(baseline_path))
+            .save(Path::new(baseline_path))
+        {
+            logger.error(&format!("Error: {e}"));
+            return EXIT_ERROR;
+        }
+    }
+
+    match format {
+        config::OutputFormat::Json => {
+            display::display_json(&overall_report, &crates, &violations, &args.paths, logger);
+        }
+        config::OutputFormat::Verbose => {
+            display::display_verbose(&overall_report, &args.paths, logger);
+            display::display_crates(&crates, &args.paths, logger);
+        }
+        config::OutputFormat::Html => {
+            html_output::generate_html_report(&overall_report, logger);
+        }
+        config::OutputFormat::Sarif => {
+            sarif_output::generate_sarif_report(&overall_report, &violations, logger);
         }
+        config::OutputFormat::Summary => {
+            display::display_summary(&overall_report, &args.paths, logger);
+            display::display_crates(&crates, &args.paths, logger);
+        }
+    }
+    if !matches!(
+        format,
+        config::OutputFormat::Json | config::OutputFormat::Sarif
+    ) {
+        display::display_violations(&violations, &args.paths, logger);
     }
+
+    // A path that could not be analyzed cannot be said to be within its thresholds.
+    if failures > 0 {
+        logger.error(&format!(
+            "Error: {} file{} or path{} could not be analyzed",
+            failures,
+            if failures == 1 { "" } else { "s" },
+            if failures == 1 { "" } else { "s" }
+        ));
+        return EXIT_ERROR;
+    }
+    if violations.is_empty() {
+        return EXIT_SUCCESS;
+    }
+    logger.error(&format!(
+        "Error: {} metric{} over threshold",
+        violations.len(),
+        if violations.len() == 1 { "" } else { "s" }
+    ));
+    EXIT_THRESHOLD_EXCEEDED
+}
+
+/// The configuration `--config` names, or else the nearest `lintric.toml` above the working
+/// directory, or else the defaults.
+fn load_config(config_path: Option<&str>) -> Result<config::Config, String> {
+    let config_path = match config_path {
+        Some(config_path) => Some(PathBuf::from(config_path)),
+        None => std::env::current_dir()
+            .ok()
+            .and_then(|dir| config::find(&dir)),
+    };
+    config_path.map_or_else(|| Ok(config::Config::default()), |path| config::load(&path))
+}
+
+/// Each file the crates reached once, however many targets reached it.
+fn unique_results(crates: &[file_processor::CrateResults]) -> Vec<lintric_core::AnalysisResult> {
+    let mut seen = HashSet::new();
+    crates
+        .iter()
+        .flat_map(|krate| &krate.targets)
+        .flat_map(|target| &target.results)
+        .filter(|result| seen.insert(result.file_path.clone()))
+        .cloned()
+        .collect()
 }
@@ -0,0 +1,570 @@
+//! A language server, so that an editor shows a file's metrics while it is being edited.
+//!
+//! Each open Rust or TypeScript document is analyzed from its buffer whenever it changes, saved or
+//! not, with the settings of `lintric.toml` a run over the file would use. The editor sends the
+//! changes rather than the whole text, so that an edit inside a function is analyzed there alone by
+//! the document's `AnalysisSession`. Its threshold violations are published as diagnostics, and its
+//! line metrics shown on hover and as inlay hints at the end of each line. The resolved edges of
+//! its IR answer "go to definition", with what a line depends on, and "find references", with what
+//! depends on it.
+
+use crate::file_processor::ProcessOptions;
+use crate::thresholds::{self, Thresholds, Violation};
+use lintric_core::models::{Dependency, OverallAnalysisReport, Position as Span};
+use lintric_core::{
+    AnalysisResult, AnalysisSession, IntermediateRepresentation, LineMetrics, TextEdit,
+};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, GotoDefinitionParams, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, InlayHint, InlayHintLabel, InlayHintParams, Location, MarkupContent,
+    MarkupKind, NumberOrString, OneOf, Position, PublishDiagnosticsParams, Range, ReferenceParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// An open document and its analysis.
+struct Document {
+    path: PathBuf,
+    /// The text as the editor has it, which every change is applied to whether or not it can be
+    /// analyzed.
+    text: String,
+    /// The analysis of the last text that could be analyzed.
+    session: AnalysisSession,
+    /// The session's IR and metrics, measured and scored as the settings say.
+    ir: IntermediateRepresentation,
+    result: AnalysisResult,
+}
+
+impl Document {
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether the session has analyzed the text as it is, rather than as it was before a change
+    /// that could not be analyzed.
+    fn is_current(&self) -> bool {
+        self.session.content() == self.text
+    }
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    options: ProcessOptions,
+    /// The documents of a language lintric analyzes, by URI.
+    documents: HashMap<Url, Document>,
+}
+
+/// Serve a client over a connection until it shuts the server down, with the settings of the
+/// config at `config_path` or the nearest `lintric.toml`.
+pub fn serve(connection: &Connection, config_path: Option<&str>) -> Result<(), String> {
+    let config = crate::load_config(config_path)?;
+    let options = ProcessOptions {
+        measure: config.measure_options(),
+        config,
+        ..Default::default()
+    };
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        inlay_hint_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection
+        .initialize(serde_json::to_value(capabilities).map_err(|e| e.to_string())?)
+        .map_err(|e| format!("Failed to initialize: {e}"))?;
+
+    let mut server = Server {
+        connection,
+        options,
+        documents: HashMap::new(),
+    };
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection
+                    .handle_shutdown(&request)
+                    .map_err(|e| e.to_string())?
+                {
+                    return Ok(());
+                }
+                server.respond(request)?;
+            }
+            Message::Notification(notification) => server.notify(notification)?,
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+impl Server<'_> {
+    fn respond(&self, request: Request) -> Result<(), String> {
+        let id = request.id.clone();
+        let response = match request.method.as_str() {
+            "textDocument/hover" => params(request)
+                .map(|params: HoverParams| self.hover(params))
+                .and_then(|hover| ok(id, hover)),
+            "textDocument/inlayHint" => params(request)
+                .map(|params: InlayHintParams| self.inlay_hints(params))
+                .and_then(|hints| ok(id, hints)),
+            "textDocument/definition" => params(request)
+                .map(|params: GotoDefinitionParams| self.dependencies(params))
+                .and_then(|locations| ok(id, locations)),
+            "textDocument/references" => params(request)
+                .map(|params: ReferenceParams| self.dependents(params))
+                .and_then(|locations| ok(id, locations)),
+            method => Ok(Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request {method}"),
+            )),
+        }
+        .unwrap_or_else(|(id, e)| Response::new_err(id, ErrorCode::InvalidParams as i32, e));
+        self.send(Message::Response(response))
+    }
+
+    fn notify(&mut self, notification: Notification) -> Result<(), String> {
+        match notification.method.as_str() {
+            "textDocument/didOpen" => {
+                let params: DidOpenTextDocumentParams = notification_params(notification)?;
+                let uri = params.text_document.uri;
+                self.open(&uri, params.text_document.text)?;
+                self.measure(&uri)
+            }
+            "textDocument/didChange" => {
+                let params: DidChangeTextDocumentParams = notification_params(notification)?;
+                let uri = params.text_document.uri;
+                for change in params.content_changes {
+                    match change.range {
+                        Some(range) => self.edit(&uri, range, change.text)?,
+                        None => self.open(&uri, change.text)?,
+                    }
+                }
+                self.measure(&uri)
+            }
+            "textDocument/didClose" => {
+                let params: DidCloseTextDocumentParams = notification_params(notification)?;
+                self.documents.remove(&params.text_document.uri);
+                self.publish(params.text_document.uri, Vec::new())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Analyze a document's text. A document lintric cannot analyze keeps the analysis of its last
+    /// text that could be, if any, along with the text.
+    fn open(&mut self, uri: &Url, text: String) -> Result<(), String> {
+        let path = uri
+            .to_file_path()
+            .unwrap_or_else(|_| PathBuf::from(uri.path()));
+        let Some(language) = self.options.config.language(&path) else {
+            return Ok(());
+        };
+
+        match AnalysisSession::new(text.clone(), language) {
+            Ok(session) => {
+                let document = Document {
+                    path,
+                    text,
+                    ir: session.ir().clone(),
+                    result: session.result().clone(),
+                    session,
+                };
+                self.documents.insert(uri.clone(), document);
+                Ok(())
+            }
+            Err(e) => {
+                if let Some(document) = self.documents.get_mut(uri) {
+                    document.text = text;
+                }
+                self.log(&format!("Error analyzing {}: {}", path.display(), e))
+            }
+        }
+    }
+
+    /// Apply a change to part of a document's text, and analyze what it changed.
+    ///
+    /// The text takes the change even when the analysis cannot, which then starts again from the
+    /// whole text at the next change. A change whose range is not in the text leaves the text
+    /// unknown, so the document is dropped until the editor opens it again.
+    fn edit(&mut self, uri: &Url, range: Range, text: String) -> Result<(), String> {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return Ok(());
+        };
+        let edit = TextEdit {
+            start_byte: byte_offset(document.text(), range.start),
+            old_end_byte: byte_offset(document.text(), range.end),
+            text,
+        };
+        if document
+            .text
+            .get(edit.start_byte..edit.old_end_byte)
+            .is_none()
+        {
+            let message = format!(
+                "Error editing {}: bytes {}..{} are not in its text",
+                document.path.display(),
+                edit.start_byte,
+                edit.old_end_byte
+            );
+            self.documents.remove(uri);
+            return self.log(&message);
+        }
+
+        let was_current = document.is_current();
+        document
+            .text
+            .replace_range(edit.start_byte..edit.old_end_byte, &edit.text);
+        let analyzed = if was_current {
+            document.session.edit(&edit)
+        } else {
+            match self.options.config.language(&document.path) {
+                Some(language) => AnalysisSession::new(document.text.clone(), language)
+                    .map(|session| document.session = session),
+                None => Ok(()),
+            }
+        };
+        match analyzed {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let message = format!("Error analyzing {}: {}", document.path.display(), e);
+                self.log(&message)
+            }
+        }
+    }
+
+    /// Measure and score a document's analysis as the settings say, and publish its diagnostics.
+    fn measure(&mut self, uri: &Url) -> Result<(), String> {
+        let Some(document) = self.documents.get(uri) else {
+            return Ok(());
+        };
+        let mut ir = document.session.ir().clone();
+        let mut result = document.session.result().clone();
+        ir.file_path = document.path.to_string_lossy().into_owned();
+        result.file_path = ir.file_path.clone();
+        if let Err(e) = self
+            .options
+            .remeasure(&ir, Some(document.session.content()), &mut result)
+        {
+            let message = format!("Error analyzing {}: {}", document.path.display(), e);
+            return self.log(&message);
+        }
+        self.options.score(&mut result);
+
+        let diagnostics = self
+            .violations(&result)
+            .iter()
+            .map(|violation| diagnostic(violation, document.text()))
+            .collect();
+        if let Some(document) = self.documents.get_mut(uri) {
+            document.ir = ir;
+            document.result = result;
+        }
+        self.publish(uri.clone(), diagnostics)
+    }
+
+    /// The metrics of a document over their thresholds.
+    fn violations(&self, result: &AnalysisResult) -> Vec<Violation> {
+        let report = OverallAnalysisReport {
+            results: vec![result.clone()],
+            total_files_analyzed: 1,
+            total_overall_complexity_score: result.overall_complexity_score,
+            average_complexity_score: result.overall_complexity_score,
+            weights: self.options.report_weights(),
+        };
+        // One document is no report to hold to the limit on an average.
+        thresholds::check(&report, &Thresholds::default(), |file_path| {
+            self.options.config.thresholds_for(Path::new(file_path))
+        })
+    }
+
+    /// The metrics of the line under the cursor.
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let line = position.position.line as usize + 1;
+        let metrics = document
+            .result
+            .line_metrics
+            .iter()
+            .find(|metrics| metrics.line_number == line)?;
+
+        let mut text = format!(
+            "**lintric**: score {:.2}\n\n\
+             - Total dependencies: {}\n\
+             - Dependency distance cost: {:.2}\n\
+             - Depth: {}\n\
+             - Transitive dependencies: {}\n\
+             - Fan-in: {}\n\
+             - Blast radius: {}",
+            self.line_score(document, metrics),
+            metrics.total_dependencies,
+            metrics.dependency_distance_cost,
+            metrics.depth,
+            metrics.transitive_dependencies,
+            metrics.fan_in,
+            metrics.blast_radius,
+        );
+        if !metrics.dependent_lines.is_empty() {
+            let lines: Vec<String> = metrics
+                .dependent_lines
+                .iter()
+                .map(|line| line.to_string())
+                .collect();
+            text.push_str(&format!("\n\nDepends on lines {}", lines.join(", ")));
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: text,
+            }),
+            range: None,
+        })
+    }
+
+    /// A hint at the end of each line in range that has metrics.
+    fn inlay_hints(&self, params: InlayHintParams) -> Vec<InlayHint> {
+        let Some(document) = self.documents.get(&params.text_document.uri) else {
+            return Vec::new();
+        };
+        let lines = params.range.start.line as usize + 1..=params.range.end.line as usize + 1;
+        document
+            .result
+            .line_metrics
+            .iter()
+            .filter(|metrics| lines.contains(&metrics.line_number))
+            .map(|metrics| InlayHint {
+                position: line_end(document.text(), metrics.line_number),
+                label: InlayHintLabel::String(format!(
+                    "deps {} · depth {} · score {:.2}",
+                    metrics.total_dependencies,
+                    metrics.depth,
+                    self.line_score(document, metrics)
+                )),
+                kind: None,
+                text_edits: None,
+                tooltip: None,
+                padding_left: Some(true),
+                padding_right: None,
+                data: None,
+            })
+            .collect()
+    }
+
+    /// Where the usage under the cursor resolved to, or, away from any usage, everything its line
+    /// depends on.
+    fn dependencies(&self, params: GotoDefinitionParams) -> Vec<Location> {
+        let position = params.text_document_position_params;
+        self.follow(
+            &position.text_document.uri,
+            position.position,
+            |dependency| {
+                (
+                    dependency.source_line,
+                    dependency.source_span,
+                    dependency.target_span,
+                )
+            },
+        )
+    }
+
+    /// The usages of the definition under the cursor, or, away from any definition, everything
+    /// that depends on its line.
+    fn dependents(&self, params: ReferenceParams) -> Vec<Location> {
+        let position = params.text_document_position;
+        self.follow(
+            &position.text_document.uri,
+            position.position,
+            |dependency| {
+                (
+                    dependency.target_line,
+                    dependency.target_span,
+                    dependency.source_span,
+                )
+            },
+        )
+    }
+
+    /// Follow the edges of a document from the cursor: `ends` gives an edge's line and span at the
+    /// cursor's end, and its span at the other.
+    fn follow(
+        &self,
+        uri: &Url,
+        cursor: Position,
+        ends: impl Fn(&Dependency) -> (usize, Span, Span),
+    ) -> Vec<Location> {
+        let Some(document) = self.documents.get(uri) else {
+            return Vec::new();
+        };
+        let (line, column) = source_position(document.text(), cursor);
+        let edges: Vec<(usize, Span, Span)> = document
+            .ir
+            .dependencies
+            .iter()
+            .filter(|dependency| !dependency.is_cross_file())
+            .map(ends)
+            .filter(|(edge_line, _, _)| *edge_line == line)
+            .collect();
+        let at_cursor: Vec<&(usize, Span, Span)> = edges
+            .iter()
+            .filter(|(_, span, _)| contains(span, line, column))
+            .collect();
+        let followed = if at_cursor.is_empty() {
+            edges.iter().collect()
+        } else {
+            at_cursor
+        };
+
+        let mut locations: Vec<Location> = Vec::new();
+        for (_, _, span) in followed {
+            let location = Location {
+                uri: uri.clone(),
+                range: range(document.text(), span),
+            };
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+        locations
+    }
+
+    fn line_score(&self, document: &Document, metrics: &LineMetrics) -> f64 {
+        document
+            .result
+            .weights
+            .unwrap_or_else(|| self.options.report_weights())
+            .line_score(metrics)
+    }
+
+    fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> Result<(), String> {
+        self.send(Message::Notification(Notification::new(
+            "textDocument/publishDiagnostics".to_string(),
+            PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            },
+        )))
+    }
+
+    fn log(&self, message: &str) -> Result<(), String> {
+        self.send(Message::Notification(Notification::new(
+            "window/logMessage".to_string(),
+            serde_json::json!({ "type": 1, "message": message }),
+        )))
+    }
+
+    fn send(&self, message: Message) -> Result<(), String> {
+        self.connection
+            .sender
+            .send(message)
+            .map_err(|e| format!("Failed to send to the client: {e}"))
+    }
+}
+
+fn params<P: DeserializeOwned>(request: Request) -> Result<P, (RequestId, String)> {
+    serde_json::from_value(request.params).map_err(|e| (request.id, e.to_string()))
+}
+
+fn notification_params<P: DeserializeOwned>(notification: Notification) -> Result<P, String> {
+    serde_json::from_value(notification.params)
+        .map_err(|e| format!("Invalid {}: {}", notification.method, e))
+}
+
+fn ok(id: RequestId, result: impl serde::Serialize) -> Result<Response, (RequestId, String)> {
+    Ok(Response::new_ok(id, result))
+}
+
+/// A violation as a diagnostic on its line, or on the first line for a file's score.
+fn diagnostic(violation: &Violation, text: &str) -> Diagnostic {
+    let range = match violation.line {
+        Some(line) => Range {
+            start: Position::new(line as u32 - 1, 0),
+            end: line_end(text, line),
+        },
+        None => Range::default(),
+    };
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(violation.metric.to_string())),
+        source: Some("lintric".to_string()),
+        message: violation.message(),
+        ..Default::default()
+    }
+}
+
+/// Whether a span holds a line and column, its end included, so a cursor just past an identifier
+/// is still on it.
+fn contains(span: &Span, line: usize, column: usize) -> bool {
+    (span.start_line, span.start_column) <= (line, column)
+        && (line, column) <= (span.end_line, span.end_column)
+}
+
+/// The text of a line, from the first.
+fn line_text(text: &str, line: usize) -> &str {
+    line.checked_sub(1)
+        .and_then(|index| text.lines().nth(index))
+        .unwrap_or("")
+}
+
+/// A line and byte column, both from one as spans have them, as an LSP position: from zero, in
+/// UTF-16 code units.
+fn lsp_position(text: &str, line: usize, column: usize) -> Position {
+    let line_text = line_text(text, line);
+    let mut byte = column.saturating_sub(1).min(line_text.len());
+    while !line_text.is_char_boundary(byte) {
+        byte -= 1;
+    }
+    Position::new(
+        line.saturating_sub(1) as u32,
+        line_text[..byte].encode_utf16().count() as u32,
+    )
+}
+
+/// An LSP position as a line and byte column, both from one.
+fn source_position(text: &str, position: Position) -> (usize, usize) {
+    let line = position.line as usize + 1;
+    let mut units = 0;
+    let mut byte = 0;
+    for character in line_text(text, line).chars() {
+        if units >= position.character as usize {
+            break;
+        }
+        units += character.len_utf16();
+        byte += character.len_utf8();
+    }
+    (line, byte + 1)
+}
+
+/// An LSP position as a byte offset into the text.
+fn byte_offset(text: &str, position: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    if line_start == text.len() {
+        return line_start;
+    }
+    let (_, column) = source_position(text, position);
+    line_start + column - 1
+}
+
+fn line_end(text: &str, line: usize) -> Position {
+    lsp_position(text, line, line_text(text, line).len() + 1)
+}
+
+fn range(text: &str, span: &Span) -> Range {
+    Range {
+        start: lsp_position(text, span.start_line, span.start_column),
+        end: lsp_position(text, span.end_line, span.end_column),
+    }
+}
@@ -1,3 +1,3 @@
 fn main() {
-    lintric_cli::run();
+    std::process::exit(lintric_cli::run());
 }
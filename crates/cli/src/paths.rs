@@ -0,0 +1,42 @@
+//! Paths written the same way however they were given and whichever directory lintric runs in.
+
+use std::path::{Component, Path, PathBuf};
+
+/// A path made absolute, with its `.` and `..` parts resolved as written rather than by following
+/// links.
+pub fn normalized(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut normalized = PathBuf::new();
+    for part in path.components() {
+        match part {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            part => normalized.push(part),
+        }
+    }
+    normalized
+}
+
+/// The path of `file` from the directory `dir`, with `/` between its parts on every platform.
+pub fn relative_to(dir: &Path, file: &Path) -> String {
+    let (dir, file) = (normalized(dir), normalized(file));
+    let common = dir
+        .components()
+        .zip(file.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let parts: Vec<String> = dir
+        .components()
+        .skip(common)
+        .map(|_| "..".to_string())
+        .chain(
+            file.components()
+                .skip(common)
+                .map(|part| part.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect();
+    parts.join("/")
+}
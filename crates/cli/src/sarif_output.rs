@@ -0,0 +1,263 @@
+//! Threshold violations as a SARIF 2.1.0 log, the format code scanning tools read annotations from.
+//!
+//! Each violation is a result of the rule named for its metric. A line's result has the lines it
+//! depends on as related locations, so a reviewer can follow what makes it deep; a file's score has
+//! the file alone as its location, and the report's average has none.
+//!
+//! Files are located relative to the working directory, declared as `%SRCROOT%`, which is the root
+//! of the repository when lintric runs there, as it does in CI.
+
+use crate::logger::Logger;
+use crate::paths;
+use crate::thresholds::Violation;
+use lintric_core::models::OverallAnalysisReport;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+/// The base every file's location is relative to.
+const SOURCE_ROOT: &str = "%SRCROOT%";
+
+/// The rules results can be of, one per metric a threshold can be set on.
+const RULES: [(&str, &str); 5] = [
+    (
+        "total_dependencies",
+        "A line depends directly on more lines than the threshold allows.",
+    ),
+    (
+        "depth",
+        "A line's longest chain of dependencies is longer than the threshold allows.",
+    ),
+    (
+        "transitive_dependencies",
+        "A line depends, directly or through other lines, on more lines than the threshold allows.",
+    ),
+    (
+        "overall_complexity_score",
+        "A file's overall complexity score is above the threshold.",
+    ),
+    (
+        "average_complexity_score",
+        "The average complexity score of the files analyzed is above the threshold.",
+    ),
+];
+
+#[derive(Serialize)]
+struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    original_uri_base_ids: BTreeMap<&'static str, ArtifactLocation>,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    short_description: Message,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+}
+
+/// Write the violations of a report as a SARIF log.
+pub fn generate_sarif_report(
+    report: &OverallAnalysisReport,
+    violations: &[Violation],
+    logger: &dyn Logger,
+) {
+    let working_dir = paths::normalized(Path::new("."));
+    let log = Log {
+        schema: SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "lintric",
+                    version: lintric_core::VERSION,
+                    rules: RULES
+                        .iter()
+                        .map(|(id, description)| Rule {
+                            id,
+                            short_description: Message {
+                                text: description.to_string(),
+                            },
+                        })
+                        .collect(),
+                },
+            },
+            original_uri_base_ids: BTreeMap::from([(
+                SOURCE_ROOT,
+                ArtifactLocation {
+                    uri: format!(
+                        "file://{}/",
+                        percent_encoded(&working_dir.to_string_lossy().replace('\\', "/"))
+                            .trim_end_matches('/')
+                    ),
+                    uri_base_id: None,
+                },
+            )]),
+            results: violations
+                .iter()
+                .map(|violation| sarif_result(report, violation, &working_dir))
+                .collect(),
+        }],
+    };
+
+    logger.info(&serde_json::to_string_pretty(&log).unwrap());
+}
+
+fn sarif_result(
+    report: &OverallAnalysisReport,
+    violation: &Violation,
+    working_dir: &Path,
+) -> SarifResult {
+    let rule_index = RULES
+        .iter()
+        .position(|(id, _)| *id == violation.metric)
+        .unwrap_or_default();
+    let message = Message {
+        text: violation.message(),
+    };
+
+    let Some(file_path) = &violation.file_path else {
+        return SarifResult {
+            rule_id: violation.metric,
+            rule_index,
+            level: "error",
+            message,
+            locations: Vec::new(),
+            related_locations: Vec::new(),
+        };
+    };
+    let uri = percent_encoded(&paths::relative_to(working_dir, Path::new(file_path)));
+    let location = |line: Option<usize>| PhysicalLocation {
+        artifact_location: ArtifactLocation {
+            uri: uri.clone(),
+            uri_base_id: Some(SOURCE_ROOT),
+        },
+        region: line.map(|start_line| Region { start_line }),
+    };
+
+    let mut dependent_lines = violation
+        .line
+        .and_then(|line| {
+            report
+                .results
+                .iter()
+                .find(|result| result.file_path == *file_path)?
+                .line_metrics
+                .iter()
+                .find(|metrics| metrics.line_number == line)
+        })
+        .map(|metrics| metrics.dependent_lines.clone())
+        .unwrap_or_default();
+    dependent_lines.sort_unstable();
+    dependent_lines.dedup();
+
+    SarifResult {
+        rule_id: violation.metric,
+        rule_index,
+        level: "error",
+        message,
+        locations: vec![Location {
+            id: None,
+            physical_location: location(violation.line),
+            message: None,
+        }],
+        related_locations: dependent_lines
+            .into_iter()
+            .enumerate()
+            .map(|(id, line)| Location {
+                id: Some(id),
+                physical_location: location(Some(line)),
+                message: Some(Message {
+                    text: format!("Line {line}, which this line depends on"),
+                }),
+            })
+            .collect(),
+    }
+}
+
+/// A path as the path of a URI: every byte but unreserved characters and `/` percent-encoded.
+fn percent_encoded(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,183 @@
+//! Limits on the metrics, and the places a report goes past them.
+//!
+//! A limit is exceeded by a value above it, not one equal to it. Limits on lines and files can be
+//! set apart for one directory by an override in `lintric.toml`; the limit on the average applies
+//! to the report as a whole, so only the top-level one counts.
+
+use lintric_core::models::OverallAnalysisReport;
+use serde::{Deserialize, Serialize};
+
+/// The limits a `[thresholds]` table or `--threshold` flags set, leaving the others as they were.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Thresholds {
+    /// Per line.
+    pub total_dependencies: Option<f64>,
+    /// Per line.
+    pub depth: Option<f64>,
+    /// Per line.
+    pub transitive_dependencies: Option<f64>,
+    /// Per file.
+    pub overall_complexity_score: Option<f64>,
+    /// Of the report.
+    pub average_complexity_score: Option<f64>,
+}
+
+/// No metric is negative, so a negative limit would be exceeded by every value, and one that is not
+/// finite by none or, as NaN, compare to nothing.
+fn valid_limit(value: f64) -> Result<f64, String> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(format!(
+            "limit must be a finite number no less than 0, got {value}"
+        ))
+    }
+}
+
+impl Thresholds {
+    /// Limits given as `--threshold METRIC=LIMIT` flags, named as the metrics are.
+    pub fn from_flags(flags: &[String]) -> Result<Self, String> {
+        let mut thresholds = Self::default();
+        for flag in flags {
+            let (name, value) = flag
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid --threshold {flag}: expected METRIC=LIMIT"))?;
+            let value: f64 = value
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid --threshold {flag}: {e}"))
+                .and_then(|value| {
+                    valid_limit(value).map_err(|e| format!("Invalid --threshold {flag}: {e}"))
+                })?;
+            let limit = match name.trim() {
+                "total_dependencies" => &mut thresholds.total_dependencies,
+                "depth" => &mut thresholds.depth,
+                "transitive_dependencies" => &mut thresholds.transitive_dependencies,
+                "overall_complexity_score" => &mut thresholds.overall_complexity_score,
+                "average_complexity_score" => &mut thresholds.average_complexity_score,
+                _ => return Err(format!("Invalid --threshold {flag}: unknown metric {name}")),
+            };
+            *limit = Some(value);
+        }
+        Ok(thresholds)
+    }
+
+    /// These limits, with those `other` sets in place of their own.
+    pub fn overridden_by(&self, other: &Thresholds) -> Thresholds {
+        Thresholds {
+            total_dependencies: other.total_dependencies.or(self.total_dependencies),
+            depth: other.depth.or(self.depth),
+            transitive_dependencies: other
+                .transitive_dependencies
+                .or(self.transitive_dependencies),
+            overall_complexity_score: other
+                .overall_complexity_score
+                .or(self.overall_complexity_score),
+            average_complexity_score: other
+                .average_complexity_score
+                .or(self.average_complexity_score),
+        }
+    }
+}
+
+/// A metric above its limit.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    /// The file, or none for the report's average.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// The line, or none for a file's score or the report's average.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub metric: &'static str,
+    pub value: f64,
+    pub threshold: f64,
+}
+
+impl Violation {
+    /// What went past which limit, as `depth is 7, over the threshold of 5`.
+    pub fn message(&self) -> String {
+        let value = if self.line.is_some() {
+            format!("{}", self.value)
+        } else {
+            format!("{:.2}", self.value)
+        };
+        format!(
+            "{} is {}, over the threshold of {}",
+            self.metric, value, self.threshold
+        )
+    }
+}
+
+/// Every metric of the report above its limit: each file's lines in order, then the file's score,
+/// and the report's average last.
+///
+/// `thresholds_for` gives the limits for a file, and `thresholds` those of the report.
+pub fn check(
+    report: &OverallAnalysisReport,
+    thresholds: &Thresholds,
+    thresholds_for: impl Fn(&str) -> Thresholds,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut results: Vec<_> = report.results.iter().collect();
+    results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    for result in results {
+        let limits = thresholds_for(&result.file_path);
+        for metrics in &result.line_metrics {
+            let line_values = [
+                (
+                    "total_dependencies",
+                    metrics.total_dependencies as f64,
+                    limits.total_dependencies,
+                ),
+                ("depth", metrics.depth as f64, limits.depth),
+                (
+                    "transitive_dependencies",
+                    metrics.transitive_dependencies as f64,
+                    limits.transitive_dependencies,
+                ),
+            ];
+            for (metric, value, limit) in line_values {
+                if let Some(threshold) = limit.filter(|threshold| value > *threshold) {
+                    violations.push(Violation {
+                        file_path: Some(result.file_path.clone()),
+                        line: Some(metrics.line_number),
+                        metric,
+                        value,
+                        threshold,
+                    });
+                }
+            }
+        }
+
+        if let Some(threshold) = limits
+            .overall_complexity_score
+            .filter(|threshold| result.overall_complexity_score > *threshold)
+        {
+            violations.push(Violation {
+                file_path: Some(result.file_path.clone()),
+                line: None,
+                metric: "overall_complexity_score",
+                value: result.overall_complexity_score,
+                threshold,
+            });
+        }
+    }
+
+    if let Some(threshold) = thresholds
+        .average_complexity_score
+        .filter(|threshold| report.average_complexity_score > *threshold)
+    {
+        violations.push(Violation {
+            file_path: None,
+            line: None,
+            metric: "average_complexity_score",
+            value: report.average_complexity_score,
+            threshold,
+        });
+    }
+
+    violations
+}
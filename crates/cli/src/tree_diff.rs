@@ -0,0 +1,430 @@
+//! How the metrics of one tree differ from those of another.
+//!
+//! A tree is a directory, or a git revision extracted to a temporary one. Files are paired by their
+//! path within the tree. Lines and dependencies are paired by the text of their lines rather than
+//! by their numbers, so an edit above a line does not make everything below it look new; of
+//! several lines with the same text, the nearest is taken.
+
+use crate::file_processor::ProcessOptions;
+use crate::logger::Logger;
+use crate::parallel;
+use lintric_core::models::{Dependency, DependencyType, LineMetrics};
+use lintric_core::{analyze_content, AnalysisResult};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The files whose metrics differ between two trees.
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeDiff {
+    pub old: String,
+    pub new: String,
+    /// In path order.
+    pub files: Vec<FileDiff>,
+    /// The paths within the trees of the files that could not be analyzed in either, in path
+    /// order. They are not compared, as a file missing from one side is not added or removed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiff {
+    /// The path within the trees.
+    pub file_path: String,
+    pub status: FileStatus,
+    /// None for an added file.
+    pub old_score: Option<f64>,
+    /// None for a removed file.
+    pub new_score: Option<f64>,
+    /// What the score went up by, the missing side counting as 0.
+    pub score_delta: f64,
+    /// With the lines of the new tree.
+    pub added_dependencies: Vec<DependencyChange>,
+    /// With the lines of the old tree.
+    pub removed_dependencies: Vec<DependencyChange>,
+    /// The lines of the new tree whose depth or transitive dependencies went up.
+    pub worse_lines: Vec<LineChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyChange {
+    pub source_line: usize,
+    pub target_line: usize,
+    pub symbol: String,
+    pub dependency_type: DependencyType,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LineChange {
+    pub line: usize,
+    /// None for a line the old file did not have.
+    pub old_depth: Option<usize>,
+    pub new_depth: usize,
+    /// None for a line the old file did not have.
+    pub old_transitive_dependencies: Option<usize>,
+    pub new_transitive_dependencies: usize,
+}
+
+/// A tree to analyze, removed when dropped if it was extracted from git.
+struct Tree {
+    /// Where the files are read from.
+    root: PathBuf,
+    /// The directory the files stand for, which `lintric.toml` settings are matched against.
+    origin: PathBuf,
+    /// The directory to remove, for a revision.
+    temporary: Option<PathBuf>,
+}
+
+impl Drop for Tree {
+    fn drop(&mut self) {
+        if let Some(temporary) = &self.temporary {
+            let _ = fs::remove_dir_all(temporary);
+        }
+    }
+}
+
+impl Tree {
+    /// The directory `spec` names, or else the git revision it names, extracted as of the
+    /// working directory.
+    fn open(spec: &str, index: usize) -> Result<Self, String> {
+        if Path::new(spec).is_dir() {
+            return Ok(Self {
+                root: PathBuf::from(spec),
+                origin: PathBuf::from(spec),
+                temporary: None,
+            });
+        }
+
+        let origin = std::env::current_dir()
+            .map_err(|e| format!("Failed to read working directory: {e}"))?;
+        let prefix = git(&["rev-parse", "--show-prefix"])
+            .map_err(|e| format!("{spec} is neither a directory nor a git revision: {e}"))?;
+        git(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{spec}^{{tree}}"),
+        ])
+        .map_err(|_| format!("{spec} is neither a directory nor a git revision"))?;
+
+        let temporary =
+            std::env::temp_dir().join(format!("lintric-diff-{}-{}", std::process::id(), index));
+        let _ = fs::remove_dir_all(&temporary);
+        fs::create_dir_all(&temporary)
+            .map_err(|e| format!("Failed to create {}: {}", temporary.display(), e))?;
+        let tree = Self {
+            root: temporary.join(prefix.trim()),
+            origin,
+            temporary: Some(temporary.clone()),
+        };
+        extract(spec, &temporary)?;
+        if !tree.root.is_dir() {
+            fs::create_dir_all(&tree.root)
+                .map_err(|e| format!("Failed to create {}: {}", tree.root.display(), e))?;
+        }
+        Ok(tree)
+    }
+}
+
+/// The metrics of `old` and `new`, compared file by file.
+pub fn diff(
+    old: &str,
+    new: &str,
+    options: &ProcessOptions,
+    logger: &dyn Logger,
+) -> Result<TreeDiff, String> {
+    let (old_files, old_failed) = analyze_tree(&Tree::open(old, 0)?, options, logger)?;
+    let (new_files, new_failed) = analyze_tree(&Tree::open(new, 1)?, options, logger)?;
+    let failed_files: BTreeSet<String> = old_failed.into_iter().chain(new_failed).collect();
+
+    let mut paths: Vec<&String> = old_files
+        .keys()
+        .chain(new_files.keys())
+        .filter(|path| !failed_files.contains(*path))
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    let files = paths
+        .into_iter()
+        .filter_map(|path| {
+            compare(
+                path,
+                old_files.get(path.as_str()),
+                new_files.get(path.as_str()),
+            )
+        })
+        .collect();
+
+    Ok(TreeDiff {
+        old: old.to_string(),
+        new: new.to_string(),
+        files,
+        failed_files: failed_files.into_iter().collect(),
+    })
+}
+
+/// A file's analysis, with what it takes to pair its lines with those of another.
+struct FileAnalysis {
+    result: AnalysisResult,
+    dependencies: Vec<Dependency>,
+    /// The file's lines, trimmed.
+    lines: Vec<String>,
+}
+
+impl FileAnalysis {
+    fn text(&self, line: usize) -> &str {
+        line.checked_sub(1)
+            .and_then(|index| self.lines.get(index))
+            .map_or("", String::as_str)
+    }
+
+    /// The line with the given text nearest to `near`.
+    fn find(&self, text: &str, near: usize) -> Option<usize> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| *line == text)
+            .map(|(index, _)| index + 1)
+            .min_by_key(|line| line.abs_diff(near))
+    }
+
+    fn metrics(&self, line: usize) -> Option<&LineMetrics> {
+        self.result
+            .line_metrics
+            .iter()
+            .find(|metrics| metrics.line_number == line)
+    }
+}
+
+/// Every file of the tree that is analyzed, by its path within the tree, with the paths of those
+/// that could not be.
+fn analyze_tree(
+    tree: &Tree,
+    options: &ProcessOptions,
+    logger: &dyn Logger,
+) -> Result<(BTreeMap<String, FileAnalysis>, Vec<String>), String> {
+    let files: Vec<(String, PathBuf)> = options
+        .filter
+        .walk_as(&tree.root, &tree.origin)?
+        .into_iter()
+        .filter_map(|file| {
+            let relative = file.strip_prefix(&tree.root).ok()?.to_path_buf();
+            Some((relative.to_string_lossy().replace('\\', "/"), file))
+        })
+        .filter(|(relative, _)| {
+            options
+                .config
+                .language(&tree.origin.join(relative))
+                .is_some()
+        })
+        .collect();
+
+    let analyzed = parallel::map(&files, options.jobs, |(relative, file)| {
+        let origin = tree.origin.join(relative);
+        let language = options
+            .config
+            .language(&origin)
+            .ok_or_else(|| format!("Unsupported file type for analysis: {relative}"))?;
+        let content =
+            fs::read_to_string(file).map_err(|e| format!("Failed to read file {relative}: {e}"))?;
+        let lines = content
+            .lines()
+            .map(|line| line.trim().to_string())
+            .collect();
+        let (ir, mut result) = analyze_content(content.clone(), language)?;
+        result.file_path = origin.to_string_lossy().into_owned();
+        options.remeasure(&ir, Some(&content), &mut result)?;
+        options.score(&mut result);
+        result.file_path = relative.clone();
+        Ok::<_, String>(FileAnalysis {
+            result,
+            dependencies: ir.dependencies,
+            lines,
+        })
+    });
+
+    let mut analyses = BTreeMap::new();
+    let mut failed = Vec::new();
+    for ((relative, _), analysis) in files.into_iter().zip(analyzed) {
+        match analysis {
+            Ok(analysis) => {
+                analyses.insert(relative, analysis);
+            }
+            Err(e) => {
+                logger.error(&format!("Error processing file {relative}: {e}"));
+                failed.push(relative);
+            }
+        }
+    }
+    Ok((analyses, failed))
+}
+
+/// How a file changed, or none if its metrics did not.
+fn compare(path: &str, old: Option<&FileAnalysis>, new: Option<&FileAnalysis>) -> Option<FileDiff> {
+    let status = match (old, new) {
+        (None, Some(_)) => FileStatus::Added,
+        (Some(_), None) => FileStatus::Removed,
+        _ => FileStatus::Modified,
+    };
+    let old_score = old.map(|old| old.result.overall_complexity_score);
+    let new_score = new.map(|new| new.result.overall_complexity_score);
+
+    let file = FileDiff {
+        file_path: path.to_string(),
+        status,
+        old_score,
+        new_score,
+        score_delta: new_score.unwrap_or(0.0) - old_score.unwrap_or(0.0),
+        added_dependencies: dependencies_only_in(new, old),
+        removed_dependencies: dependencies_only_in(old, new),
+        worse_lines: new.map_or_else(Vec::new, |new| worse_lines(old, new)),
+    };
+
+    let unchanged = file.status == FileStatus::Modified
+        && file.score_delta == 0.0
+        && file.added_dependencies.is_empty()
+        && file.removed_dependencies.is_empty()
+        && file.worse_lines.is_empty();
+    (!unchanged).then_some(file)
+}
+
+/// The dependencies of `file` that `other` has no counterpart for: none with the same symbol and
+/// type between lines with the same text.
+fn dependencies_only_in(
+    file: Option<&FileAnalysis>,
+    other: Option<&FileAnalysis>,
+) -> Vec<DependencyChange> {
+    let Some(file) = file else {
+        return Vec::new();
+    };
+
+    let key = |analysis: &FileAnalysis, dependency: &Dependency| {
+        (
+            analysis.text(dependency.source_line).to_string(),
+            analysis.text(dependency.target_line).to_string(),
+            dependency.symbol.clone(),
+            format!("{:?}", dependency.dependency_type),
+        )
+    };
+    let mut unmatched: HashMap<_, usize> = HashMap::new();
+    if let Some(other) = other {
+        for dependency in &other.dependencies {
+            *unmatched.entry(key(other, dependency)).or_default() += 1;
+        }
+    }
+
+    let mut changes: Vec<DependencyChange> = file
+        .dependencies
+        .iter()
+        .filter(
+            |dependency| match unmatched.get_mut(&key(file, dependency)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            },
+        )
+        .map(|dependency| DependencyChange {
+            source_line: dependency.source_line,
+            target_line: dependency.target_line,
+            symbol: dependency.symbol.clone(),
+            dependency_type: dependency.dependency_type.clone(),
+        })
+        .collect();
+    changes.sort_by(|a, b| {
+        (a.source_line, a.target_line, &a.symbol).cmp(&(b.source_line, b.target_line, &b.symbol))
+    });
+    changes
+}
+
+/// The lines of `new` whose depth or transitive dependencies are above those of the same line in
+/// `old`; a line with no metrics had none, and a line `old` does not have had nothing to go up from.
+fn worse_lines(old: Option<&FileAnalysis>, new: &FileAnalysis) -> Vec<LineChange> {
+    new.result
+        .line_metrics
+        .iter()
+        .filter_map(|metrics| {
+            let before = old.and_then(|old| {
+                let line = old.find(new.text(metrics.line_number), metrics.line_number)?;
+                Some(
+                    old.metrics(line)
+                        .map_or((0, 0), |old| (old.depth, old.transitive_dependencies)),
+                )
+            });
+            let (old_depth, old_transitive) = before.unwrap_or((0, 0));
+            if metrics.depth <= old_depth && metrics.transitive_dependencies <= old_transitive {
+                return None;
+            }
+            Some(LineChange {
+                line: metrics.line_number,
+                old_depth: before.map(|(depth, _)| depth),
+                new_depth: metrics.depth,
+                old_transitive_dependencies: before.map(|(_, transitive)| transitive),
+                new_transitive_dependencies: metrics.transitive_dependencies,
+            })
+        })
+        .collect()
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Write the files of a revision into `dir`, as `git archive` gives them to `tar`.
+fn extract(revision: &str, dir: &Path) -> Result<(), String> {
+    let mut archive = Command::new("git")
+        .args(["archive", "--format=tar", revision])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    let stdout = archive
+        .stdout
+        .take()
+        .ok_or_else(|| "Failed to read git archive output".to_string())?;
+    let unpacked = Command::new("tar")
+        .arg("-x")
+        .arg("-C")
+        .arg(dir)
+        .stdin(stdout)
+        .output()
+        .map_err(|e| format!("Failed to run tar: {e}"))?;
+    let archived = archive
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if !archived.status.success() {
+        return Err(format!(
+            "git archive {} failed: {}",
+            revision,
+            String::from_utf8_lossy(&archived.stderr).trim()
+        ));
+    }
+    if !unpacked.status.success() {
+        return Err(format!(
+            "Failed to extract {}: {}",
+            revision,
+            String::from_utf8_lossy(&unpacked.stderr).trim()
+        ));
+    }
+    Ok(())
+}
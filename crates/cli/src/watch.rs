@@ -0,0 +1,230 @@
+//! Watching source files, so that their metrics are reported again whenever one is saved.
+//!
+//! The paths given are analyzed once, and each file's score then is where the session starts from.
+//! Whenever files under them change, those files alone are analyzed again and the summary printed
+//! again, with how far each score has moved since the start. Which files are watched is decided as
+//! a run over the paths would decide it, so build output or an ignored file changing reports
+//! nothing, nor does the HTML report being written, which `--html` regenerates in place.
+
+use crate::display;
+use crate::file_processor::{self, ProcessOptions};
+use crate::html_output;
+use crate::logger::Logger;
+use crate::parallel;
+use lintric_core::models::{AnalysisResult, OverallAnalysisReport};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// How long a change waits for the rest of the same save: an editor writes a file in several
+/// steps, and a formatter run on save rewrites it right after.
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// Watch the filesystem under `paths`, sending the path of every file created, written or removed.
+///
+/// Changes are sent for as long as the watcher is kept.
+pub fn changes(paths: &[String]) -> Result<(RecommendedWatcher, Receiver<PathBuf>), String> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            for path in event.paths {
+                let _ = sender.send(path);
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to watch for changes: {e}"))?;
+
+    for path in paths {
+        watcher
+            .watch(Path::new(path), RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {path}: {e}"))?;
+    }
+    Ok((watcher, receiver))
+}
+
+/// Analyze `paths`, then again whatever `changes` reports changed under them, until its sender is
+/// dropped.
+pub fn watch(
+    paths: &[String],
+    html: bool,
+    config_path: Option<&str>,
+    changes: &Receiver<PathBuf>,
+    logger: &dyn Logger,
+) -> Result<(), String> {
+    let config = crate::load_config(config_path)?;
+    let options = ProcessOptions {
+        filter: crate::file_filter::FileFilter::default().with_config(&config),
+        jobs: parallel::default_jobs(),
+        measure: config.measure_options(),
+        config,
+        ..Default::default()
+    };
+    let mut session = Session::start(paths, options, logger)?;
+    session.report(html, logger);
+    logger.info(&format!("\nWatching {} for changes...", paths.join(", ")));
+
+    while let Ok(changed) = changes.recv() {
+        let mut changed = BTreeSet::from([changed]);
+        while let Ok(path) = changes.recv_timeout(SETTLE) {
+            changed.insert(path);
+        }
+
+        let reanalyzed = session.update(&changed, logger);
+        if !reanalyzed.is_empty() {
+            logger.info(&format!("\n--- Changed: {} ---", reanalyzed.join(", ")));
+            session.report(html, logger);
+        }
+    }
+    Ok(())
+}
+
+/// The files being watched and their latest results.
+struct Session<'a> {
+    paths: &'a [String],
+    options: ProcessOptions,
+    files: BTreeSet<PathBuf>,
+    results: BTreeMap<String, AnalysisResult>,
+    /// Each file's score when the session started, which files added since do not have.
+    started: BTreeMap<String, f64>,
+    /// Files changed since the paths were last walked, made absolute.
+    changed: BTreeSet<PathBuf>,
+}
+
+impl<'a> Session<'a> {
+    fn start(
+        paths: &'a [String],
+        options: ProcessOptions,
+        logger: &dyn Logger,
+    ) -> Result<Self, String> {
+        let mut session = Self {
+            paths,
+            options,
+            files: BTreeSet::new(),
+            results: BTreeMap::new(),
+            started: BTreeMap::new(),
+            changed: BTreeSet::new(),
+        };
+        session.files = session.watched_files()?;
+        let files: Vec<PathBuf> = session.files.iter().cloned().collect();
+        session.analyze(&files, logger);
+        session.started = session
+            .results
+            .iter()
+            .map(|(file_path, result)| (file_path.clone(), result.overall_complexity_score))
+            .collect();
+        Ok(session)
+    }
+
+    /// The files a run over the paths would analyze.
+    fn watched_files(&self) -> Result<BTreeSet<PathBuf>, String> {
+        let mut files = BTreeSet::new();
+        for path in self.paths.iter().map(Path::new) {
+            if path.is_dir() {
+                files.extend(self.options.filter.walk(path)?);
+            } else if path.is_file() {
+                files.insert(path.to_path_buf());
+            }
+        }
+        files.retain(|file| self.options.config.language(file).is_some());
+        Ok(files)
+    }
+
+    /// Analyze files again, keeping a file's last result when it cannot be.
+    fn analyze(&mut self, files: &[PathBuf], logger: &dyn Logger) {
+        let processed = parallel::map(files, self.options.jobs, |file_path| {
+            file_processor::process_file(file_path, &self.options)
+        });
+        for (file_path, result) in files.iter().zip(processed) {
+            match result {
+                Ok(result) => {
+                    self.results.insert(result.file_path.clone(), result);
+                }
+                Err(e) => logger.error(&format!(
+                    "Error processing file {}: {}",
+                    file_path.display(),
+                    e
+                )),
+            }
+        }
+    }
+
+    /// Analyze the watched files among those changed, and any that have appeared, and forget those
+    /// that have gone, giving the display paths of them all in order.
+    ///
+    /// When the paths cannot be walked, as while a directory is being replaced, the error is
+    /// reported and the changes kept for the next walk.
+    fn update(&mut self, changed: &BTreeSet<PathBuf>, logger: &dyn Logger) -> Vec<String> {
+        // The watcher may report paths made absolute, where the paths given were relative.
+        self.changed
+            .extend(changed.iter().filter_map(|path| absolute(path)));
+        let files = match self.watched_files() {
+            Ok(files) => files,
+            Err(e) => {
+                logger.error(&format!("Error: {e}"));
+                return Vec::new();
+            }
+        };
+        let changed = std::mem::take(&mut self.changed);
+
+        let removed: Vec<PathBuf> = self.files.difference(&files).cloned().collect();
+        let reanalyzed: Vec<PathBuf> = files
+            .iter()
+            .filter(|file| {
+                !self.files.contains(*file) || absolute(file).is_some_and(|f| changed.contains(&f))
+            })
+            .cloned()
+            .collect();
+        for file in &removed {
+            self.results.remove(&*file.to_string_lossy());
+        }
+        self.analyze(&reanalyzed, logger);
+        self.files = files;
+
+        let mut updated: Vec<&PathBuf> = removed.iter().chain(&reanalyzed).collect();
+        updated.sort();
+        updated
+            .into_iter()
+            .map(|file| display::format_file_path_for_display(&file.to_string_lossy(), self.paths))
+            .collect()
+    }
+
+    /// Print the summary, with each file's change since the session started, and regenerate the
+    /// HTML report if asked to.
+    fn report(&self, html: bool, logger: &dyn Logger) {
+        let results: Vec<AnalysisResult> = self.results.values().cloned().collect();
+        let total_overall_complexity_score: f64 = results
+            .iter()
+            .map(|result| result.overall_complexity_score)
+            .sum();
+        let overall_report = OverallAnalysisReport {
+            total_files_analyzed: results.len(),
+            average_complexity_score: if results.is_empty() {
+                0.0
+            } else {
+                total_overall_complexity_score / results.len() as f64
+            },
+            total_overall_complexity_score,
+            results,
+            weights: self.options.report_weights(),
+        };
+
+        display::display_watch_summary(&overall_report, &self.started, self.paths, logger);
+        if html {
+            html_output::generate_html_report(&overall_report, logger);
+        }
+    }
+}
+
+/// A path made absolute against the working directory, without touching the filesystem, as the
+/// file may be gone.
+fn absolute(path: &Path) -> Option<PathBuf> {
+    std::path::absolute(path).ok()
+}
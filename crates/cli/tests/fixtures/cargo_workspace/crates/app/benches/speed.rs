@@ -0,0 +1,4 @@
+fn main() {
+    let iterations = 10;
+    println!("{iterations}");
+}
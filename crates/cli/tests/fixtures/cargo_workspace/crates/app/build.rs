@@ -0,0 +1,4 @@
+fn main() {
+    let out = std::env::var("OUT_DIR").unwrap();
+    println!("{out}");
+}
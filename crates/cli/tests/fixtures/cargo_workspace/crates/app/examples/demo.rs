@@ -0,0 +1,4 @@
+fn main() {
+    let demo = "demo";
+    println!("{demo}");
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let name = "tool";
+    println!("{name}");
+}
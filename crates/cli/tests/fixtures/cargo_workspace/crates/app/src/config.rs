@@ -0,0 +1,8 @@
+pub struct Config {
+    pub retries: u32,
+}
+
+pub fn default_config() -> Config {
+    let retries = 3;
+    Config { retries }
+}
@@ -0,0 +1,8 @@
+mod config;
+
+pub use config::Config;
+
+pub fn run(config: &Config) -> u32 {
+    let retries = config.retries;
+    retries + 1
+}
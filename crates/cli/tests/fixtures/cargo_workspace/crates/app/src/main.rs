@@ -0,0 +1,5 @@
+fn main() {
+    let count = 2;
+    let doubled = count * 2;
+    println!("{doubled}");
+}
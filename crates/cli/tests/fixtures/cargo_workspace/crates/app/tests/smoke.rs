@@ -0,0 +1,5 @@
+#[test]
+fn smoke() {
+    let value = 1;
+    assert_eq!(value, 1);
+}
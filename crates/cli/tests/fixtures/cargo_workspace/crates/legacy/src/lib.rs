@@ -0,0 +1,4 @@
+pub fn old() -> u32 {
+    let value = 1;
+    value
+}
@@ -0,0 +1,4 @@
+pub fn double(value: u32) -> u32 {
+    let doubled = value * 2;
+    doubled
+}
@@ -0,0 +1,2 @@
+const schema = 1;
+const copy = schema;
@@ -0,0 +1,2 @@
+const base = 1;
+const next = base * 2;
@@ -0,0 +1,2 @@
+const q = 1;
+const r = q;
@@ -0,0 +1,4 @@
+const z = 0;
+const a = 1;
+const b = a + z;
+const c = b;
@@ -0,0 +1 @@
+const unchanged = 1;
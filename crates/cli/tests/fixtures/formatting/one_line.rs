@@ -0,0 +1,19 @@
+struct Builder {
+    size: usize,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Builder { size: 0 }
+    }
+
+    fn size(self, size: usize) -> Self {
+        Builder { size }
+    }
+}
+
+fn build(width: usize, height: usize) -> Builder {
+    let area = width * height;
+    let builder = Builder::new().size(area).size(width).size(height);
+    builder
+}
@@ -0,0 +1,2 @@
+const bundled = 1;
+export const output = bundled + 1;
@@ -0,0 +1,4 @@
+pub fn schema() -> u32 {
+    let version = 1;
+    version
+}
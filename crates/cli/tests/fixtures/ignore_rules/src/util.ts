@@ -0,0 +1,2 @@
+const base = 2;
+export const doubled = base * 2;
@@ -0,0 +1,8 @@
+mod models;
+pub mod services;
+
+pub use models::User;
+
+pub fn run() -> User {
+    services::greet::build_user("guest")
+}
@@ -0,0 +1,8 @@
+pub enum Role {
+    Admin,
+    Guest,
+}
+
+pub fn default_role() -> Role {
+    Role::Guest
+}
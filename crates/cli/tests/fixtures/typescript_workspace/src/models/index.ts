@@ -0,0 +1,3 @@
+export * from "./user";
+export { Role } from "./role";
+export { defaultRole as fallbackRole } from "./role";
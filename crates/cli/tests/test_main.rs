@@ -42,6 +42,7 @@ fn test_basic_analysis() {
     lintric_cli::run_from_iter(
         [
             "lintric-cli",
+            "--no-cache",
             "--verbose",
             "tests/fixtures/temp_test_file.rs",
         ],
@@ -54,17 +55,1258 @@ fn test_basic_analysis() {
 #[test]
 fn test_multiple_files_analysis() {
     let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
-    lintric_cli::run_from_iter(["lintric-cli", "tests/fixtures/multiple"], &shared);
+    lintric_cli::run_from_iter(
+        ["lintric-cli", "--no-cache", "tests/fixtures/multiple"],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_project_analysis() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--project",
+            "tests/fixtures/rust_crate",
+        ],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_typescript_project_analysis() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--project",
+            "tests/fixtures/typescript_workspace",
+        ],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_typescript_project_with_path_aliases() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--project",
+            "tests/fixtures/typescript_paths",
+        ],
+        &shared,
+    );
+    let logger = shared.0.lock().unwrap();
+    assert_snapshot!(logger.err.clone());
+}
+
+#[test]
+fn test_typescript_project_with_an_unreadable_tsconfig() {
+    let dir = std::env::temp_dir().join(format!("lintric-tsconfig-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("app")).unwrap();
+    std::fs::write(dir.join("app/tsconfig.json"), "{ \"compilerOptions\": {").unwrap();
+    std::fs::write(
+        dir.join("app/main.ts"),
+        "import { greet } from \"../lib\";\nexport const hello = greet();\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("lib.ts"), "export function greet() {}\n").unwrap();
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--project",
+            &dir.to_string_lossy(),
+        ],
+        &shared,
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+    let logger = shared.0.lock().unwrap();
+    assert!(logger.err.contains("Warning: Could not read"));
+    assert!(logger.err.contains("app/tsconfig.json"));
+    assert!(logger.out.contains("main.ts"));
+    assert!(logger.out.contains("lib.ts"));
+}
+
+#[test]
+fn test_typescript_project_with_a_file_that_cannot_be_analyzed() {
+    let dir = std::env::temp_dir().join(format!("lintric-ts-broken-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("main.ts"),
+        "import { greet } from \"./broken\";\nexport const hello = greet();\n",
+    )
+    .unwrap();
+    // Not UTF-8, so not source code.
+    std::fs::write(dir.join("broken.ts"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--project",
+            &dir.to_string_lossy(),
+        ],
+        &shared,
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exit_code, lintric_cli::EXIT_ERROR);
+    let logger = shared.0.lock().unwrap();
+    assert!(logger.out.contains("main.ts"));
+    assert!(logger.err.contains("Error processing file"));
+    assert!(logger.err.contains("broken.ts"));
+    // The import of the broken file names a file of the project, though nothing comes of it.
+    assert!(!logger.err.contains("Unresolved module"));
+    assert!(logger
+        .err
+        .ends_with("Error: 1 file or path could not be analyzed\n"));
+}
+
+#[test]
+fn test_cargo_workspace_analysis() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "tests/fixtures/cargo_workspace",
+        ],
+        &shared,
+    );
     let out = shared.0.lock().unwrap().out.clone();
     assert_snapshot!(out);
 }
 
+#[test]
+fn test_cargo_directory_analyzes_files_outside_its_crates() {
+    let dir = std::env::temp_dir().join(format!("lintric-cargo-dir-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::create_dir_all(dir.join("web")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"site\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("src/lib.rs"), "pub fn serve() {}\n").unwrap();
+    std::fs::write(dir.join("web/app.ts"), "export const start = () => 1;\n").unwrap();
+
+    let run = |path: &std::path::Path| {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        let exit_code = lintric_cli::run_from_iter(
+            ["lintric-cli", "--no-cache", &path.to_string_lossy()],
+            &shared,
+        );
+        assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+        let out = shared.0.lock().unwrap().out.clone();
+        out
+    };
+    let directory = run(&dir);
+    let manifest = run(&dir.join("Cargo.toml"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(directory.contains("src/lib.rs"));
+    assert!(directory.contains("web/app.ts"));
+    assert!(manifest.contains("src/lib.rs"));
+    assert!(!manifest.contains("web/app.ts"));
+}
+
+#[test]
+fn test_cargo_declared_target_claims_its_path() {
+    let dir = std::env::temp_dir().join(format!("lintric-cargo-path-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("tests")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"core\"\nversion = \"0.1.0\"\n\n\
+         [[test]]\nname = \"integration_tests\"\npath = \"tests/mod.rs\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("tests/mod.rs"), "mod helpers;\n").unwrap();
+    std::fs::write(dir.join("tests/helpers.rs"), "pub fn helper() {}\n").unwrap();
+    std::fs::write(dir.join("tests/smoke.rs"), "fn smoke() {}\n").unwrap();
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--json",
+            &dir.join("Cargo.toml").to_string_lossy(),
+        ],
+        &shared,
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+    let out = shared.0.lock().unwrap().out.clone();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let targets: Vec<(&str, &str)> = report["crates"][0]["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|target| {
+            (
+                target["name"].as_str().unwrap(),
+                target["root"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    // `helpers.rs` is still discovered as a test of its own, as Cargo discovers it.
+    assert_eq!(
+        targets,
+        vec![
+            ("helpers", "tests/helpers.rs"),
+            ("integration_tests", "tests/mod.rs"),
+            ("smoke", "tests/smoke.rs"),
+        ]
+    );
+}
+
+#[test]
+fn test_cargo_manifest_json_output() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--json",
+            "tests/fixtures/cargo_workspace/crates/app/Cargo.toml",
+        ],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_ignore_files_are_honoured() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        ["lintric-cli", "--no-cache", "tests/fixtures/ignore_rules"],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_exclude_and_include_globs() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--exclude",
+            "vendor",
+            "--exclude",
+            "src/main.rs",
+            "tests/fixtures/ignore_rules",
+        ],
+        &shared,
+    );
+    let excluded = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!("exclude_globs", excluded);
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--include",
+            "*.rs",
+            "tests/fixtures/ignore_rules",
+        ],
+        &shared,
+    );
+    let included = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!("include_globs", included);
+}
+
+#[test]
+fn test_config_globs_are_relative_to_the_config() {
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("lintric-config-globs-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src/gen")).unwrap();
+    fs::write(dir.join("lintric.toml"), "exclude = [\"src/gen\"]\n").unwrap();
+    fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(dir.join("src/gen/g.rs"), "fn g() {}\n").unwrap();
+    let config = dir.join("lintric.toml").to_string_lossy().into_owned();
+
+    // The glob names `src/gen` from the config's directory, whichever directory is analyzed.
+    for path in [dir.clone(), dir.join("src")] {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        lintric_cli::run_from_iter(
+            [
+                "lintric-cli",
+                "--no-cache",
+                "--config",
+                &config,
+                &path.to_string_lossy(),
+            ],
+            &shared,
+        );
+        let out = shared.0.lock().unwrap().out.clone();
+        assert!(out.contains("main.rs"), "{out}");
+        assert!(!out.contains("g.rs"), "{out}");
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_parallel_analysis_matches_sequential() {
+    let run_with_jobs = |jobs: &str| {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        lintric_cli::run_from_iter(
+            [
+                "lintric-cli",
+                "--no-cache",
+                "--json",
+                "--jobs",
+                jobs,
+                "tests/fixtures",
+            ],
+            &shared,
+        );
+        let out = shared.0.lock().unwrap().out.clone();
+        out
+    };
+
+    let sequential = run_with_jobs("1");
+    let parallel = run_with_jobs("4");
+
+    assert_eq!(sequential, parallel);
+    let report: serde_json::Value = serde_json::from_str(&parallel).unwrap();
+    let paths: Vec<&str> = report["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["file_path"].as_str().unwrap())
+        .collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+}
+
+#[test]
+fn test_default_cache_is_kept_beside_the_config() {
+    let dir = std::env::temp_dir().join(format!("lintric-cache-dir-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(dir.join("lintric.toml"), "").unwrap();
+    std::fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--config",
+            &dir.join("lintric.toml").to_string_lossy(),
+            &dir.join("src").to_string_lossy(),
+        ],
+        &shared,
+    );
+    let entries = std::fs::read_dir(dir.join(".lintric/cache")).map(|entries| entries.count());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+    assert_eq!(entries.unwrap(), 1);
+}
+
+#[test]
+fn test_cache_reuses_analyses_of_unchanged_files() {
+    let cache_dir = std::env::temp_dir().join(format!("lintric-cache-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let cache_dir_str = cache_dir.to_string_lossy().into_owned();
+    let run_with = |extra_args: &[&str]| {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        let mut args = vec!["lintric-cli", "--json", "--cache-dir", &cache_dir_str];
+        args.extend_from_slice(extra_args);
+        args.push("tests/fixtures/multiple");
+        lintric_cli::run_from_iter(args, &shared);
+        let out = shared.0.lock().unwrap().out.clone();
+        out
+    };
+    let run = || run_with(&[]);
+
+    let uncached = run_with(&["--no-cache"]);
+    assert!(!cache_dir.exists());
+    assert_eq!(run(), uncached);
+    let entries: Vec<std::path::PathBuf> = std::fs::read_dir(&cache_dir)
+        .unwrap()
+        .flat_map(|dir| std::fs::read_dir(dir.unwrap().path()).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(run(), uncached);
+
+    // An entry is what the next run reports, so it was not analyzed again.
+    for entry in &entries {
+        let mut cached: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(entry).unwrap()).unwrap();
+        cached["result"]["overall_complexity_score"] = serde_json::json!(1234.5);
+        std::fs::write(entry, cached.to_string()).unwrap();
+    }
+    assert!(run().contains("1234.5"));
+
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[test]
+fn test_config_file_settings() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--config",
+            "tests/fixtures/config_project/lintric.toml",
+            "tests/fixtures/config_project",
+        ],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_output_flags_take_precedence_over_config() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--json",
+            "--config",
+            "tests/fixtures/config_project/lintric.toml",
+            "tests/fixtures/config_project",
+        ],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(report["total_files_analyzed"], 3);
+}
+
+#[test]
+fn test_score_weights_from_flags_and_config() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--json",
+            "--config",
+            "tests/fixtures/config_project/lintric.toml",
+            "--weight",
+            "transitive_dependencies=0",
+            "tests/fixtures/config_project",
+        ],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    assert_eq!(report["weights"]["transitive_dependencies"], 0.0);
+    assert_eq!(report["weights"]["depth"], 1.0);
+    // Each file has one line, of one dependency at distance 0.5 and depth 1.
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results[0]["file_path"], "app.ts");
+    assert_eq!(results[0]["overall_complexity_score"], 2.05);
+    assert!(results[0].get("weights").is_none());
+    // `legacy` weighs depth three times over, as its override in lintric.toml says.
+    assert_eq!(results[1]["file_path"], "legacy/old.inc");
+    assert_eq!(results[1]["overall_complexity_score"], 4.05);
+    assert_eq!(results[1]["weights"]["depth"], 3.0);
+    assert_eq!(results[1]["weights"]["transitive_dependencies"], 0.0);
+}
+
+#[test]
+fn test_impact_weights() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--json",
+            "--weight",
+            "fan_in=1",
+            "--weight",
+            "blast_radius=2",
+            "tests/fixtures/config_project/app.ts",
+        ],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    // Line 1 is depended on by line 2 alone, which adds 1 + 2 to the 2.25 line 2 scores.
+    let result = &report["results"][0];
+    assert_eq!(result["line_metrics"][0]["fan_in"], 1);
+    assert_eq!(result["line_metrics"][0]["blast_radius"], 1);
+    assert_eq!(result["overall_complexity_score"], 5.25);
+}
+
+#[test]
+fn test_exclude_dependency_types() {
+    let report = |flags: &[&str]| {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        let args = [&["lintric-cli", "--json", "--no-cache"], flags].concat();
+        let args = [args, vec!["tests/fixtures/complex_rust_code.rs"]].concat();
+        lintric_cli::run_from_iter(args, &shared);
+        let out = shared.0.lock().unwrap().out.clone();
+        serde_json::from_str::<serde_json::Value>(&out).unwrap()["results"][0].clone()
+    };
+
+    let all = report(&[]);
+    assert_eq!(all["dependency_types"]["TypeReference"], 4);
+    assert_eq!(all["dependency_types"]["VariableUse"], 13);
+
+    let without_types = report(&["--exclude-dependency-type", "TypeReference"]);
+    assert!(without_types["dependency_types"]
+        .get("TypeReference")
+        .is_none());
+    assert_eq!(without_types["dependency_types"]["VariableUse"], 13);
+    assert!(
+        without_types["overall_complexity_score"].as_f64().unwrap()
+            < all["overall_complexity_score"].as_f64().unwrap()
+    );
+}
+
+#[test]
+fn test_statement_granularity() {
+    let scores = |granularity: &str| {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        lintric_cli::run_from_iter(
+            [
+                "lintric-cli",
+                "--no-cache",
+                "--json",
+                "--granularity",
+                granularity,
+                "tests/fixtures/formatting/one_line.rs",
+                "tests/fixtures/formatting/split.rs",
+            ],
+            &shared,
+        );
+        let out = shared.0.lock().unwrap().out.clone();
+        let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+        (
+            report["results"][0]["overall_complexity_score"].clone(),
+            report["results"][1]["overall_complexity_score"].clone(),
+        )
+    };
+
+    // The same code, formatted two ways.
+    let (one_line, split) = scores("line");
+    assert_ne!(one_line, split);
+    let (one_line, split) = scores("statement");
+    assert_eq!(one_line, split);
+}
+
+#[test]
+fn test_invalid_excluded_dependency_type() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--exclude-dependency-type",
+            "TypeRef",
+            "tests/fixtures/multiple",
+        ],
+        &shared,
+    );
+    let logger = shared.0.lock().unwrap();
+    assert_eq!(logger.out, "");
+    assert_eq!(
+        logger.err,
+        "Error: Invalid --exclude-dependency-type TypeRef: Unknown dependency type TypeRef\n"
+    );
+}
+
+#[test]
+fn test_invalid_weight_flag() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--weight",
+            "width=2",
+            "tests/fixtures/multiple",
+        ],
+        &shared,
+    );
+    let logger = shared.0.lock().unwrap();
+    assert_eq!(logger.out, "");
+    assert_eq!(
+        logger.err,
+        "Error: Invalid --weight width=2: unknown metric width\n"
+    );
+}
+
+#[test]
+fn test_thresholds_must_be_finite_and_not_negative() {
+    for threshold in ["depth=-1", "depth=NaN", "depth=inf"] {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        let exit_code = lintric_cli::run_from_iter(
+            [
+                "lintric-cli",
+                "--no-cache",
+                "--threshold",
+                threshold,
+                "tests/fixtures/multiple",
+            ],
+            &shared,
+        );
+        assert_eq!(exit_code, lintric_cli::EXIT_ERROR);
+        let logger = shared.0.lock().unwrap();
+        assert_eq!(logger.out, "");
+        assert!(
+            logger.err.starts_with(&format!(
+                "Error: Invalid --threshold {threshold}: limit must be"
+            )),
+            "{threshold}: {}",
+            logger.err
+        );
+    }
+}
+
+#[test]
+fn test_weights_must_be_finite_and_not_negative() {
+    for weight in ["depth=-1", "depth=NaN", "depth=inf"] {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        let exit_code = lintric_cli::run_from_iter(
+            [
+                "lintric-cli",
+                "--no-cache",
+                "--weight",
+                weight,
+                "tests/fixtures/multiple",
+            ],
+            &shared,
+        );
+        assert_eq!(exit_code, lintric_cli::EXIT_ERROR);
+        let logger = shared.0.lock().unwrap();
+        assert_eq!(logger.out, "");
+        assert!(
+            logger
+                .err
+                .starts_with(&format!("Error: Invalid --weight {weight}: weight must be")),
+            "{weight}: {}",
+            logger.err
+        );
+    }
+
+    let dir = std::env::temp_dir().join(format!("lintric-weights-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("lintric.toml");
+    std::fs::write(&config, "[weights]\nfan_in = -0.5\n").unwrap();
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--config",
+            &config.to_string_lossy(),
+            "tests/fixtures/multiple",
+        ],
+        &shared,
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exit_code, lintric_cli::EXIT_ERROR);
+    let logger = shared.0.lock().unwrap();
+    assert!(logger.err.contains("Failed to parse"));
+    assert!(logger
+        .err
+        .contains("weight must be a finite number no less than 0"));
+}
+
+#[test]
+fn test_thresholds_pass() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--config",
+            "tests/fixtures/config_project/lintric.toml",
+            "tests/fixtures/config_project",
+        ],
+        &shared,
+    );
+    assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+    assert_eq!(shared.0.lock().unwrap().err, "");
+}
+
+#[test]
+fn test_thresholds_exceeded() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    // Weighing depth more takes every file past 3.0, but `legacy` is allowed 5.0.
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--config",
+            "tests/fixtures/config_project/lintric.toml",
+            "--weight",
+            "depth=3",
+            "--threshold",
+            "average_complexity_score=4",
+            "tests/fixtures/config_project",
+        ],
+        &shared,
+    );
+    assert_eq!(exit_code, lintric_cli::EXIT_THRESHOLD_EXCEEDED);
+    let logger = shared.0.lock().unwrap();
+    assert_snapshot!("thresholds_exceeded_out", logger.out);
+    assert_snapshot!("thresholds_exceeded_err", logger.err);
+}
+
+#[test]
+fn test_missing_path_is_an_error() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--threshold",
+            "depth=100",
+            "tests/fixtures/multiple",
+            "tests/fixtures/missing",
+        ],
+        &shared,
+    );
+    assert_eq!(exit_code, lintric_cli::EXIT_ERROR);
+    assert_eq!(
+        shared.0.lock().unwrap().err,
+        "Error: Path tests/fixtures/missing is neither a file nor a directory.\n\
+         Error: 1 file or path could not be analyzed\n"
+    );
+}
+
+#[test]
+fn test_file_that_cannot_be_analyzed_is_an_error() {
+    let dir = std::env::temp_dir().join(format!("lintric-broken-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+    // Not UTF-8, so not source code.
+    std::fs::write(dir.join("broken.rs"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--threshold",
+            "depth=0",
+            &dir.to_string_lossy(),
+        ],
+        &shared,
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exit_code, lintric_cli::EXIT_ERROR);
+    let logger = shared.0.lock().unwrap();
+    assert!(logger.out.contains("main.rs"));
+    assert!(logger.err.contains("Error processing file"));
+    assert!(logger
+        .err
+        .ends_with("Error: 1 file or path could not be analyzed\n"));
+}
+
+#[test]
+fn test_thresholds_json_output() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--json",
+            "--threshold",
+            "total_dependencies=0",
+            "tests/fixtures/multiple",
+        ],
+        &shared,
+    );
+    assert_eq!(exit_code, lintric_cli::EXIT_THRESHOLD_EXCEEDED);
+    let out = shared.0.lock().unwrap().out.clone();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        report["violations"],
+        serde_json::json!([
+            {
+                "file_path": "file1.rs",
+                "line": 2,
+                "metric": "total_dependencies",
+                "value": 1.0,
+                "threshold": 0.0
+            },
+            {
+                "file_path": "file2.ts",
+                "line": 2,
+                "metric": "total_dependencies",
+                "value": 1.0,
+                "threshold": 0.0
+            }
+        ])
+    );
+}
+
+#[test]
+fn test_baseline_fails_only_on_what_got_worse() {
+    let baseline_path =
+        std::env::temp_dir().join(format!("lintric-baseline-test-{}.json", std::process::id()));
+    let baseline = baseline_path.to_string_lossy().into_owned();
+    let run_on = |path: &str, extra_args: &[&str]| {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        let mut args = vec![
+            "lintric-cli",
+            "--no-cache",
+            "--config",
+            "tests/fixtures/config_project/lintric.toml",
+        ];
+        args.extend_from_slice(extra_args);
+        args.push(path);
+        let exit_code = lintric_cli::run_from_iter(args, &shared);
+        let err = shared.0.lock().unwrap().err.clone();
+        (exit_code, err)
+    };
+    let run = |extra_args: &[&str]| run_on("tests/fixtures/config_project", extra_args);
+
+    assert_eq!(
+        run(&[&format!("--write-baseline={baseline}")]),
+        (lintric_cli::EXIT_SUCCESS, String::new())
+    );
+    let recorded: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&baseline_path).unwrap()).unwrap();
+    // Files are recorded by their path from the baseline's directory.
+    let (key, app) = recorded["files"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .find(|(key, _)| key.ends_with("/tests/fixtures/config_project/app.ts"))
+        .unwrap();
+    assert!(key.starts_with("../"), "{key}");
+    assert_eq!(app["lines"][0]["text"], "let b = a + 1;");
+
+    // Every line goes past the limit, as it did when the baseline was written.
+    assert_eq!(
+        run(&[
+            "--threshold",
+            "total_dependencies=0",
+            "--baseline",
+            &baseline
+        ]),
+        (lintric_cli::EXIT_SUCCESS, String::new())
+    );
+    assert_eq!(
+        run(&["--threshold", "total_dependencies=0"]).0,
+        lintric_cli::EXIT_THRESHOLD_EXCEEDED
+    );
+    // However the path analyzed is written.
+    assert_eq!(
+        run_on(
+            "./tests/fixtures/config_project",
+            &[
+                "--threshold",
+                "total_dependencies=0",
+                "--baseline",
+                &baseline
+            ]
+        ),
+        (lintric_cli::EXIT_SUCCESS, String::new())
+    );
+
+    // Weighing depth more makes two files' scores worse; `legacy` weighed it so already.
+    assert_eq!(
+        run(&["--weight", "depth=3", "--baseline", &baseline]),
+        (
+            lintric_cli::EXIT_THRESHOLD_EXCEEDED,
+            "Error: 2 metrics over threshold\n".to_string()
+        )
+    );
+
+    std::fs::remove_file(&baseline_path).unwrap();
+}
+
+#[test]
+fn test_write_baseline_takes_its_file_only_after_an_equals_sign() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--write-baseline",
+            "tests/fixtures/multiple",
+        ],
+        &shared,
+    );
+    let written = std::fs::read_to_string("lintric-baseline.json");
+    let _ = std::fs::remove_file("lintric-baseline.json");
+
+    assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+    assert!(shared
+        .0
+        .lock()
+        .unwrap()
+        .out
+        .contains("Total Files Analyzed: 2"));
+    assert!(written.unwrap().contains("\"files\""));
+}
+
+#[test]
+fn test_changed_since_reports_only_what_changed() {
+    // git runs in the working directory, so this runs the binary in a repository of its own.
+    let repo = std::env::temp_dir().join(format!("lintric-changes-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(&repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(&repo)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+    let lintric = |args: &[&str], stdin: &str| {
+        use std::io::Write;
+        let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_lintric-cli"))
+            .args(args)
+            .current_dir(&repo)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        report["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| {
+                let lines: Vec<u64> = result["line_metrics"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|metrics| metrics["line_number"].as_u64().unwrap())
+                    .collect();
+                (result["file_path"].as_str().unwrap().to_string(), lines)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    std::fs::write(
+        repo.join("a.ts"),
+        "const a = 1;\nconst b = 2;\nconst c = b;\nconst d = c;\nconst e = a;\n",
+    )
+    .unwrap();
+    std::fs::write(repo.join("b.ts"), "const x = 1;\nconst y = x;\n").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+    std::fs::write(
+        repo.join("a.ts"),
+        "const a = 1;\nconst b = 3;\nconst c = b;\nconst d = c;\nconst e = a;\n",
+    )
+    .unwrap();
+
+    // Line 2 changed, and is reported for the lines that depend on it: 3, and 4 through 3. Line 5
+    // depends on neither.
+    let expected = vec![("a.ts".to_string(), vec![2, 3, 4])];
+    assert_eq!(
+        lintric(
+            &["--json", "--no-cache", "--changed-since", "HEAD", "."],
+            ""
+        ),
+        expected
+    );
+
+    let diff = "--- a/a.ts\n+++ b/a.ts\n@@ -2 +2 @@\n-const b = 2;\n+const b = 3;\n";
+    assert_eq!(
+        lintric(&["--json", "--no-cache", "--changed-since", "-", "."], diff),
+        expected
+    );
+
+    std::fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_graph_export() {
+    let graph = |extra_args: &[&str]| {
+        let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+        let mut args = vec!["lintric-cli", "graph"];
+        args.extend_from_slice(extra_args);
+        args.push("tests/fixtures/complex_rust_code.rs");
+        assert_eq!(
+            lintric_cli::run_from_iter(args, &shared),
+            lintric_cli::EXIT_SUCCESS
+        );
+        let out = shared.0.lock().unwrap().out.clone();
+        out
+    };
+
+    assert_snapshot!("graph_lines_dot", graph(&[]));
+    assert_snapshot!(
+        "graph_symbols_mermaid",
+        graph(&["--symbols", "--format", "mermaid"])
+    );
+}
+
+#[test]
+fn test_definitions() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "definitions",
+            "tests/fixtures/complex_rust_code.rs",
+        ],
+        &shared,
+    );
+    assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_diff_directories() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "diff",
+            "tests/fixtures/diff/old",
+            "tests/fixtures/diff/new",
+        ],
+        &shared,
+    );
+    assert_eq!(exit_code, lintric_cli::EXIT_SUCCESS);
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+}
+
+#[test]
+fn test_diff_with_a_file_that_cannot_be_analyzed() {
+    let dir = std::env::temp_dir().join(format!("lintric-diff-broken-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for side in ["old", "new"] {
+        std::fs::create_dir_all(dir.join(side)).unwrap();
+        std::fs::write(dir.join(side).join("main.rs"), "fn main() {}\n").unwrap();
+    }
+    std::fs::write(dir.join("old/lib.rs"), "pub fn lib() {}\n").unwrap();
+    // Not UTF-8, so not source code.
+    std::fs::write(dir.join("new/lib.rs"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "diff",
+            "--json",
+            &dir.join("old").to_string_lossy(),
+            &dir.join("new").to_string_lossy(),
+        ],
+        &shared,
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(exit_code, lintric_cli::EXIT_ERROR);
+    let logger = shared.0.lock().unwrap();
+    let report: serde_json::Value = serde_json::from_str(&logger.out).unwrap();
+    assert_eq!(report["files"], serde_json::json!([]));
+    assert_eq!(report["failed_files"], serde_json::json!(["lib.rs"]));
+    assert!(logger.err.contains("Error processing file lib.rs"));
+    assert!(logger
+        .err
+        .ends_with("Error: 1 file could not be analyzed\n"));
+}
+
+#[test]
+fn test_diff_git_revisions() {
+    // Revisions are read from the repository of the working directory, so this runs the binary in
+    // one of its own.
+    let repo = std::env::temp_dir().join(format!("lintric-diff-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(&repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(&repo)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+
+    std::fs::write(
+        repo.join("a.ts"),
+        "const a = 1;\nconst b = a;\nconst c = 2;\n",
+    )
+    .unwrap();
+    std::fs::write(repo.join("b.ts"), "const x = 1;\nconst y = x;\n").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+    std::fs::write(
+        repo.join("a.ts"),
+        "const a = 1;\nconst b = a;\nconst c = b;\n",
+    )
+    .unwrap();
+    // A line added above the others moves them without changing what they depend on.
+    std::fs::write(repo.join("b.ts"), "// x\nconst x = 1;\nconst y = x;\n").unwrap();
+    git(&["commit", "-q", "-am", "change"]);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_lintric-cli"))
+        .args(["diff", "HEAD~1", "HEAD", "--json"])
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let diff: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let file = |path: &str| {
+        diff["files"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|file| file["file_path"] == path)
+            .cloned()
+            .unwrap()
+    };
+
+    let a = file("a.ts");
+    assert_eq!(a["status"], "modified");
+    assert!(a["score_delta"].as_f64().unwrap() > 0.0);
+    assert_eq!(
+        a["added_dependencies"],
+        serde_json::json!([
+            {"source_line": 3, "target_line": 2, "symbol": "b", "dependency_type": "VariableUse"}
+        ])
+    );
+    assert_eq!(a["removed_dependencies"], serde_json::json!([]));
+    assert_eq!(
+        a["worse_lines"],
+        serde_json::json!([{
+            "line": 3,
+            "old_depth": null,
+            "new_depth": 2,
+            "old_transitive_dependencies": null,
+            "new_transitive_dependencies": 2
+        }])
+    );
+
+    let b = file("b.ts");
+    assert_eq!(b["added_dependencies"], serde_json::json!([]));
+    assert_eq!(b["removed_dependencies"], serde_json::json!([]));
+    assert_eq!(b["worse_lines"], serde_json::json!([]));
+
+    std::fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_sarif_output() {
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    let exit_code = lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--sarif",
+            "--threshold",
+            "depth=2",
+            "--threshold",
+            "overall_complexity_score=1",
+            "--threshold",
+            "average_complexity_score=1",
+            "tests/fixtures/complex_typescript_code.ts",
+        ],
+        &shared,
+    );
+    assert_eq!(exit_code, lintric_cli::EXIT_THRESHOLD_EXCEEDED);
+    let out = shared.0.lock().unwrap().out.clone();
+    let log: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let working_dir = format!("file://{}/", std::env::current_dir().unwrap().display());
+    assert_eq!(
+        log["runs"][0]["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+        working_dir
+    );
+    assert_snapshot!(out.replace(&working_dir, "file:///[working directory]/"));
+
+    // Files under a directory are located from the working directory, not the directory.
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        [
+            "lintric-cli",
+            "--no-cache",
+            "--sarif",
+            "--threshold",
+            "overall_complexity_score=0",
+            "tests/fixtures/multiple",
+        ],
+        &shared,
+    );
+    let log: serde_json::Value = serde_json::from_str(&shared.0.lock().unwrap().out).unwrap();
+    let locations: Vec<&serde_json::Value> = log["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| &result["locations"][0]["physicalLocation"]["artifactLocation"])
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            &serde_json::json!({ "uri": "tests/fixtures/multiple/file1.rs", "uriBaseId": "%SRCROOT%" }),
+            &serde_json::json!({ "uri": "tests/fixtures/multiple/file2.ts", "uriBaseId": "%SRCROOT%" }),
+        ]
+    );
+}
+
 #[test]
 fn test_json_output() {
     let fixture_path = "tests/fixtures/complex_rust_code.rs";
 
     let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
-    lintric_cli::run_from_iter(["lintric-cli", "--json", fixture_path], &shared);
+    lintric_cli::run_from_iter(
+        ["lintric-cli", "--no-cache", "--json", fixture_path],
+        &shared,
+    );
     let out = shared.0.lock().unwrap().out.clone();
     assert_snapshot!(out);
 }
@@ -74,7 +1316,10 @@ fn test_complex_rust_analysis() {
     let fixture_path = "tests/fixtures/complex_rust_code.rs";
 
     let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
-    lintric_cli::run_from_iter(["lintric-cli", "--verbose", fixture_path], &shared);
+    lintric_cli::run_from_iter(
+        ["lintric-cli", "--no-cache", "--verbose", fixture_path],
+        &shared,
+    );
     let out = shared.0.lock().unwrap().out.clone();
     assert_snapshot!(out);
 }
@@ -84,11 +1329,271 @@ fn test_complex_typescript_analysis() {
     let fixture_path = "tests/fixtures/complex_typescript_code.ts";
 
     let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
-    lintric_cli::run_from_iter(["lintric-cli", "--verbose", fixture_path], &shared);
+    lintric_cli::run_from_iter(
+        ["lintric-cli", "--no-cache", "--verbose", fixture_path],
+        &shared,
+    );
     let out = shared.0.lock().unwrap().out.clone();
     assert_snapshot!(out);
 }
 
+#[test]
+fn test_cycles_report() {
+    let fixture_path = "tests/fixtures/recursion.rs";
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        ["lintric-cli", "--no-cache", "--verbose", fixture_path],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    assert_snapshot!(out);
+
+    let shared = SharedLogger(Arc::new(Mutex::new(BufLogger::new())));
+    lintric_cli::run_from_iter(
+        ["lintric-cli", "--no-cache", "--json", fixture_path],
+        &shared,
+    );
+    let out = shared.0.lock().unwrap().out.clone();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(report["results"][0]["cycle_count"], 2);
+    assert_eq!(
+        report["results"][0]["definition_cycles"][0]["items"],
+        serde_json::json!(["is_even", "is_odd"])
+    );
+}
+
+#[test]
+fn test_language_server() {
+    use lsp_server::{Connection, Message, Notification, Request, RequestId};
+    use serde_json::{json, Value};
+
+    let (server, client) = Connection::memory();
+    let serving = std::thread::spawn(move || {
+        lintric_cli::lsp::serve(&server, Some("tests/fixtures/lsp/lintric.toml"))
+    });
+
+    let mut next_id = 0;
+    let mut request = |method: &str, params: Value| {
+        next_id += 1;
+        let id = RequestId::from(next_id);
+        let request = Request::new(id.clone(), method.to_string(), params);
+        client.sender.send(Message::Request(request)).unwrap();
+        loop {
+            match client.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id => {
+                    return response.result.unwrap_or(Value::Null)
+                }
+                _ => {}
+            }
+        }
+    };
+    let notify = |method: &str, params: Value| {
+        let notification = Notification::new(method.to_string(), params);
+        client
+            .sender
+            .send(Message::Notification(notification))
+            .unwrap();
+    };
+    let diagnostics = || loop {
+        if let Message::Notification(notification) = client.receiver.recv().unwrap() {
+            if notification.method == "textDocument/publishDiagnostics" {
+                return notification.params["diagnostics"].clone();
+            }
+        }
+    };
+
+    let capabilities = request("initialize", json!({ "capabilities": {} }));
+    assert_eq!(capabilities["capabilities"]["hoverProvider"], json!(true));
+    assert_eq!(capabilities["capabilities"]["textDocumentSync"], json!(2));
+    notify("initialized", json!({}));
+
+    // The document is never saved: it is analyzed from its buffer.
+    let uri = "file:///lintric-lsp/main.rs";
+    let text = "fn main() {\n    let a = 1;\n    let b = a + 1;\n    println!(\"{}\", b);\n}\n";
+    notify(
+        "textDocument/didOpen",
+        json!({ "textDocument": {
+            "uri": uri, "languageId": "rust", "version": 1, "text": text
+        } }),
+    );
+    assert_eq!(
+        diagnostics(),
+        json!([{
+            "range": { "start": { "line": 3, "character": 0 }, "end": { "line": 3, "character": 22 } },
+            "severity": 2,
+            "code": "depth",
+            "source": "lintric",
+            "message": "depth is 2, over the threshold of 1"
+        }])
+    );
+
+    let at = |line: u32, character: u32| {
+        json!({
+            "textDocument": { "uri": uri },
+            "position": { "line": line, "character": character },
+            "context": { "includeDeclaration": true }
+        })
+    };
+    let hover = request("textDocument/hover", at(2, 0));
+    let hover = hover["contents"]["value"].as_str().unwrap();
+    assert!(hover.contains("- Depth: 1"), "{hover}");
+    assert!(hover.contains("Depends on lines 2"), "{hover}");
+
+    // From the usage of `a` to its definition, and back.
+    let a_definition = json!([{
+        "uri": uri,
+        "range": { "start": { "line": 1, "character": 8 }, "end": { "line": 1, "character": 9 } }
+    }]);
+    let a_usage = json!([{
+        "uri": uri,
+        "range": { "start": { "line": 2, "character": 12 }, "end": { "line": 2, "character": 13 } }
+    }]);
+    assert_eq!(request("textDocument/definition", at(2, 12)), a_definition);
+    assert_eq!(request("textDocument/references", at(1, 8)), a_usage);
+
+    let hints = request(
+        "textDocument/inlayHint",
+        json!({
+            "textDocument": { "uri": uri },
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 4, "character": 0 } }
+        }),
+    );
+    let labels: Vec<&Value> = hints
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hint| &hint["label"])
+        .collect();
+    assert_eq!(labels.len(), 3);
+    assert!(labels[2].as_str().unwrap().starts_with("deps 1 · depth 2"));
+
+    // An unsaved edit is analyzed again, sent as the change to the line it removes.
+    notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{
+                "range": { "start": { "line": 3, "character": 0 }, "end": { "line": 4, "character": 0 } },
+                "text": ""
+            }]
+        }),
+    );
+    assert_eq!(diagnostics(), json!([]));
+    let hover = request("textDocument/hover", at(3, 0));
+    assert_eq!(hover, Value::Null);
+
+    // Or as the whole text.
+    notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": uri, "version": 3 },
+            "contentChanges": [{ "text": text }]
+        }),
+    );
+    assert_eq!(diagnostics().as_array().unwrap().len(), 1);
+
+    // A change to a range that is not in the text leaves it unknown until it is sent whole.
+    notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": uri, "version": 4 },
+            "contentChanges": [{
+                "range": { "start": { "line": 3, "character": 0 }, "end": { "line": 1, "character": 0 } },
+                "text": ""
+            }]
+        }),
+    );
+    assert_eq!(request("textDocument/hover", at(2, 0)), Value::Null);
+    notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": uri, "version": 5 },
+            "contentChanges": [{ "text": text }]
+        }),
+    );
+    assert_eq!(diagnostics().as_array().unwrap().len(), 1);
+
+    request("shutdown", Value::Null);
+    notify("exit", Value::Null);
+    assert_eq!(serving.join().unwrap(), Ok(()));
+}
+
+#[test]
+fn test_watch_reports_changes_since_start() {
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("lintric-watch-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("a.rs"),
+        "fn main() {\n    let a = 1;\n    println!(\"{}\", a);\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("c.rs"),
+        "fn c() {\n    let c = 1;\n    c + 1;\n}\n",
+    )
+    .unwrap();
+
+    let shared = Arc::new(Mutex::new(BufLogger::new()));
+    let logger = SharedLogger(shared.clone());
+    let paths = vec![dir.to_string_lossy().into_owned()];
+    let (sender, changes) = std::sync::mpsc::channel();
+    let watching = std::thread::spawn(move || {
+        lintric_cli::watch::watch(&paths, false, None, &changes, &logger)
+    });
+    while !shared.lock().unwrap().out.contains("Watching") {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    // A file written, one added and one removed, with a file that is not analyzed among them.
+    fs::write(
+        dir.join("a.rs"),
+        "fn main() {\n    let a = 1;\n    let b = a + 1;\n    println!(\"{}\", b);\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("b.rs"),
+        "fn b() {\n    let b = 1;\n    b + 1;\n}\n",
+    )
+    .unwrap();
+    fs::remove_file(dir.join("c.rs")).unwrap();
+    fs::write(dir.join("notes.txt"), "not code").unwrap();
+    for file in ["a.rs", "b.rs", "c.rs", "notes.txt"] {
+        sender.send(dir.join(file)).unwrap();
+    }
+    // Only files that are not analyzed changing reports nothing.
+    sender.send(dir.join("notes.txt")).unwrap();
+    drop(sender);
+    watching.join().unwrap().unwrap();
+
+    let out = shared.lock().unwrap().out.clone();
+    fs::remove_dir_all(&dir).unwrap();
+    let (started, changed) = out.split_once("Watching").unwrap();
+    assert!(started.contains("a.rs") && started.contains("c.rs"));
+    assert_eq!(changed.matches("--- Changed").count(), 1);
+    assert_snapshot!(changed.split_once('\n').unwrap().1);
+}
+
+#[test]
+fn test_watch_changes_are_sent_for_files_written() {
+    let dir = std::env::temp_dir().join(format!("lintric-watch-events-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let (_watcher, changes) =
+        lintric_cli::watch::changes(&[dir.to_string_lossy().into_owned()]).unwrap();
+    std::fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+    let changed = changes
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(changed.file_name().unwrap(), "main.rs");
+}
+
 #[test]
 fn test_debug_ast_rust() {
     let fixture_path = "tests/fixtures/complex_rust_code.rs";
@@ -0,0 +1,105 @@
+//! Cycles in the dependencies of a file, of lines and of the items the lines are in.
+//!
+//! A cycle is a strongly connected component: lines or items each reaching all the others. The line
+//! metrics follow chains of dependencies until they come back on themselves and stop there, so
+//! without this a cycle would not show at all. Lines rarely form one; items do whenever functions
+//! call each other, or one calls itself, which the definition graph leaves out of its edges as it
+//! does every dependency of an item on itself.
+
+use crate::definition_graph::DefinitionGraph;
+use crate::metric_calculator::ir_to_graph;
+use crate::models::{DefinitionCycle, IntermediateRepresentation, LineCycle};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::DiGraph;
+use std::collections::{BTreeSet, HashSet};
+
+/// The cycles of the line graph the metrics are calculated on.
+pub fn find_line_cycles(ir: &IntermediateRepresentation) -> Vec<LineCycle> {
+    let graph = ir_to_graph(ir);
+    let mut cycles: Vec<LineCycle> = tarjan_scc(&graph)
+        .into_iter()
+        .filter(|component| component.len() > 1 || graph.contains_edge(component[0], component[0]))
+        .map(|component| {
+            let lines: BTreeSet<usize> = component.iter().map(|&node| graph[node]).collect();
+            let symbols: BTreeSet<String> = ir
+                .dependencies
+                .iter()
+                .filter(|dependency| {
+                    !dependency.is_cross_file()
+                        && lines.contains(&dependency.source_line)
+                        && lines.contains(&dependency.target_line)
+                })
+                .map(|dependency| dependency.symbol.clone())
+                .collect();
+            LineCycle {
+                lines: lines.into_iter().collect(),
+                symbols: symbols.into_iter().collect(),
+            }
+        })
+        .collect();
+    cycles.sort_by_key(|cycle| cycle.lines[0]);
+    cycles
+}
+
+/// The cycles among the items of a file.
+pub fn find_definition_cycles(
+    ir: &IntermediateRepresentation,
+    definitions: &DefinitionGraph,
+) -> Vec<DefinitionCycle> {
+    let recursive = recursive_items(ir, definitions);
+
+    let mut graph: DiGraph<usize, ()> = DiGraph::new();
+    let nodes: Vec<_> = (0..definitions.items.len())
+        .map(|item| graph.add_node(item))
+        .collect();
+    for edge in &definitions.edges {
+        graph.add_edge(nodes[edge.source], nodes[edge.target], ());
+    }
+
+    let mut cycles: Vec<(usize, DefinitionCycle)> = tarjan_scc(&graph)
+        .into_iter()
+        .filter(|component| component.len() > 1 || recursive.contains(&graph[component[0]]))
+        .map(|component| {
+            let items: BTreeSet<usize> = component.iter().map(|&node| graph[node]).collect();
+            let mut symbols: BTreeSet<String> = definitions
+                .edges
+                .iter()
+                .filter(|edge| items.contains(&edge.source) && items.contains(&edge.target))
+                .flat_map(|edge| edge.symbols.iter().cloned())
+                .collect();
+            for &item in items.iter().filter(|item| recursive.contains(item)) {
+                symbols.insert(definitions.items[item].name.clone());
+            }
+            let first = *items.first().unwrap();
+            let cycle = DefinitionCycle {
+                items: items
+                    .into_iter()
+                    .map(|item| definitions.items[item].qualified_name.clone())
+                    .collect(),
+                symbols: symbols.into_iter().collect(),
+            };
+            (first, cycle)
+        })
+        .collect();
+    cycles.sort_by_key(|(first, _)| *first);
+    cycles.into_iter().map(|(_, cycle)| cycle).collect()
+}
+
+/// The items that use their own name from within, as a function calling itself does.
+fn recursive_items(
+    ir: &IntermediateRepresentation,
+    definitions: &DefinitionGraph,
+) -> HashSet<usize> {
+    ir.dependencies
+        .iter()
+        .filter(|dependency| !dependency.is_cross_file())
+        .filter_map(|dependency| {
+            let item = definitions.enclosing_item(dependency.source_line)?;
+            let definition = &definitions.items[item];
+            (dependency.source_line != definition.line
+                && dependency.target_line == definition.line
+                && dependency.symbol == definition.name)
+                .then_some(item)
+        })
+        .collect()
+}
@@ -0,0 +1,323 @@
+//! Dependencies lifted from lines to the items they are written in, such as functions, methods,
+//! structs, classes and modules, so a file can be asked which functions `parse` depends on.
+//!
+//! An item is a definition that opens a scope of its own, as a function does, or one declared at
+//! the level of a file or module, as a constant is. The scope tree says where each scope begins and
+//! ends; the syntax tree says which definition names it, since a scope's node carries its name.
+//! Each line belongs to the innermost item it is in, and a dependency between lines of two items is
+//! a dependency between the items. Lines in no item, such as imports, take part in none.
+
+use crate::models::{DefinitionType, IntermediateRepresentation, Position, ScopeTree, ScopeType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tree_sitter::{Node, Point};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinitionGraph {
+    pub file_path: String,
+    /// In source order.
+    pub items: Vec<DefinitionItem>,
+    /// By source item, then target item.
+    pub edges: Vec<DefinitionEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinitionItem {
+    pub name: String,
+    /// The name with those of the items, and Rust `impl` blocks, it is declared in, as
+    /// `Parser::parse` in Rust or `Parser.parse` in TypeScript.
+    pub qualified_name: String,
+    pub definition_type: DefinitionType,
+    /// The line the name is on.
+    pub line: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// The item this one is declared in, by index.
+    pub parent: Option<usize>,
+    /// How many other items depend on this one.
+    pub fan_in: usize,
+    /// How many other items this one depends on.
+    pub fan_out: usize,
+}
+
+/// One item depending on another, through one or more symbols.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinitionEdge {
+    /// The dependent item, by index.
+    pub source: usize,
+    /// The item depended on, by index.
+    pub target: usize,
+    /// In name order.
+    pub symbols: Vec<String>,
+}
+
+/// The kinds of definition that are items when declared at the level of a file or module without
+/// opening a scope, as `const LIMIT: usize = 10;` or `type Id = u32;` are.
+const MODULE_LEVEL_ITEM_TYPES: [DefinitionType; 9] = [
+    DefinitionType::FunctionDefinition,
+    DefinitionType::VariableDefinition,
+    DefinitionType::ConstDefinition,
+    DefinitionType::TypeDefinition,
+    DefinitionType::StructDefinition,
+    DefinitionType::EnumDefinition,
+    DefinitionType::ClassDefinition,
+    DefinitionType::InterfaceDefinition,
+    DefinitionType::MacroDefinition,
+];
+
+impl DefinitionGraph {
+    /// The item of a name, qualified or not; of several with the same name, the first.
+    pub fn item(&self, name: &str) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| item.qualified_name == name)
+            .or_else(|| self.items.iter().position(|item| item.name == name))
+    }
+
+    /// The items an item depends on.
+    pub fn dependencies(&self, item: usize) -> Vec<&DefinitionItem> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.source == item)
+            .map(|edge| &self.items[edge.target])
+            .collect()
+    }
+
+    /// The innermost item a line is in.
+    pub fn enclosing_item(&self, line: usize) -> Option<usize> {
+        enclosing(&self.items, line)
+    }
+
+    /// The items that depend on an item.
+    pub fn dependents(&self, item: usize) -> Vec<&DefinitionItem> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.target == item)
+            .map(|edge| &self.items[edge.source])
+            .collect()
+    }
+}
+
+/// An item as found, with the node its span and parent are read from.
+struct Found<'a> {
+    definition: usize,
+    node: Node<'a>,
+}
+
+/// The definition graph of a file from its IR, the scopes its analysis found, its source and its
+/// syntax tree.
+pub fn build(
+    ir: &IntermediateRepresentation,
+    scopes: &ScopeTree,
+    source: &str,
+    root: Node,
+) -> DefinitionGraph {
+    let separator = if ir.analysis_metadata.language == "Rust" {
+        "::"
+    } else {
+        "."
+    };
+    let by_position: BTreeMap<Position, usize> = ir
+        .definitions
+        .iter()
+        .enumerate()
+        .map(|(index, definition)| (definition.position, index))
+        .collect();
+
+    let mut found: Vec<Found> = Vec::new();
+    let mut scope_ids: Vec<_> = scopes.scopes.keys().copied().collect();
+    scope_ids.sort_unstable();
+    for scope_id in scope_ids {
+        let scope = &scopes.scopes[&scope_id];
+        if scope_id == scopes.root {
+            continue;
+        }
+        let Some(node) = node_at(root, &scope.position) else {
+            continue;
+        };
+        let Some(definition) = scope_name(node)
+            .and_then(|name| by_position.get(&Position::from_node(&name)))
+            .copied()
+        else {
+            continue;
+        };
+        if !found.iter().any(|item| item.definition == definition) {
+            found.push(Found { definition, node });
+        }
+    }
+
+    for (index, definition) in ir.definitions.iter().enumerate() {
+        if found.iter().any(|item| item.definition == index)
+            || !MODULE_LEVEL_ITEM_TYPES.contains(&definition.definition_type)
+        {
+            continue;
+        }
+        let Some(scope) = definition.scope_id.and_then(|id| scopes.get_scope(id)) else {
+            continue;
+        };
+        let container = if scope.id == scopes.root {
+            Some(root)
+        } else if scope.scope_type == ScopeType::Module {
+            node_at(root, &scope.position)
+                .map(|node| node.child_by_field_name("body").unwrap_or(node))
+        } else {
+            None
+        };
+        let Some(container) = container else {
+            continue;
+        };
+        if let Some(node) =
+            node_at(root, &definition.position).and_then(|name| declaration_in(name, container))
+        {
+            found.push(Found {
+                definition: index,
+                node,
+            });
+        }
+    }
+
+    found.sort_by_key(|item| {
+        (
+            item.node.start_byte(),
+            ir.definitions[item.definition].position,
+        )
+    });
+    let index_of_node: HashMap<usize, usize> = found
+        .iter()
+        .enumerate()
+        .map(|(index, item)| (item.node.id(), index))
+        .collect();
+
+    let mut items: Vec<DefinitionItem> = Vec::with_capacity(found.len());
+    for item in &found {
+        let definition = &ir.definitions[item.definition];
+        let mut qualifiers = Vec::new();
+        let mut parent = None;
+        let mut ancestor = item.node.parent();
+        while let Some(node) = ancestor {
+            if let Some(&index) = index_of_node.get(&node.id()) {
+                parent = Some(index);
+                break;
+            }
+            if node.kind() == "impl_item" {
+                if let Some(impl_type) = node
+                    .child_by_field_name("type")
+                    .and_then(|impl_type| impl_type.utf8_text(source.as_bytes()).ok())
+                {
+                    qualifiers.push(impl_type.to_string());
+                }
+            }
+            ancestor = node.parent();
+        }
+        qualifiers.reverse();
+        let mut segments: Vec<String> = parent
+            .map(|parent: usize| items[parent].qualified_name.clone())
+            .into_iter()
+            .collect();
+        segments.extend(qualifiers);
+        segments.push(definition.name.clone());
+
+        items.push(DefinitionItem {
+            name: definition.name.clone(),
+            qualified_name: segments.join(separator),
+            definition_type: definition.definition_type.clone(),
+            line: definition.position.start_line,
+            start_line: item.node.start_position().row + 1,
+            end_line: item.node.end_position().row + 1,
+            parent,
+            fan_in: 0,
+            fan_out: 0,
+        });
+    }
+
+    let mut symbols: BTreeMap<(usize, usize), BTreeSet<String>> = BTreeMap::new();
+    for dependency in ir
+        .dependencies
+        .iter()
+        .filter(|dependency| !dependency.is_cross_file())
+    {
+        let (Some(source), Some(target)) = (
+            enclosing(&items, dependency.source_line),
+            enclosing(&items, dependency.target_line),
+        ) else {
+            continue;
+        };
+        if source != target {
+            symbols
+                .entry((source, target))
+                .or_default()
+                .insert(dependency.symbol.clone());
+        }
+    }
+
+    let edges: Vec<DefinitionEdge> = symbols
+        .into_iter()
+        .map(|((source, target), symbols)| DefinitionEdge {
+            source,
+            target,
+            symbols: symbols.into_iter().collect(),
+        })
+        .collect();
+    for edge in &edges {
+        items[edge.source].fan_out += 1;
+        items[edge.target].fan_in += 1;
+    }
+
+    DefinitionGraph {
+        file_path: ir.file_path.clone(),
+        items,
+        edges,
+    }
+}
+
+/// The smallest node spanning a position.
+fn node_at<'a>(root: Node<'a>, position: &Position) -> Option<Node<'a>> {
+    let point = |line: usize, column: usize| Point {
+        row: line.saturating_sub(1),
+        column: column.saturating_sub(1),
+    };
+    root.descendant_for_point_range(
+        point(position.start_line, position.start_column),
+        point(position.end_line, position.end_column),
+    )
+}
+
+/// The name of what a scope's node declares: its own, or, for a function written as the value of a
+/// variable or field, as `const parse = () => {}` is, that of the variable or field.
+fn scope_name(node: Node) -> Option<Node> {
+    if let Some(name) = node.child_by_field_name("name") {
+        return Some(name);
+    }
+    let parent = node.parent()?;
+    let is_value = parent
+        .child_by_field_name("value")
+        .is_some_and(|value| value.id() == node.id());
+    if is_value {
+        parent.child_by_field_name("name")
+    } else {
+        None
+    }
+}
+
+/// The declaration a name is written in: the node holding it that is written directly in
+/// `container`, the body of a file or module.
+fn declaration_in<'a>(name: Node<'a>, container: Node<'a>) -> Option<Node<'a>> {
+    let mut node = name;
+    loop {
+        let parent = node.parent()?;
+        if parent.id() == container.id() {
+            return Some(node);
+        }
+        node = parent;
+    }
+}
+
+/// The innermost item a line is in.
+fn enclosing(items: &[DefinitionItem], line: usize) -> Option<usize> {
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.start_line <= line && line <= item.end_line)
+        .min_by_key(|(index, item)| (item.end_line - item.start_line, usize::MAX - index))
+        .map(|(index, _)| index)
+}
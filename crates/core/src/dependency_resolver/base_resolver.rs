@@ -8,6 +8,41 @@ pub trait DependencyResolver: Send + Sync {
         root_node: Node,
         usage_nodes: &[Usage],
         definitions: &[Definition],
+    ) -> Result<Vec<Dependency>, String> {
+        let mut dependencies: Vec<Dependency> = self
+            .resolve_usages(
+                source_code,
+                root_node,
+                usage_nodes,
+                usage_nodes,
+                definitions,
+            )?
+            .into_iter()
+            .flatten()
+            .collect();
+        dependencies.extend(self.resolve_structure(source_code, root_node, definitions)?);
+        Ok(dependencies)
+    }
+
+    /// The dependencies of each of `resolved`, in order, among the file's `usage_nodes`.
+    ///
+    /// A usage resolves the same way whichever others are resolved with it, so an analysis that
+    /// keeps what it resolved before need only resolve the usages an edit could have changed.
+    fn resolve_usages(
+        &self,
+        source_code: &str,
+        root_node: Node,
+        resolved: &[Usage],
+        usage_nodes: &[Usage],
+        definitions: &[Definition],
+    ) -> Result<Vec<Vec<Dependency>>, String>;
+
+    /// The dependencies no usage accounts for, derived from the structure of the file instead.
+    fn resolve_structure(
+        &self,
+        source_code: &str,
+        root_node: Node,
+        definitions: &[Definition],
     ) -> Result<Vec<Dependency>, String>;
 
     /// Classify a resolved dependency.
@@ -34,7 +69,9 @@ pub trait DependencyResolver: Send + Sync {
     }
 }
 
-fn dependency_type_of_definition(definition_type: &DefinitionType) -> Option<DependencyType> {
+pub(crate) fn dependency_type_of_definition(
+    definition_type: &DefinitionType,
+) -> Option<DependencyType> {
     match definition_type {
         DefinitionType::FunctionDefinition | DefinitionType::MethodDefinition => {
             Some(DependencyType::FunctionCall)
@@ -1,4 +1,4 @@
-use crate::models::{Dependency, DependencyType};
+use crate::models::{Dependency, DependencyType, Position};
 use crate::query::map_pairs;
 use std::collections::{HashMap, HashSet, VecDeque};
 use tree_sitter::Node;
@@ -44,7 +44,7 @@ pub fn resolve(
         methods(queries.implementations, source_code, root_node, &roles)?
             .iter()
             .filter_map(|method| {
-                declaration_line(&declared, &inherited, method).map(|line| dependency(method, line))
+                declaration(&declared, &inherited, method).map(|target| dependency(method, target))
             })
             .filter(|dependency| dependency.source_line != dependency.target_line)
             .collect(),
@@ -56,7 +56,8 @@ struct Method {
     type_name: String,
     name: String,
     role: Role,
-    line: usize,
+    /// Where its name is written.
+    position: Position,
 }
 
 /// Which half of an accessor pair a member is, where the language has them.
@@ -116,18 +117,18 @@ impl Roles {
     }
 }
 
-/// The line declaring this method, looked up on the named type and then on what it inherits.
+/// Where the method is declared, looked up on the named type and then on what it inherits.
 ///
 /// The search is breadth first, so the nearest declaration wins when a type and one of its
 /// supertypes both declare the method. The visited set also keeps an inheritance cycle — invalid
 /// but parseable — from looping.
 type DeclarationKey = (String, String, Role);
 
-fn declaration_line(
-    declared: &HashMap<DeclarationKey, usize>,
+fn declaration(
+    declared: &HashMap<DeclarationKey, Position>,
     inherited: &HashMap<String, Vec<String>>,
     method: &Method,
-) -> Option<usize> {
+) -> Option<Position> {
     let mut visited: HashSet<&str> = HashSet::new();
     let mut pending: VecDeque<&str> = VecDeque::from([method.type_name.as_str()]);
 
@@ -136,9 +137,10 @@ fn declaration_line(
             continue;
         }
 
-        if let Some(line) = declared.get(&(type_name.to_string(), method.name.clone(), method.role))
+        if let Some(position) =
+            declared.get(&(type_name.to_string(), method.name.clone(), method.role))
         {
-            return Some(*line);
+            return Some(*position);
         }
 
         if let Some(supertypes) = inherited.get(type_name) {
@@ -154,10 +156,15 @@ fn declarations(
     source_code: &str,
     root_node: Node,
     roles: &Roles,
-) -> Result<HashMap<DeclarationKey, usize>, String> {
+) -> Result<HashMap<DeclarationKey, Position>, String> {
     Ok(methods(query_source, source_code, root_node, roles)?
         .into_iter()
-        .map(|method| ((method.type_name, method.name, method.role), method.line))
+        .map(|method| {
+            (
+                (method.type_name, method.name, method.role),
+                method.position,
+            )
+        })
         .collect())
 }
 
@@ -209,7 +216,7 @@ fn method(source_code: &str, type_node: Node, method_node: Node, roles: &Roles)
         type_name: type_text(source_code, type_node)?,
         name: text(source_code, method_node)?,
         role: roles.of(method_node),
-        line: method_node.start_position().row + 1,
+        position: Position::from_node(&method_node),
     })
 }
 
@@ -234,12 +241,15 @@ fn text(source_code: &str, node: Node) -> Option<String> {
         .map(str::to_string)
 }
 
-fn dependency(method: &Method, target_line: usize) -> Dependency {
+fn dependency(method: &Method, target: Position) -> Dependency {
     Dependency {
-        source_line: method.line,
-        target_line,
+        source_line: method.position.start_line,
+        target_line: target.start_line,
+        source_span: method.position,
+        target_span: target,
         symbol: method.name.clone(),
         dependency_type: DependencyType::TraitImplementation,
         context: Some(format!("trait_implementation::{}", method.type_name)),
+        target_file: None,
     }
 }
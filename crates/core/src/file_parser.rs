@@ -40,7 +40,17 @@ impl FileParser {
         Ok(formatter.format_node(tree.root_node(), 0))
     }
 
+    /// Parse the content again after an edit, reusing what is unchanged of `edited`, the tree of the
+    /// content before it, which must already have been told of the edit with `Tree::edit`.
+    pub fn reparse(&self, edited: &Tree) -> Result<Tree, String> {
+        self.parse_file_from(Some(edited))
+    }
+
     fn parse_file(&self) -> Result<Tree, String> {
+        self.parse_file_from(None)
+    }
+
+    fn parse_file_from(&self, old_tree: Option<&Tree>) -> Result<Tree, String> {
         let mut parser = TreeSitterParser::new();
 
         let lang = &self.language.get_tree_sitter_language();
@@ -50,7 +60,7 @@ impl FileParser {
             .map_err(|e| format!("Error loading grammar: {e}"))?;
 
         let tree = parser
-            .parse(&self.file_content, None)
+            .parse(&self.file_content, old_tree)
             .ok_or_else(|| "Failed to parse the source code.".to_string())?;
 
         Ok(tree)
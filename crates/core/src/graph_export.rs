@@ -0,0 +1,201 @@
+//! The dependency graph of a file as Graphviz DOT or Mermaid source, for pictures in design docs
+//! and reviews.
+//!
+//! The line graph is the one the metrics are calculated on: one edge from each line to each line it
+//! depends on. The symbol graph keeps what the line graph collapses, with an edge for every symbol
+//! a line uses from another, labelled with the symbol and the kind of dependency and coloured by
+//! the kind. Either way only lines with an edge are drawn, each labelled with its source.
+
+use crate::metric_calculator::ir_to_graph;
+use crate::models::{DependencyType, IntermediateRepresentation};
+use petgraph::visit::EdgeRef;
+use std::collections::BTreeMap;
+
+/// How much of a line's source a node shows before it is cut short.
+const MAX_LABEL_LENGTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    /// One edge per pair of lines, as the metrics see them.
+    Lines,
+    /// One edge per symbol and kind of dependency between two lines.
+    Symbols,
+}
+
+/// An edge to draw, with its label and colour in the symbol graph.
+struct Edge {
+    source: usize,
+    target: usize,
+    label: Option<String>,
+    color: Option<&'static str>,
+}
+
+/// The graph of `ir`, whose source is `content`, written in `format`.
+pub fn export_graph(
+    ir: &IntermediateRepresentation,
+    content: &str,
+    kind: GraphKind,
+    format: GraphFormat,
+) -> String {
+    let edges = match kind {
+        GraphKind::Lines => line_edges(ir),
+        GraphKind::Symbols => symbol_edges(ir),
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let nodes: BTreeMap<usize, String> = edges
+        .iter()
+        .flat_map(|edge| [edge.source, edge.target])
+        .map(|line| (line, node_label(line, &lines)))
+        .collect();
+
+    match format {
+        GraphFormat::Dot => dot(&nodes, &edges),
+        GraphFormat::Mermaid => mermaid(&nodes, &edges),
+    }
+}
+
+/// The name a kind of dependency goes by in labels.
+pub fn dependency_type_name(dependency_type: &DependencyType) -> String {
+    dependency_type.to_string()
+}
+
+/// The colour edges of a kind of dependency are drawn in, the same in either format.
+pub fn dependency_type_color(dependency_type: &DependencyType) -> &'static str {
+    match dependency_type {
+        DependencyType::FunctionCall => "#1f77b4",
+        DependencyType::VariableUse => "#2ca02c",
+        DependencyType::Import => "#9467bd",
+        DependencyType::StructFieldAccess => "#ff7f0e",
+        DependencyType::TypeReference => "#17becf",
+        DependencyType::ModuleReference => "#8c564b",
+        DependencyType::MacroInvocation => "#d62728",
+        DependencyType::MacroVariable => "#e377c2",
+        DependencyType::EnumVariantReference => "#bcbd22",
+        DependencyType::TraitImplementation => "#393b79",
+        DependencyType::Other(_) => "#7f7f7f",
+    }
+}
+
+fn line_edges(ir: &IntermediateRepresentation) -> Vec<Edge> {
+    let graph = ir_to_graph(ir);
+    let mut edges: Vec<Edge> = graph
+        .edge_references()
+        .map(|edge| Edge {
+            source: graph[edge.source()],
+            target: graph[edge.target()],
+            label: None,
+            color: None,
+        })
+        .collect();
+    edges.sort_by_key(|edge| (edge.source, edge.target));
+    edges
+}
+
+fn symbol_edges(ir: &IntermediateRepresentation) -> Vec<Edge> {
+    // `DependencyType` has no order of its own, so edges are ordered by the name of theirs.
+    let mut unique: BTreeMap<(usize, usize, &str, String), &DependencyType> = BTreeMap::new();
+    for dependency in ir
+        .dependencies
+        .iter()
+        .filter(|dependency| !dependency.is_cross_file())
+    {
+        unique
+            .entry((
+                dependency.source_line,
+                dependency.target_line,
+                dependency.symbol.as_str(),
+                dependency_type_name(&dependency.dependency_type),
+            ))
+            .or_insert(&dependency.dependency_type);
+    }
+
+    unique
+        .into_iter()
+        .map(
+            |((source, target, symbol, type_name), dependency_type)| Edge {
+                source,
+                target,
+                label: Some(format!("{symbol}: {type_name}")),
+                color: Some(dependency_type_color(dependency_type)),
+            },
+        )
+        .collect()
+}
+
+/// A line's number and source, trimmed and cut short.
+fn node_label(line: usize, lines: &[&str]) -> String {
+    let text = line
+        .checked_sub(1)
+        .and_then(|index| lines.get(index))
+        .map_or("", |text| text.trim());
+    let text = if text.chars().count() > MAX_LABEL_LENGTH {
+        let cut: String = text.chars().take(MAX_LABEL_LENGTH).collect();
+        format!("{cut}…")
+    } else {
+        text.to_string()
+    };
+    format!("{line}: {text}")
+}
+
+fn dot(nodes: &BTreeMap<usize, String>, edges: &[Edge]) -> String {
+    let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = String::from("digraph dependencies {\n");
+    out.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+    for (line, label) in nodes {
+        out.push_str(&format!("    L{line} [label=\"{}\"];\n", escape(label)));
+    }
+    for edge in edges {
+        let mut attributes = Vec::new();
+        if let Some(label) = &edge.label {
+            attributes.push(format!("label=\"{}\"", escape(label)));
+        }
+        if let Some(color) = edge.color {
+            attributes.push(format!("color=\"{color}\", fontcolor=\"{color}\""));
+        }
+        if attributes.is_empty() {
+            out.push_str(&format!("    L{} -> L{};\n", edge.source, edge.target));
+        } else {
+            out.push_str(&format!(
+                "    L{} -> L{} [{}];\n",
+                edge.source,
+                edge.target,
+                attributes.join(", ")
+            ));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn mermaid(nodes: &BTreeMap<usize, String>, edges: &[Edge]) -> String {
+    let escape = |text: &str| text.replace('"', "#quot;");
+    let mut out = String::from("flowchart TD\n");
+    for (line, label) in nodes {
+        out.push_str(&format!("    L{line}[\"{}\"]\n", escape(label)));
+    }
+    for edge in edges {
+        match &edge.label {
+            Some(label) => out.push_str(&format!(
+                "    L{} -->|\"{}\"| L{}\n",
+                edge.source,
+                escape(label),
+                edge.target
+            )),
+            None => out.push_str(&format!("    L{} --> L{}\n", edge.source, edge.target)),
+        }
+    }
+    for (index, edge) in edges.iter().enumerate() {
+        if let Some(color) = edge.color {
+            out.push_str(&format!(
+                "    linkStyle {index} stroke:{color},color:{color}\n"
+            ));
+        }
+    }
+    out
+}
@@ -480,9 +480,12 @@ impl MethodResolver {
                     let dependency = Dependency {
                         source_line,
                         target_line,
+                        source_span: usage_node.position,
+                        target_span: definition.position,
                         symbol: field_name.clone(),
                         dependency_type: crate::models::DependencyType::StructFieldAccess,
                         context: Some("field_access".to_string()),
+                        target_file: None,
                     };
                     dependencies.push(dependency);
                 }
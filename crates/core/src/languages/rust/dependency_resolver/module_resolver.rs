@@ -93,6 +93,16 @@ impl ModuleResolver {
             .find_symbol_in_imports(symbol, current_module)
     }
 
+    /// The item a `use` written in `current_module` brings in, with the module declaring it.
+    pub fn resolve_imported_item(
+        &self,
+        import: &ImportInfo,
+        current_module: ModuleId,
+    ) -> Option<(ModuleId, Definition)> {
+        self.import_resolver
+            .resolve_imported_item(import, current_module)
+    }
+
     pub fn is_accessible(
         &self,
         definition: &Definition,
@@ -222,22 +232,109 @@ impl ImportResolver {
         None
     }
 
-    fn resolve_module_path(&self, path: &str, current_module: ModuleId) -> Option<ModuleId> {
-        // Handle relative paths like "super", "crate", etc.
-        if let Some(remaining_path) = path.strip_prefix("super::") {
-            if let Some(current) = self.module_tree.modules.get(&current_module) {
-                if let Some(parent_id) = current.parent {
-                    return self.resolve_relative_path(remaining_path, parent_id);
+    /// The module a `use` path names, read from the module the `use` is written in.
+    ///
+    /// `crate`, `self` and any number of leading `super`s anchor the path; otherwise it starts
+    /// among the current module's children, as a 2018-edition path does, and failing that at the
+    /// crate root. A path naming neither, such as `std::collections`, is another crate's and `None`.
+    pub fn resolve_module_path(&self, path: &str, current_module: ModuleId) -> Option<ModuleId> {
+        let mut segments: Vec<&str> = path.split("::").filter(|s| !s.is_empty()).collect();
+
+        let anchor = match segments.first() {
+            None => return Some(current_module),
+            Some(&"crate") => {
+                segments.remove(0);
+                self.module_tree.root_module
+            }
+            Some(&"self") => {
+                segments.remove(0);
+                current_module
+            }
+            Some(&"super") => {
+                let mut anchor = current_module;
+                while segments.first() == Some(&"super") {
+                    segments.remove(0);
+                    anchor = self.module_tree.modules.get(&anchor)?.parent?;
                 }
+                anchor
             }
-        } else if let Some(remaining_path) = path.strip_prefix("crate::") {
-            return self.resolve_relative_path(remaining_path, self.module_tree.root_module);
-        } else {
-            // Absolute or relative path
-            return self.module_tree.find_module_by_path(path);
+            Some(_) => {
+                return self
+                    .resolve_relative_path(path, current_module)
+                    .or_else(|| self.resolve_relative_path(path, self.module_tree.root_module));
+            }
+        };
+
+        self.resolve_relative_path(&segments.join("::"), anchor)
+    }
+
+    /// The item a `use` brings in, following re-exports to where it is declared.
+    ///
+    /// Gives the declaring module alongside the declaration, since a `Definition` does not record
+    /// which file it is in and the module does.
+    pub fn resolve_imported_item(
+        &self,
+        import: &ImportInfo,
+        current_module: ModuleId,
+    ) -> Option<(ModuleId, Definition)> {
+        match import.import_type {
+            ImportType::Wildcard => None,
+            _ => self.resolve_item(
+                &import.source_module,
+                &import.imported_symbol,
+                current_module,
+                MAX_REEXPORT_HOPS,
+            ),
+        }
+    }
+
+    fn resolve_item(
+        &self,
+        module_path: &str,
+        symbol: &str,
+        current_module: ModuleId,
+        hops: usize,
+    ) -> Option<(ModuleId, Definition)> {
+        let module_id = self.resolve_module_path(module_path, current_module)?;
+        self.find_item(module_id, symbol, hops)
+    }
+
+    /// A name declared in a module, or reached through a `use` written there.
+    fn find_item(
+        &self,
+        module_id: ModuleId,
+        symbol: &str,
+        hops: usize,
+    ) -> Option<(ModuleId, Definition)> {
+        let module = self.module_tree.modules.get(&module_id)?;
+        if let Some(definition) = module.exports.get(symbol) {
+            return Some((module_id, definition.clone()));
+        }
+        if hops == 0 {
+            return None;
         }
 
-        None
+        // A named re-export is preferred to a glob, as the compiler does.
+        let named = module.imports.iter().find(|import| {
+            !matches!(import.import_type, ImportType::Wildcard) && local_name(import) == symbol
+        });
+        if let Some(import) = named {
+            return self.resolve_item(
+                &import.source_module,
+                &import.imported_symbol,
+                module_id,
+                hops - 1,
+            );
+        }
+
+        module
+            .imports
+            .iter()
+            .filter(|import| matches!(import.import_type, ImportType::Wildcard))
+            .find_map(|import| {
+                let glob_module = self.resolve_module_path(&import.source_module, module_id)?;
+                self.find_item(glob_module, symbol, hops - 1)
+            })
     }
 
     fn resolve_relative_path(&self, path: &str, from_module: ModuleId) -> Option<ModuleId> {
@@ -263,6 +360,17 @@ impl ImportResolver {
     }
 }
 
+/// How many `use`s deep a re-export is followed before giving up.
+///
+/// Deep enough for any chain of `pub use` a crate would write, and a bound all the same, since two
+/// globs importing each other would otherwise never finish.
+const MAX_REEXPORT_HOPS: usize = 8;
+
+/// The name a `use` introduces where it is written: its alias if it has one.
+fn local_name(import: &ImportInfo) -> &str {
+    import.alias.as_deref().unwrap_or(&import.imported_symbol)
+}
+
 impl VisibilityChecker {
     pub fn new(
         module_tree: ModuleTree,
@@ -59,12 +59,15 @@ impl RustDependencyResolver {
                 let dependency = Dependency {
                     source_line: import_def.position.start_line,
                     target_line: original_def.position.start_line,
+                    source_span: import_def.position,
+                    target_span: original_def.position,
                     symbol: import_def.name.clone(),
                     dependency_type: crate::models::DependencyType::Import,
                     context: Some(format!(
                         "ImportDefinition:{}:{}",
                         import_def.position.start_line, import_def.position.start_column
                     )),
+                    target_file: None,
                 };
                 import_dependencies.push(dependency);
             }
@@ -75,49 +78,23 @@ impl RustDependencyResolver {
 }
 
 impl DependencyResolverTrait for RustDependencyResolver {
-    fn resolve_dependencies(
+    fn resolve_usages(
         &self,
         source_code: &str,
         root_node: Node,
+        resolved: &[Usage],
         usage_nodes: &[Usage],
         definitions: &[Definition],
-    ) -> Result<Vec<Dependency>, String> {
-        // Use basic resolution with fixed priorities
-        let mut dependencies =
-            self.resolve_basic_dependencies(source_code, root_node, usage_nodes, definitions)?;
-
-        // Add import definition dependencies (ImportDefinition -> original definition)
-        let import_deps = self.resolve_import_dependencies(definitions);
-        dependencies.extend(import_deps);
-
-        // Add trait implementation dependencies (impl method -> trait declaration), which have no
-        // usage to resolve and are derived from the impl block's structure instead
-        let trait_impl_deps =
-            super::trait_implementation_resolver::resolve(source_code, root_node)?;
-        dependencies.extend(trait_impl_deps);
-
-        Ok(dependencies)
-    }
-}
-
-impl RustDependencyResolver {
-    /// Basic fallback resolution for cases where advanced resolution fails
-    fn resolve_basic_dependencies(
-        &self,
-        source_code: &str,
-        root_node: Node,
-        usage_nodes: &[Usage],
-        definitions: &[Definition],
-    ) -> Result<Vec<Dependency>, String> {
+    ) -> Result<Vec<Vec<Dependency>>, String> {
         // Read off the file once rather than per usage: every method call asks the same questions of
         // it, and a malformed query must fail rather than quietly resolve nothing.
         let narrowing =
             ReceiverNarrowing::new(&super::receiver_narrowing::DIALECT, source_code, root_node)?;
         let own = SelfReference::new(OWN_INITIALIZERS, source_code, root_node)?;
 
-        Ok(usage_nodes
+        Ok(resolved
             .iter()
-            .flat_map(|usage_node| {
+            .map(|usage_node| {
                 self.resolve_single_dependency_with_scope_aware_external_filtering(
                     &narrowing,
                     &own,
@@ -129,6 +106,26 @@ impl RustDependencyResolver {
             .collect())
     }
 
+    fn resolve_structure(
+        &self,
+        source_code: &str,
+        root_node: Node,
+        definitions: &[Definition],
+    ) -> Result<Vec<Dependency>, String> {
+        // Add import definition dependencies (ImportDefinition -> original definition)
+        let mut dependencies = self.resolve_import_dependencies(definitions);
+
+        // Add trait implementation dependencies (impl method -> trait declaration), which have no
+        // usage to resolve and are derived from the impl block's structure instead
+        let trait_impl_deps =
+            super::trait_implementation_resolver::resolve(source_code, root_node)?;
+        dependencies.extend(trait_impl_deps);
+
+        Ok(dependencies)
+    }
+}
+
+impl RustDependencyResolver {
     fn resolve_single_dependency_with_scope_aware_external_filtering(
         &self,
         narrowing: &ReceiverNarrowing,
@@ -179,9 +176,12 @@ impl RustDependencyResolver {
                 dependencies.push(Dependency {
                     source_line,
                     target_line,
+                    source_span: usage_node.position,
+                    target_span: def.position,
                     symbol: usage_node.name.clone(),
                     dependency_type: self.get_dependency_type(usage_node, def),
                     context: self.get_context(usage_node),
+                    target_file: None,
                 });
             }
         }
@@ -4,6 +4,7 @@ pub mod definition_queries;
 pub mod dependency_resolver;
 pub mod format_string;
 pub mod formatter;
+pub mod module_items;
 pub mod pattern_bindings;
 pub mod scope_queries;
 pub mod self_type;
@@ -0,0 +1,303 @@
+//! What a Rust file contributes to its crate's module tree.
+//!
+//! A file holds the items of one module and of any modules written inline in it, and declares the
+//! modules whose items live in other files with `mod name;`. Reading those off the syntax tree is
+//! what lets a `use` in one file be followed to the declaration in another.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use tree_sitter::Node;
+
+use crate::models::{
+    Definition, ImportInfo, ImportType, ModuleId, ModuleTree, Position, Visibility,
+};
+
+/// Items a module declares under a name another module can `use`.
+const NAMED_ITEMS: [&str; 11] = [
+    "function_item",
+    "function_signature_item",
+    "struct_item",
+    "union_item",
+    "enum_item",
+    "trait_item",
+    "type_item",
+    "const_item",
+    "static_item",
+    "mod_item",
+    "macro_definition",
+];
+
+/// A module declared with `mod name;`, whose items are in a file of their own.
+#[derive(Debug, Clone)]
+pub struct OutOfLineModule {
+    pub module: ModuleId,
+    /// The file the module's items were found in, if one of the candidates exists.
+    pub file_path: Option<PathBuf>,
+}
+
+/// Add one file's modules, items and `use`s to the tree, under the module the file holds.
+///
+/// `module_dir` is where `mod name;` looks for `name.rs` and `name/mod.rs`: the file's own
+/// directory for a crate root or a `mod.rs`, and a directory named after the module otherwise.
+/// Definitions are matched to items by the position of their name, so they must be the ones
+/// analysis produced for this same file.
+pub fn collect(
+    module_tree: &mut ModuleTree,
+    module: ModuleId,
+    module_dir: &Path,
+    root_node: Node,
+    source_code: &str,
+    definitions: &[Definition],
+) -> Vec<OutOfLineModule> {
+    let by_position: HashMap<(usize, usize), &Definition> = definitions
+        .iter()
+        .map(|definition| {
+            (
+                (
+                    definition.position.start_line,
+                    definition.position.start_column,
+                ),
+                definition,
+            )
+        })
+        .collect();
+
+    let mut collector = Collector {
+        module_tree,
+        source_code,
+        by_position,
+        out_of_line: Vec::new(),
+    };
+    collector.collect_body(module, module_dir, root_node);
+    collector.out_of_line
+}
+
+/// The directory a module file's own `mod name;` declarations are relative to.
+pub fn module_dir(file_path: &Path, is_crate_root: bool) -> PathBuf {
+    let parent = file_path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let owns_directory = is_crate_root
+        || file_path
+            .file_name()
+            .is_some_and(|name| name == "mod.rs" || name == "lib.rs" || name == "main.rs");
+
+    match file_path.file_stem() {
+        Some(stem) if !owns_directory => parent.join(stem),
+        _ => parent,
+    }
+}
+
+struct Collector<'t, 's, 'd> {
+    module_tree: &'t mut ModuleTree,
+    source_code: &'s str,
+    by_position: HashMap<(usize, usize), &'d Definition>,
+    out_of_line: Vec<OutOfLineModule>,
+}
+
+impl Collector<'_, '_, '_> {
+    fn collect_body(&mut self, module: ModuleId, module_dir: &Path, body: Node) {
+        let mut cursor = body.walk();
+        for item in body.named_children(&mut cursor) {
+            if NAMED_ITEMS.contains(&item.kind()) {
+                self.export(module, item);
+            }
+            match item.kind() {
+                "mod_item" => self.collect_module(module, module_dir, item),
+                "use_declaration" => self.collect_use(module, item),
+                _ => {}
+            }
+        }
+    }
+
+    fn export(&mut self, module: ModuleId, item: Node) {
+        let Some(name) = item.child_by_field_name("name") else {
+            return;
+        };
+        let position = Position::from_node(&name);
+        if let Some(definition) = self
+            .by_position
+            .get(&(position.start_line, position.start_column))
+        {
+            // The first declaration of a name wins, which for a type and a function sharing one
+            // is whichever comes first; `use` does not say which namespace it means.
+            let declared = self
+                .module_tree
+                .modules
+                .get(&module)
+                .is_some_and(|module| module.exports.contains_key(&definition.name));
+            if !declared {
+                self.module_tree
+                    .add_export(module, definition.name.clone(), (*definition).clone());
+            }
+        }
+    }
+
+    fn collect_module(&mut self, parent: ModuleId, parent_dir: &Path, item: Node) {
+        let Some(name) = item
+            .child_by_field_name("name")
+            .and_then(|name| name.utf8_text(self.source_code.as_bytes()).ok())
+        else {
+            return;
+        };
+        let name = name.to_string();
+        let dir = parent_dir.join(&name);
+
+        match item.child_by_field_name("body") {
+            Some(body) => {
+                let file_path = self.module_tree.modules[&parent].file_path.clone();
+                let module = self.module_tree.add_module(name, Some(parent), file_path);
+                self.collect_body(module, &dir, body);
+            }
+            None => {
+                let file_path = [parent_dir.join(format!("{name}.rs")), dir.join("mod.rs")]
+                    .into_iter()
+                    .find(|candidate| candidate.is_file());
+                let module = self.module_tree.add_module(
+                    name,
+                    Some(parent),
+                    file_path
+                        .as_ref()
+                        .map(|path| path.to_string_lossy().into_owned()),
+                );
+                self.out_of_line.push(OutOfLineModule { module, file_path });
+            }
+        }
+    }
+
+    fn collect_use(&mut self, module: ModuleId, item: Node) {
+        let visibility = if has_child_of_kind(item, "visibility_modifier") {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        };
+        let Some(argument) = item.child_by_field_name("argument") else {
+            return;
+        };
+
+        let mut imports = Vec::new();
+        self.use_tree(argument, &[], &visibility, &mut imports);
+        for import in imports {
+            self.module_tree.add_import(module, import);
+        }
+    }
+
+    /// Flatten a `use` tree into one import per name it introduces.
+    fn use_tree(
+        &self,
+        node: Node,
+        prefix: &[String],
+        visibility: &Visibility,
+        imports: &mut Vec<ImportInfo>,
+    ) {
+        match node.kind() {
+            "use_list" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.use_tree(child, prefix, visibility, imports);
+                }
+            }
+            "scoped_use_list" => {
+                let mut nested = prefix.to_vec();
+                if let Some(path) = node.child_by_field_name("path") {
+                    nested.extend(self.segments(path));
+                }
+                if let Some(list) = node.child_by_field_name("list") {
+                    self.use_tree(list, &nested, visibility, imports);
+                }
+            }
+            "use_wildcard" => {
+                let mut source = prefix.to_vec();
+                if let Some(path) = node.named_child(0) {
+                    source.extend(self.segments(path));
+                }
+                imports.push(ImportInfo {
+                    imported_symbol: "*".to_string(),
+                    source_module: source.join("::"),
+                    alias: None,
+                    import_type: ImportType::Wildcard,
+                    visibility: visibility.clone(),
+                    position: Position::from_node(&node),
+                });
+            }
+            "use_as_clause" => {
+                let (Some(path), Some(alias)) = (
+                    node.child_by_field_name("path"),
+                    node.child_by_field_name("alias"),
+                ) else {
+                    return;
+                };
+                if let Some(mut import) = self.named_import(path, prefix, visibility) {
+                    import.alias = self.text(alias);
+                    import.position = Position::from_node(&alias);
+                    imports.push(import);
+                }
+            }
+            _ => imports.extend(self.named_import(node, prefix, visibility)),
+        }
+    }
+
+    /// The import a plain path introduces: its last segment, from the module the rest names.
+    ///
+    /// `self` in a braced list imports the module the list is written under, by that module's name.
+    fn named_import(
+        &self,
+        path: Node,
+        prefix: &[String],
+        visibility: &Visibility,
+    ) -> Option<ImportInfo> {
+        let mut segments = prefix.to_vec();
+        segments.extend(self.segments(path));
+        let name_node = match path.kind() {
+            "scoped_identifier" => path.child_by_field_name("name")?,
+            _ => path,
+        };
+
+        let (imported_symbol, import_type) = if path.kind() == "self" {
+            segments.pop();
+            (segments.last()?.clone(), ImportType::Module)
+        } else {
+            let name = segments.last()?.clone();
+            (name.clone(), ImportType::Named(name))
+        };
+        segments.pop();
+
+        Some(ImportInfo {
+            imported_symbol,
+            source_module: segments.join("::"),
+            alias: None,
+            import_type,
+            visibility: visibility.clone(),
+            position: Position::from_node(&name_node),
+        })
+    }
+
+    /// The segments of a path, `crate`, `self` and `super` included.
+    fn segments(&self, path: Node) -> Vec<String> {
+        match path.kind() {
+            "scoped_identifier" => {
+                let mut segments = path
+                    .child_by_field_name("path")
+                    .map(|head| self.segments(head))
+                    .unwrap_or_default();
+                segments.extend(
+                    path.child_by_field_name("name")
+                        .and_then(|name| self.text(name)),
+                );
+                segments
+            }
+            _ => self.text(path).into_iter().collect(),
+        }
+    }
+
+    fn text(&self, node: Node) -> Option<String> {
+        node.utf8_text(self.source_code.as_bytes())
+            .ok()
+            .map(str::to_string)
+    }
+}
+
+fn has_child_of_kind(node: Node, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).any(|child| child.kind() == kind);
+    found
+}
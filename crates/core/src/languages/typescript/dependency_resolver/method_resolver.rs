@@ -120,9 +120,12 @@ impl MethodResolver {
         Dependency {
             source_line: usage_node.position.start_line,
             target_line: definition.position.start_line,
+            source_span: usage_node.position,
+            target_span: definition.position,
             symbol: field_name.to_string(),
             dependency_type: crate::models::DependencyType::StructFieldAccess,
             context: Some("field_access".to_string()),
+            target_file: None,
         }
     }
 }
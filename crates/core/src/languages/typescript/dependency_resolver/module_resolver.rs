@@ -1,9 +1,18 @@
+use crate::languages::typescript::tsconfig::TsConfig;
 use crate::models::{Definition, Usage};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// Extensions tried, in order, after a specifier written without one.
+const EXTENSIONS: [&str; 3] = ["ts", "tsx", "d.ts"];
 
 #[derive(Debug, Clone)]
 pub struct ModuleResolver {
     imports: HashMap<String, String>, // import_name -> module_path
+    /// The files a specifier may resolve to, normalized; empty outside project analysis.
+    files: BTreeSet<PathBuf>,
+    /// The configs that map specifiers to paths, each applying to the files below it.
+    configs: Vec<TsConfig>,
 }
 
 impl Default for ModuleResolver {
@@ -16,9 +25,91 @@ impl ModuleResolver {
     pub fn new() -> Self {
         Self {
             imports: HashMap::new(),
+            files: BTreeSet::new(),
+            configs: Vec::new(),
+        }
+    }
+
+    /// A resolver whose specifiers resolve among `files`, the files of a project.
+    pub fn for_files(files: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            imports: HashMap::new(),
+            files: files.into_iter().map(|file| normalize(&file)).collect(),
+            configs: Vec::new(),
         }
     }
 
+    /// Also resolve specifiers by the `paths` and `baseUrl` of a config, in the files below it.
+    pub fn with_config(mut self, config: TsConfig) -> Self {
+        self.configs.push(config);
+        self
+    }
+
+    /// The project file a specifier written in `importing_file` names, normalized.
+    ///
+    /// A relative specifier names a path from the importing file. Otherwise the config over the
+    /// importing file decides: a specifier matching one of its `paths` names each path the pattern
+    /// maps to in turn, and any other is looked for under its `baseUrl`. Each path is tried as
+    /// written, then with each TypeScript extension, then as a directory whose `index` is a barrel
+    /// re-exporting its files. A `.js` specifier names the `.ts` file it compiles from.
+    pub fn resolve_file(&self, importing_file: &Path, specifier: &str) -> Option<PathBuf> {
+        if is_relative(specifier) {
+            let base = importing_file.parent().unwrap_or(Path::new(""));
+            return self.find_file(&normalize(&base.join(specifier)));
+        }
+
+        let config = self.config_for(importing_file)?;
+        if let Some(substitutions) = config.matching_paths(specifier) {
+            return substitutions
+                .iter()
+                .find_map(|path| self.find_file(&normalize(path)));
+        }
+        let base_url = config.base_url.as_ref()?;
+        self.find_file(&normalize(&base_url.join(specifier)))
+    }
+
+    /// Whether a specifier is meant to name a file of the project, found or not.
+    ///
+    /// A relative specifier is, and so is one a `paths` pattern maps. Any other names a package,
+    /// unless it happens to be found under the `baseUrl`.
+    pub fn names_project_file(&self, importing_file: &Path, specifier: &str) -> bool {
+        is_relative(specifier)
+            || self
+                .config_for(importing_file)
+                .is_some_and(|config| config.matching_paths(specifier).is_some())
+    }
+
+    /// The config closest above a file, which is the one a compiler run from it would use.
+    fn config_for(&self, file: &Path) -> Option<&TsConfig> {
+        self.configs
+            .iter()
+            .filter(|config| file.starts_with(&config.directory))
+            .max_by_key(|config| config.directory.components().count())
+    }
+
+    fn find_file(&self, path: &Path) -> Option<PathBuf> {
+        let written = path.to_string_lossy();
+        let mut candidates = vec![path.to_path_buf()];
+        if let Some(stem) = written.strip_suffix(".js") {
+            candidates.push(PathBuf::from(format!("{stem}.ts")));
+            candidates.push(PathBuf::from(format!("{stem}.tsx")));
+        }
+        candidates.extend(
+            EXTENSIONS
+                .iter()
+                .map(|extension| PathBuf::from(format!("{written}.{extension}"))),
+        );
+        candidates.extend(
+            EXTENSIONS
+                .iter()
+                .map(|extension| path.join(format!("index.{extension}"))),
+        );
+
+        candidates
+            .into_iter()
+            .find(|candidate| self.files.contains(candidate))
+    }
+
     pub fn resolve_import(&self, usage: &Usage) -> Option<String> {
         self.imports.get(&usage.name).cloned()
     }
@@ -203,3 +294,30 @@ impl ModuleResolver {
         closest_definition.or_else(|| definitions.first().copied())
     }
 }
+
+fn is_relative(specifier: &str) -> bool {
+    specifier.starts_with("./") || specifier.starts_with("../")
+}
+
+/// A path with `.` and `..` resolved lexically, so the same file is always the same path.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let climbs_out = matches!(
+                    normalized.components().next_back(),
+                    None | Some(Component::ParentDir)
+                );
+                if climbs_out {
+                    normalized.push("..");
+                } else {
+                    normalized.pop();
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
@@ -254,13 +254,14 @@ impl TypeScriptDependencyResolver {
 }
 
 impl DependencyResolverTrait for TypeScriptDependencyResolver {
-    fn resolve_dependencies(
+    fn resolve_usages(
         &self,
         source_code: &str,
         root_node: Node,
-        usage_nodes: &[Usage],
+        resolved: &[Usage],
+        _usage_nodes: &[Usage],
         definitions: &[Definition],
-    ) -> Result<Vec<Dependency>, String> {
+    ) -> Result<Vec<Vec<Dependency>>, String> {
         // Read off the file once rather than per usage: every member access asks the same questions
         // of it, and a malformed query must fail rather than quietly resolve nothing.
         let narrowing =
@@ -270,9 +271,9 @@ impl DependencyResolverTrait for TypeScriptDependencyResolver {
         let exported =
             query::captured_positions(EXPORT_SPECIFIERS, source_code, root_node, "exported")?;
 
-        let mut all_dependencies: Vec<Dependency> = usage_nodes
+        Ok(resolved
             .iter()
-            .flat_map(|usage| {
+            .map(|usage| {
                 self.resolve_single_dependency(
                     &narrowing,
                     &direction,
@@ -282,15 +283,18 @@ impl DependencyResolverTrait for TypeScriptDependencyResolver {
                     definitions,
                 )
             })
-            .collect();
-
-        // Add interface implementation dependencies (class method -> interface declaration), which
-        // have no usage to resolve and are derived from the class heritage instead
-        let implementation_deps =
-            super::interface_implementation_resolver::resolve(source_code, root_node)?;
-        all_dependencies.extend(implementation_deps);
+            .collect())
+    }
 
-        Ok(all_dependencies)
+    fn resolve_structure(
+        &self,
+        source_code: &str,
+        root_node: Node,
+        _definitions: &[Definition],
+    ) -> Result<Vec<Dependency>, String> {
+        // Interface implementation dependencies (class method -> interface declaration) have no
+        // usage to resolve and are derived from the class heritage instead
+        super::interface_implementation_resolver::resolve(source_code, root_node)
     }
 }
 
@@ -361,9 +365,12 @@ impl TypeScriptDependencyResolver {
                 let dependency = Dependency {
                     source_line,
                     target_line,
+                    source_span: usage_node.position,
+                    target_span: definition.position,
                     symbol: usage_node.name.clone(),
                     dependency_type: self.get_dependency_type(usage_node, definition),
                     context: self.get_context(usage_node),
+                    target_file: None,
                 };
                 dependencies.push(dependency);
             }
@@ -4,6 +4,8 @@ pub mod definition_queries;
 pub mod dependency_resolver;
 pub mod formatter;
 pub mod imports;
+pub mod module_items;
 pub mod pattern_bindings;
 pub mod scope_queries;
+pub mod tsconfig;
 pub mod usage_extractor;
@@ -0,0 +1,309 @@
+//! What a TypeScript file imports and exports.
+//!
+//! A file is a module of its own: it exports names, either declared in it or taken from another
+//! module, and imports names other modules export. Reading those off the syntax tree is what lets
+//! an import in one file be followed to the declaration in another.
+
+use std::collections::HashMap;
+
+use tree_sitter::Node;
+
+use crate::models::{Definition, ImportInfo, ImportType, Position, Visibility};
+
+/// The imports and exports of one file.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleItems {
+    /// Each exported name with the declaration it exports, `default` included. An exported import
+    /// maps to the `ImportDefinition`, which is followed through the import written at its
+    /// position.
+    pub exports: HashMap<String, Definition>,
+    /// One import per name an `import` statement introduces, the alias being the local name.
+    pub imports: Vec<ImportInfo>,
+    /// What `export ... from` takes from another module, the alias being the exported name.
+    pub reexports: Vec<ImportInfo>,
+}
+
+impl ModuleItems {
+    /// The import written at `position`, which is where the local name it introduces is.
+    pub fn import_at(&self, position: &Position) -> Option<&ImportInfo> {
+        self.imports.iter().find(|import| {
+            import.position.start_line == position.start_line
+                && import.position.start_column == position.start_column
+        })
+    }
+}
+
+/// Read a file's top-level imports and exports.
+///
+/// Definitions are matched to declarations by the position of their name, so they must be the
+/// ones analysis produced for this same file.
+pub fn collect(root_node: Node, source_code: &str, definitions: &[Definition]) -> ModuleItems {
+    let by_position: HashMap<(usize, usize), &Definition> = definitions
+        .iter()
+        .map(|definition| {
+            (
+                (
+                    definition.position.start_line,
+                    definition.position.start_column,
+                ),
+                definition,
+            )
+        })
+        .collect();
+
+    let mut collector = Collector {
+        source_code,
+        by_position,
+        top_level: HashMap::new(),
+        items: ModuleItems::default(),
+    };
+    collector.collect_program(root_node);
+    collector.items
+}
+
+struct Collector<'s, 'd> {
+    source_code: &'s str,
+    by_position: HashMap<(usize, usize), &'d Definition>,
+    /// Names declared or imported at the top of the file, which `export { name }` refers to.
+    top_level: HashMap<String, Definition>,
+    items: ModuleItems,
+}
+
+impl Collector<'_, '_> {
+    fn collect_program(&mut self, program: Node) {
+        let mut cursor = program.walk();
+        let statements: Vec<Node> = program.named_children(&mut cursor).collect();
+
+        // `export { name }` may come before the declaration of `name`, so every top-level name is
+        // known before any export is read.
+        for statement in &statements {
+            match statement.kind() {
+                "import_statement" => self.collect_import(*statement),
+                "export_statement" => {
+                    if let Some(declaration) = statement.child_by_field_name("declaration") {
+                        self.declare(declaration);
+                    }
+                }
+                _ => self.declare(*statement),
+            }
+        }
+
+        for statement in statements {
+            if statement.kind() == "export_statement" {
+                self.collect_export(statement);
+            }
+        }
+    }
+
+    /// Record the names a top-level declaration introduces.
+    fn declare(&mut self, declaration: Node) {
+        for name in self.declared_names(declaration) {
+            if let Some(definition) = self.definition_at(name) {
+                self.top_level
+                    .entry(definition.name.clone())
+                    .or_insert(definition);
+            }
+        }
+    }
+
+    /// The name nodes of what a declaration declares: one for most, any number for `const a, b`.
+    fn declared_names<'t>(&self, declaration: Node<'t>) -> Vec<Node<'t>> {
+        match declaration.kind() {
+            "lexical_declaration" | "variable_declaration" => {
+                let mut cursor = declaration.walk();
+                declaration
+                    .named_children(&mut cursor)
+                    .filter(|child| child.kind() == "variable_declarator")
+                    .filter_map(|declarator| declarator.child_by_field_name("name"))
+                    .filter(|name| name.kind() == "identifier")
+                    .collect()
+            }
+            "ambient_declaration" => declaration
+                .named_child(0)
+                .map(|inner| self.declared_names(inner))
+                .unwrap_or_default(),
+            _ => declaration
+                .child_by_field_name("name")
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn collect_import(&mut self, statement: Node) {
+        let Some(source_module) = self.source_module(statement) else {
+            return;
+        };
+        let Some(clause) = first_child_of_kind(statement, "import_clause") else {
+            return;
+        };
+
+        let mut cursor = clause.walk();
+        for child in clause.named_children(&mut cursor) {
+            match child.kind() {
+                "identifier" => self.add_import(
+                    child,
+                    "default",
+                    Some(child),
+                    ImportType::Default,
+                    &source_module,
+                ),
+                "namespace_import" => {
+                    if let Some(name) = first_child_of_kind(child, "identifier") {
+                        self.add_import(name, "*", Some(name), ImportType::Module, &source_module);
+                    }
+                }
+                "named_imports" => {
+                    let mut specifiers = child.walk();
+                    for specifier in child.named_children(&mut specifiers) {
+                        let Some(name) = specifier.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let Some(imported) = self.text(name) else {
+                            continue;
+                        };
+                        let alias = specifier.child_by_field_name("alias");
+                        self.add_import(
+                            alias.unwrap_or(name),
+                            &imported,
+                            alias,
+                            ImportType::Named(imported.clone()),
+                            &source_module,
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn add_import(
+        &mut self,
+        local: Node,
+        imported_symbol: &str,
+        alias: Option<Node>,
+        import_type: ImportType,
+        source_module: &str,
+    ) {
+        if let Some(definition) = self.definition_at(local) {
+            self.top_level
+                .entry(definition.name.clone())
+                .or_insert(definition);
+        }
+        self.items.imports.push(ImportInfo {
+            imported_symbol: imported_symbol.to_string(),
+            source_module: source_module.to_string(),
+            alias: alias.and_then(|alias| self.text(alias)),
+            import_type,
+            visibility: Visibility::Private,
+            position: Position::from_node(&local),
+        });
+    }
+
+    fn collect_export(&mut self, statement: Node) {
+        let is_default = first_child_of_kind(statement, "default").is_some();
+
+        if let Some(declaration) = statement.child_by_field_name("declaration") {
+            for name in self.declared_names(declaration) {
+                if let Some(definition) = self.definition_at(name) {
+                    let exported = if is_default {
+                        "default".to_string()
+                    } else {
+                        definition.name.clone()
+                    };
+                    self.items.exports.insert(exported, definition);
+                }
+            }
+            return;
+        }
+
+        if let Some(value) = statement.child_by_field_name("value") {
+            // `export default name;` exports what `name` is; an expression declares nothing.
+            if let Some(definition) = self.text(value).and_then(|name| self.top_level.get(&name)) {
+                self.items
+                    .exports
+                    .insert("default".to_string(), definition.clone());
+            }
+            return;
+        }
+
+        let source_module = self.source_module(statement);
+        if let Some(clause) = first_child_of_kind(statement, "export_clause") {
+            let mut cursor = clause.walk();
+            for specifier in clause.named_children(&mut cursor) {
+                self.collect_export_specifier(specifier, source_module.as_deref());
+            }
+        } else if let Some(source_module) = source_module {
+            // `export * from` re-exports every name, and `export * as ns from` one namespace.
+            let namespace = first_child_of_kind(statement, "namespace_export")
+                .and_then(|export| export.named_child(0));
+            let (alias, import_type) = match namespace {
+                Some(name) => (self.text(name), ImportType::Module),
+                None => (None, ImportType::Wildcard),
+            };
+            self.items.reexports.push(ImportInfo {
+                imported_symbol: "*".to_string(),
+                source_module,
+                alias,
+                import_type,
+                visibility: Visibility::Public,
+                position: Position::from_node(&namespace.unwrap_or(statement)),
+            });
+        }
+    }
+
+    fn collect_export_specifier(&mut self, specifier: Node, source_module: Option<&str>) {
+        let Some(name) = specifier.child_by_field_name("name") else {
+            return;
+        };
+        let Some(local) = self.text(name) else {
+            return;
+        };
+        let alias = specifier.child_by_field_name("alias");
+        let exported = alias.and_then(|alias| self.text(alias));
+
+        match source_module {
+            Some(source_module) => self.items.reexports.push(ImportInfo {
+                imported_symbol: local.clone(),
+                source_module: source_module.to_string(),
+                alias: exported,
+                import_type: ImportType::Named(local),
+                visibility: Visibility::Public,
+                position: Position::from_node(&alias.unwrap_or(name)),
+            }),
+            None => {
+                if let Some(definition) = self.top_level.get(&local) {
+                    self.items
+                        .exports
+                        .insert(exported.unwrap_or(local), definition.clone());
+                }
+            }
+        }
+    }
+
+    /// The module specifier a statement's `from` names, without its quotes.
+    fn source_module(&self, statement: Node) -> Option<String> {
+        let source = statement.child_by_field_name("source")?;
+        first_child_of_kind(source, "string_fragment").and_then(|fragment| self.text(fragment))
+    }
+
+    fn definition_at(&self, name: Node) -> Option<Definition> {
+        let position = Position::from_node(&name);
+        self.by_position
+            .get(&(position.start_line, position.start_column))
+            .map(|definition| (*definition).clone())
+    }
+
+    fn text(&self, node: Node) -> Option<String> {
+        node.utf8_text(self.source_code.as_bytes())
+            .ok()
+            .map(str::to_string)
+    }
+}
+
+fn first_child_of_kind<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    let mut cursor = node.walk();
+    let found = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == kind);
+    found
+}
@@ -0,0 +1,328 @@
+//! The module resolution settings of a `tsconfig.json`.
+//!
+//! Only `compilerOptions.baseUrl` and `compilerOptions.paths` matter for finding the file an import
+//! names. A config may take them from the configs it `extends`, each setting resolved relative to
+//! the file that wrote it, so the whole chain is read to know them.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::dependency_resolver::module_resolver::normalize;
+
+/// The name of the file searched for in a source file's directory and each directory above it.
+pub const CONFIG_FILE_NAME: &str = "tsconfig.json";
+
+/// What a config and those it extends say about resolving specifiers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TsConfig {
+    /// The directory of the config file, whose files the config applies to.
+    pub directory: PathBuf,
+    /// Where a specifier that is neither relative nor mapped is looked for, if anywhere.
+    pub base_url: Option<PathBuf>,
+    /// Each `paths` pattern with the paths it maps to, in the order they are tried.
+    pub paths: Vec<PathMapping>,
+}
+
+/// One entry of `paths`: a specifier pattern with at most one `*`, and what it stands for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathMapping {
+    pub pattern: String,
+    /// Paths, each with at most one `*`, already joined to the directory they are relative to.
+    pub substitutions: Vec<PathBuf>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawConfig {
+    #[serde(default)]
+    extends: Option<Extends>,
+    #[serde(default, rename = "compilerOptions")]
+    compiler_options: RawCompilerOptions,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize, Default)]
+struct RawCompilerOptions {
+    #[serde(default, rename = "baseUrl")]
+    base_url: Option<String>,
+    #[serde(default)]
+    paths: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// The settings as the chain so far has left them, each path already absolute to its config.
+#[derive(Default)]
+struct Resolved {
+    base_url: Option<PathBuf>,
+    paths: Option<WrittenPaths>,
+}
+
+/// `paths` as a config wrote them.
+struct WrittenPaths {
+    patterns: Vec<(String, Vec<String>)>,
+    /// The directory of the config, which the paths are relative to when no `baseUrl` is set.
+    written_in: PathBuf,
+}
+
+impl TsConfig {
+    /// The config file closest above `file`, if there is one.
+    pub fn find(file: &Path) -> Option<PathBuf> {
+        file.ancestors()
+            .skip(1)
+            .map(|directory| directory.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Read a config file and every config it extends.
+    pub fn load(config_file: &Path) -> Result<Self, String> {
+        let config_file = normalize(config_file);
+        let resolved = read_chain(&config_file, &mut HashSet::new())?;
+        let directory = config_file.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        // Without a `baseUrl`, `paths` are relative to the config that wrote them.
+        let paths = match resolved.paths {
+            Some(WrittenPaths {
+                patterns,
+                written_in,
+            }) => {
+                let base = resolved.base_url.clone().unwrap_or(written_in);
+                patterns
+                    .into_iter()
+                    .map(|(pattern, substitutions)| PathMapping {
+                        pattern,
+                        substitutions: substitutions
+                            .iter()
+                            .map(|substitution| normalize(&base.join(substitution)))
+                            .collect(),
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            directory,
+            base_url: resolved.base_url,
+            paths,
+        })
+    }
+
+    /// The `paths` mapping a specifier matches, if any.
+    ///
+    /// An exact pattern wins over one with a `*`, and among those the longest prefix before the
+    /// `*` wins, as TypeScript chooses.
+    pub fn matching_paths(&self, specifier: &str) -> Option<Vec<PathBuf>> {
+        if let Some(exact) = self
+            .paths
+            .iter()
+            .find(|mapping| !mapping.pattern.contains('*') && mapping.pattern == specifier)
+        {
+            return Some(exact.substitutions.clone());
+        }
+
+        let (mapping, matched) = self
+            .paths
+            .iter()
+            .filter_map(|mapping| {
+                let (prefix, suffix) = mapping.pattern.split_once('*')?;
+                let matched = specifier
+                    .strip_prefix(prefix)?
+                    .strip_suffix(suffix)?
+                    .to_string();
+                Some((mapping, prefix.len(), matched))
+            })
+            .max_by_key(|(_, prefix_len, _)| *prefix_len)
+            .map(|(mapping, _, matched)| (mapping, matched))?;
+
+        Some(
+            mapping
+                .substitutions
+                .iter()
+                .map(|substitution| {
+                    PathBuf::from(substitution.to_string_lossy().replacen('*', &matched, 1))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The settings of a config with those of everything it extends applied underneath.
+fn read_chain(config_file: &Path, visiting: &mut HashSet<PathBuf>) -> Result<Resolved, String> {
+    if !visiting.insert(config_file.to_path_buf()) {
+        return Err(format!(
+            "tsconfig {} extends itself through its chain",
+            config_file.display()
+        ));
+    }
+
+    let text = fs::read_to_string(config_file)
+        .map_err(|e| format!("Failed to read {}: {e}", config_file.display()))?;
+    let raw: RawConfig = serde_json::from_str(&strip_trailing_commas(&strip_comments(&text)))
+        .map_err(|e| format!("Failed to parse {}: {e}", config_file.display()))?;
+    let directory = config_file.parent().unwrap_or(Path::new(""));
+
+    // A later config in an `extends` list overrides an earlier one, and the config itself both.
+    let mut resolved = Resolved::default();
+    let extended = match raw.extends {
+        Some(Extends::One(extended)) => vec![extended],
+        Some(Extends::Many(extended)) => extended,
+        None => Vec::new(),
+    };
+    for extended in extended {
+        let extended_file = find_extended(directory, &extended).ok_or_else(|| {
+            format!(
+                "Failed to find {extended}, extended by {}",
+                config_file.display()
+            )
+        })?;
+        let parent = read_chain(&extended_file, visiting)?;
+        if parent.base_url.is_some() {
+            resolved.base_url = parent.base_url;
+        }
+        if parent.paths.is_some() {
+            resolved.paths = parent.paths;
+        }
+    }
+
+    let options = raw.compiler_options;
+    if let Some(base_url) = options.base_url {
+        resolved.base_url = Some(normalize(&directory.join(base_url)));
+    }
+    if let Some(paths) = options.paths {
+        let patterns = paths
+            .into_iter()
+            .map(|(pattern, substitutions)| {
+                let substitutions = substitutions
+                    .as_array()
+                    .map(|values| {
+                        values
+                            .iter()
+                            .filter_map(|value| value.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (pattern, substitutions)
+            })
+            .collect();
+        resolved.paths = Some(WrittenPaths {
+            patterns,
+            written_in: directory.to_path_buf(),
+        });
+    }
+
+    visiting.remove(config_file);
+    Ok(resolved)
+}
+
+/// The file an `extends` names: a path relative to the config, or a config in a package.
+fn find_extended(directory: &Path, extended: &str) -> Option<PathBuf> {
+    let with_json = |path: PathBuf| {
+        let mut candidates = vec![path.clone()];
+        if path.extension().is_none_or(|extension| extension != "json") {
+            candidates.push(PathBuf::from(format!("{}.json", path.display())));
+        }
+        candidates.push(path.join(CONFIG_FILE_NAME));
+        candidates
+            .into_iter()
+            .find(|candidate| candidate.is_file())
+            .map(|candidate| normalize(&candidate))
+    };
+
+    if extended.starts_with("./")
+        || extended.starts_with("../")
+        || Path::new(extended).is_absolute()
+    {
+        return with_json(directory.join(extended));
+    }
+
+    directory
+        .ancestors()
+        .find_map(|ancestor| with_json(ancestor.join("node_modules").join(extended)))
+}
+
+/// The text of a JSON file with `//` and `/* */` comments removed, which `tsconfig.json` allows.
+fn strip_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        stripped.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+/// The text of a JSON file without the commas before a closing bracket, which `tsconfig.json` also
+/// allows. Comments must already be gone.
+fn strip_trailing_commas(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let closes = chars[index + 1..]
+                .iter()
+                .find(|next| !next.is_whitespace())
+                .is_some_and(|next| *next == '}' || *next == ']');
+            if closes {
+                continue;
+            }
+        }
+        stripped.push(c);
+    }
+
+    stripped
+}
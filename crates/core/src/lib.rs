@@ -1,21 +1,37 @@
 pub mod ast_formatter;
+pub mod cycles;
 pub mod definition_context;
+pub mod definition_graph;
 pub mod dependency_resolver;
 pub mod file_parser;
+pub mod graph_export;
 pub mod languages;
 pub mod metric_calculator;
 pub mod models;
+pub mod project;
 pub mod query;
+pub mod session;
+pub mod statements;
 
 use serde::Serialize;
 
+pub use definition_graph::DefinitionGraph;
 pub use file_parser::FileParser;
 use languages::language_factory;
-use metric_calculator::calculate_metrics;
+use metric_calculator::{calculate_item_metrics, calculate_metrics};
 pub use models::{
-    Accessibility, AnalysisMetadata, AnalysisResult, IntermediateRepresentation, Language,
-    LineMetrics, ScopeId, ScopeTree, ScopeType,
+    Accessibility, AnalysisMetadata, AnalysisResult, DependencyType, Granularity,
+    IntermediateRepresentation, Language, LineMetrics, MeasureOptions, ScopeId, ScopeTree,
+    ScopeType, ScoreWeights,
 };
+use models::{CodeAnalysisContext, Definition, Dependency, Usage};
+pub use project::rust_crate::CrateAnalysis;
+pub use project::typescript_workspace::WorkspaceAnalysis;
+pub use session::{AnalysisSession, TextEdit};
+use statements::Statements;
+
+/// The version of lintric doing the analysis, which is recorded in every IR it produces.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[derive(Debug, Serialize)]
 pub struct DependencyEdge {
@@ -29,22 +45,40 @@ pub struct DefinitionEntry {
     pub line: usize,
 }
 
+/// Analyze one file, scoring it with the default weights: `AnalysisResult::rescore` scores it with
+/// others, and `remeasure` measures it otherwise, with the weights of its options.
 pub fn analyze_code(
     file_path: String,
 ) -> Result<(IntermediateRepresentation, AnalysisResult), String> {
     let file_parser = FileParser::new(file_path.clone())?;
     let (file_content, language, tree) = file_parser.parse()?;
 
-    let ir = _get_intermediate_representation(file_path, &file_content, language, tree)?;
-    let mut result = calculate_metrics(&ir, &file_content)?;
-
-    result
-        .line_metrics
-        .retain(|line_metrics| line_metrics.total_dependencies > 0);
+    let (ir, scopes) = analyze_with_scopes(file_path, &file_content, language, &tree)?;
+    let definitions = definition_graph::build(&ir, &scopes, &file_content, tree.root_node());
+    let result = measure(&ir, &file_content, &definitions)?;
 
     Ok((ir, result))
 }
 
+/// Analyze a Rust crate as a whole, starting from its root file (`lib.rs` or `main.rs`).
+///
+/// Every file reached through `mod name;` declarations is analyzed, and a `use` that names an item
+/// declared in another of them becomes an edge into that file, so one result is returned per file,
+/// scored as `analyze_code` scores a file.
+pub fn analyze_crate(root_file: String) -> Result<CrateAnalysis, String> {
+    project::rust_crate::analyze(root_file)
+}
+
+/// Analyze TypeScript files as one workspace.
+///
+/// An import with a relative specifier that names another of the files, directly or through an
+/// `index.ts` barrel and its re-exports, becomes an edge into the file declaring the name. One
+/// result is returned per file, in path order and scored as `analyze_code` scores a file, with
+/// each file and `tsconfig.json` that could not be read.
+pub fn analyze_workspace(file_paths: Vec<String>) -> Result<WorkspaceAnalysis, String> {
+    project::typescript_workspace::analyze(file_paths)
+}
+
 pub fn get_intermediate_representation(
     file_path: String,
 ) -> Result<IntermediateRepresentation, String> {
@@ -54,10 +88,42 @@ pub fn get_intermediate_representation(
     _get_intermediate_representation(file_path, &file_content, language, tree)
 }
 
+/// The items of a file, such as functions, methods, structs, classes and modules, and which of them
+/// depend on which.
+pub fn get_definition_graph(file_path: String) -> Result<DefinitionGraph, String> {
+    let file_parser = FileParser::new(file_path.clone())?;
+    let (file_content, language, tree) = file_parser.parse()?;
+
+    let (ir, scopes) = analyze_with_scopes(file_path, &file_content, language, &tree)?;
+    Ok(definition_graph::build(
+        &ir,
+        &scopes,
+        &file_content,
+        tree.root_node(),
+    ))
+}
+
+pub fn get_definition_graph_from_content(
+    content: String,
+    language: Language,
+) -> Result<DefinitionGraph, String> {
+    let file_parser = FileParser::from_content(content, language);
+    let (file_content, language, tree) = file_parser.parse()?;
+
+    let (ir, scopes) = analyze_with_scopes("<memory>".to_string(), &file_content, language, &tree)?;
+    Ok(definition_graph::build(
+        &ir,
+        &scopes,
+        &file_content,
+        tree.root_node(),
+    ))
+}
+
 pub fn get_s_expression(path: String) -> Result<String, String> {
     FileParser::new(path)?.parse_as_s_expression()
 }
 
+/// Analyze source that is not in a file, scored as `analyze_code` scores a file.
 pub fn analyze_content(
     content: String,
     language: Language,
@@ -65,17 +131,114 @@ pub fn analyze_content(
     let file_parser = FileParser::from_content(content.clone(), language);
     let (file_content, language, tree) = file_parser.parse()?;
 
-    let ir =
-        _get_intermediate_representation("<memory>".to_string(), &file_content, language, tree)?;
-    let mut result = calculate_metrics(&ir, &file_content)?;
-
-    result
-        .line_metrics
-        .retain(|line_metrics| line_metrics.total_dependencies > 0);
+    let (ir, scopes) = analyze_with_scopes("<memory>".to_string(), &file_content, language, &tree)?;
+    let definitions = definition_graph::build(&ir, &scopes, &file_content, tree.root_node());
+    let result = measure(&ir, &file_content, &definitions)?;
 
     Ok((ir, result))
 }
 
+/// The metrics of an analyzed file, reporting only the lines that depend on something or that
+/// something depends on, and those of its functions, methods and classes, with the cycles among its
+/// lines and items.
+pub(crate) fn measure(
+    ir: &IntermediateRepresentation,
+    file_content: &str,
+    definitions: &DefinitionGraph,
+) -> Result<AnalysisResult, String> {
+    let mut result = calculate_metrics(ir, file_content)?;
+
+    result.line_metrics.retain(is_reported);
+    result.item_metrics =
+        calculate_item_metrics(definitions, &result.line_metrics, &ScoreWeights::default());
+    result.line_cycles = cycles::find_line_cycles(ir);
+    result.definition_cycles = cycles::find_definition_cycles(ir, definitions);
+    result.cycle_count = result.line_cycles.len() + result.definition_cycles.len();
+
+    Ok(result)
+}
+
+/// The metrics of an analyzed file measured other than by default: with the dependencies of some
+/// kinds left out, as though its lines did not have them, so that calls and variable uses can be
+/// scored apart from type references, say, or statement by statement rather than line by line,
+/// with the weights of `options`.
+///
+/// `result` is the file's measurement by default, which the spans of its items are taken from, and
+/// `file_content` its source, which the statements are found in. Its cycles are kept as they are,
+/// being of the code's structure rather than its score, but for those of its lines, which are found
+/// among its statements when those are measured.
+pub fn remeasure(
+    ir: &IntermediateRepresentation,
+    file_content: &str,
+    language: Language,
+    result: &AnalysisResult,
+    options: &MeasureOptions,
+) -> Result<AnalysisResult, String> {
+    let statements = match options.granularity {
+        Granularity::Line => None,
+        Granularity::Statement => {
+            let (_, language, tree) =
+                FileParser::from_content(file_content.to_string(), language).parse()?;
+            Some(Statements::find(
+                tree.root_node(),
+                &language,
+                ir.analysis_metadata.total_lines,
+            ))
+        }
+    };
+    let mut ir = match &statements {
+        Some(statements) => statements.collapse(ir),
+        None => ir.clone(),
+    };
+    let line_cycles = match &statements {
+        Some(statements) => cycles::find_line_cycles(&ir)
+            .into_iter()
+            .map(|mut cycle| {
+                for line in &mut cycle.lines {
+                    *line = statements.start(*line);
+                }
+                cycle
+            })
+            .collect(),
+        None => result.line_cycles.clone(),
+    };
+    ir.dependencies.retain(|dependency| {
+        !options
+            .excluded_dependency_types
+            .contains(&dependency.dependency_type)
+    });
+
+    let mut measured = metric_calculator::calculate_metrics_of_lines(
+        &ir,
+        ir.analysis_metadata.total_lines,
+        &options.weights,
+    );
+    if let Some(statements) = &statements {
+        for line_metrics in &mut measured.line_metrics {
+            line_metrics.line_number = statements.start(line_metrics.line_number);
+            for line in &mut line_metrics.dependent_lines {
+                *line = statements.start(*line);
+            }
+        }
+    }
+
+    measured.file_path = result.file_path.clone();
+    measured.line_metrics.retain(is_reported);
+    measured.item_metrics = result.item_metrics.clone();
+    for item in &mut measured.item_metrics {
+        item.measure(&measured.line_metrics, &options.weights);
+    }
+    measured.cycle_count = line_cycles.len() + result.definition_cycles.len();
+    measured.line_cycles = line_cycles;
+    measured.definition_cycles = result.definition_cycles.clone();
+    Ok(measured)
+}
+
+/// Whether a line's metrics are reported: whether it depends on something or something on it.
+fn is_reported(line_metrics: &LineMetrics) -> bool {
+    line_metrics.total_dependencies > 0 || line_metrics.fan_in > 0
+}
+
 pub fn get_s_expression_from_content(
     content: String,
     language: Language,
@@ -84,16 +247,49 @@ pub fn get_s_expression_from_content(
     file_parser.parse_as_s_expression()
 }
 
-fn _get_intermediate_representation(
+pub(crate) fn _get_intermediate_representation(
     file_path: String,
     file_content: &str,
     language: Language,
     tree: tree_sitter::Tree,
 ) -> Result<IntermediateRepresentation, String> {
+    let (ir, _) = analyze_with_scopes(file_path, file_content, language, &tree)?;
+    Ok(ir)
+}
+
+/// The IR of a file, with the tree of scopes its analysis found, which the IR does not keep.
+pub(crate) fn analyze_with_scopes(
+    file_path: String,
+    file_content: &str,
+    language: Language,
+    tree: &tree_sitter::Tree,
+) -> Result<(IntermediateRepresentation, ScopeTree), String> {
     // Use new unified analysis with single AST traversal
     let context =
         language_factory::analyze_code_unified(language.clone(), file_content, tree.root_node())?;
+    let scopes = context.scopes.clone();
+
+    let definitions = sorted_definitions(&context);
+    let usages = context.usages.get_all_usages().clone();
+
+    // Resolve dependencies using new context-based resolver
+    let dependencies = language_factory::get_dependency_resolver(language.clone(), context)?
+        .resolve_dependencies(file_content, tree.root_node(), &usages, &definitions)
+        .map_err(|e| format!("Failed to resolve dependencies: {e}"))?;
 
+    let ir = intermediate_representation(
+        file_path,
+        file_content,
+        &language,
+        definitions,
+        usages,
+        dependencies,
+    );
+    Ok((ir, scopes))
+}
+
+/// Every definition a traversal found, in order of position.
+pub(crate) fn sorted_definitions(context: &CodeAnalysisContext) -> Vec<Definition> {
     let mut definitions: Vec<_> = context
         .definitions
         .get_all_definitions()
@@ -104,16 +300,20 @@ fn _get_intermediate_representation(
 
     // Sort definitions by position for consistent output
     definitions.sort();
+    definitions
+}
 
-    let usages = context.usages.get_all_usages().clone();
-
-    // Resolve dependencies using new context-based resolver
-    let dependencies = language_factory::get_dependency_resolver(language.clone(), context)?
-        .resolve_dependencies(file_content, tree.root_node(), &usages, &definitions)
-        .map_err(|e| format!("Failed to resolve dependencies: {e}"))?;
-
-    Ok(IntermediateRepresentation {
-        file_path: file_path.clone(),
+/// The IR of a file from what its analysis extracted and resolved.
+pub(crate) fn intermediate_representation(
+    file_path: String,
+    file_content: &str,
+    language: &Language,
+    definitions: Vec<Definition>,
+    usages: Vec<Usage>,
+    dependencies: Vec<Dependency>,
+) -> IntermediateRepresentation {
+    IntermediateRepresentation {
+        file_path,
         definitions,
         usage: usages,
         dependencies,
@@ -121,7 +321,8 @@ fn _get_intermediate_representation(
             language: language.to_string(),
             total_lines: file_content.lines().count(),
             analysis_timestamp: "now".to_string(),
-            lintric_version: env!("CARGO_PKG_VERSION").to_string(),
+            lintric_version: VERSION.to_string(),
         },
-    })
+        unresolved_modules: Vec::new(),
+    }
 }
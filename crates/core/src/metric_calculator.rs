@@ -1,48 +1,122 @@
 use petgraph::graph::{DiGraph, NodeIndex};
-use petgraph::visit::Dfs;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use petgraph::visit::{Dfs, Reversed};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use crate::models::{AnalysisResult, IntermediateRepresentation, LineMetrics};
+use crate::definition_graph::DefinitionGraph;
+use crate::models::{
+    AnalysisResult, DefinitionType, IntermediateRepresentation, ItemMetrics, LineMetrics,
+    ScoreWeights,
+};
+
+/// The kinds of item whose metrics are gathered from their lines.
+const MEASURED_ITEM_TYPES: [DefinitionType; 3] = [
+    DefinitionType::FunctionDefinition,
+    DefinitionType::MethodDefinition,
+    DefinitionType::ClassDefinition,
+];
 
 pub fn calculate_metrics(
     ir: &IntermediateRepresentation,
     content: &str,
 ) -> Result<AnalysisResult, String> {
+    calculate_metrics_with_weights(ir, content, &ScoreWeights::default())
+}
+
+/// Calculate the metrics of every line, scoring the file with the given weights.
+pub fn calculate_metrics_with_weights(
+    ir: &IntermediateRepresentation,
+    content: &str,
+    weights: &ScoreWeights,
+) -> Result<AnalysisResult, String> {
+    Ok(calculate_metrics_of_lines(
+        ir,
+        content.lines().count(),
+        weights,
+    ))
+}
+
+/// Calculate the metrics of every line of a file of `line_count` lines.
+pub(crate) fn calculate_metrics_of_lines(
+    ir: &IntermediateRepresentation,
+    line_count: usize,
+    weights: &ScoreWeights,
+) -> AnalysisResult {
     let graph = ir_to_graph(ir);
+    let cross_file = cross_file_edge_counts(ir);
+    let mut dependency_types = dependency_type_counts(ir);
 
     let mut overall_complexity_score = 0.0;
     let mut all_line_metrics: Vec<LineMetrics> = Vec::new();
+    let mut file_dependency_types: BTreeMap<String, usize> = BTreeMap::new();
 
     for node_index in graph.node_indices() {
-        let line_metrics = calculate_line_metrics(&graph, node_index, content);
+        let mut line_metrics = calculate_line_metrics(&graph, node_index, line_count);
+        add_cross_file_edges(&mut line_metrics, &cross_file);
+        line_metrics.dependency_types = dependency_types
+            .remove(&line_metrics.line_number)
+            .unwrap_or_default();
+        for (type_name, count) in &line_metrics.dependency_types {
+            *file_dependency_types.entry(type_name.clone()).or_insert(0) += count;
+        }
 
-        overall_complexity_score += (line_metrics.total_dependencies as f64)
-            + (line_metrics.dependency_distance_cost as f64 / 10.0)
-            + (line_metrics.depth as f64)
-            + (line_metrics.transitive_dependencies as f64 / 5.0);
+        overall_complexity_score += weights.line_score(&line_metrics);
 
         all_line_metrics.push(line_metrics);
     }
 
-    Ok(AnalysisResult {
+    AnalysisResult {
         file_path: ir.file_path.clone(),
         line_metrics: all_line_metrics,
+        item_metrics: Vec::new(),
+        line_cycles: Vec::new(),
+        definition_cycles: Vec::new(),
+        cycle_count: 0,
+        dependency_types: file_dependency_types,
         overall_complexity_score,
-    })
+        weights: None,
+    }
+}
+
+/// The metrics of the functions, methods and classes of a file, from those of the lines each spans.
+pub fn calculate_item_metrics(
+    definition_graph: &DefinitionGraph,
+    line_metrics: &[LineMetrics],
+    weights: &ScoreWeights,
+) -> Vec<ItemMetrics> {
+    definition_graph
+        .items
+        .iter()
+        .filter(|item| MEASURED_ITEM_TYPES.contains(&item.definition_type))
+        .map(|item| {
+            let mut metrics = ItemMetrics {
+                name: item.qualified_name.clone(),
+                definition_type: item.definition_type.clone(),
+                start_line: item.start_line,
+                end_line: item.end_line,
+                total_dependencies: 0,
+                max_depth: 0,
+                complexity_score: 0.0,
+            };
+            metrics.measure(line_metrics, weights);
+            metrics
+        })
+        .collect()
 }
 
 fn calculate_line_metrics(
     graph: &DiGraph<usize, usize>,
     node_index: NodeIndex,
-    content: &str,
+    line_count: usize,
 ) -> LineMetrics {
     let line_number = graph[node_index];
 
     let total_dependencies = total_dependencies(graph, node_index);
-    let dependency_distance_cost = dependency_distance_cost(graph, node_index, content);
+    let dependency_distance_cost = dependency_distance_cost(graph, node_index, line_count);
     let depth = dfs_longest_path(graph, node_index, &mut HashMap::new(), &mut HashSet::new());
     let transitive_dependencies = transitive_dependencies(graph, node_index);
     let dependent_lines = get_dependent_lines(graph, node_index);
+    let fan_in = fan_in(graph, node_index);
+    let blast_radius = blast_radius(graph, node_index);
 
     LineMetrics {
         line_number,
@@ -51,9 +125,28 @@ fn calculate_line_metrics(
         depth,
         transitive_dependencies,
         dependent_lines,
+        fan_in,
+        blast_radius,
+        dependency_types: BTreeMap::new(),
     }
 }
 
+/// Count the edges a line has into other files as direct dependencies.
+///
+/// The line graph holds one file's lines, so what another file's lines depend on in turn is out of
+/// its reach: these edges add to a line's own dependencies but not to its depth or transitive set.
+/// A declaration in another file is as far from the line as anything can be, so each edge costs the
+/// most distance an edge can: the length of the whole file, which normalizes to `1.0`.
+fn add_cross_file_edges(line_metrics: &mut LineMetrics, cross_file: &HashMap<usize, usize>) {
+    let edges = cross_file
+        .get(&line_metrics.line_number)
+        .copied()
+        .unwrap_or(0);
+
+    line_metrics.total_dependencies += edges;
+    line_metrics.dependency_distance_cost += edges as f64;
+}
+
 fn get_dependent_lines(graph: &DiGraph<usize, usize>, node_index: NodeIndex) -> Vec<usize> {
     graph
         .neighbors_directed(node_index, petgraph::Direction::Outgoing)
@@ -70,9 +163,8 @@ fn total_dependencies(graph: &DiGraph<usize, usize>, node_index: NodeIndex) -> u
 fn dependency_distance_cost(
     graph: &DiGraph<usize, usize>,
     node_index: NodeIndex,
-    content: &str,
+    line_count: usize,
 ) -> f64 {
-    let line_count = content.lines().count();
     graph
         .edges_directed(node_index, petgraph::Direction::Outgoing)
         .map(|edge| (*edge.weight() as f64) / (line_count as f64))
@@ -91,6 +183,27 @@ fn transitive_dependencies(graph: &DiGraph<usize, usize>, node_index: NodeIndex)
     transitive_dependencies
 }
 
+/// How many lines depend on a line directly: its edges run the other way from its dependencies.
+fn fan_in(graph: &DiGraph<usize, usize>, node_index: NodeIndex) -> usize {
+    graph
+        .neighbors_directed(node_index, petgraph::Direction::Incoming)
+        .count()
+}
+
+/// How many lines reach a line, found as `transitive_dependencies` finds what it reaches, with the
+/// edges reversed.
+fn blast_radius(graph: &DiGraph<usize, usize>, node_index: NodeIndex) -> usize {
+    let reversed = Reversed(graph);
+    let mut dfs = Dfs::new(reversed, node_index);
+    let mut blast_radius: usize = 0;
+
+    while dfs.next(reversed).is_some() {
+        blast_radius += 1;
+    }
+
+    blast_radius.saturating_sub(1)
+}
+
 fn dfs_longest_path(
     graph: &DiGraph<usize, usize>,
     start_node: NodeIndex,
@@ -159,7 +272,7 @@ fn dfs_longest_path(
     *memo.get(&start_node).unwrap_or(&0)
 }
 
-fn ir_to_graph(ir: &IntermediateRepresentation) -> DiGraph<usize, usize> {
+pub(crate) fn ir_to_graph(ir: &IntermediateRepresentation) -> DiGraph<usize, usize> {
     let mut graph: DiGraph<usize, usize> = DiGraph::new();
     let line_nodes: HashMap<usize, NodeIndex> = (1..=ir.analysis_metadata.total_lines)
         .map(|line| (line, graph.add_node(line)))
@@ -182,6 +295,57 @@ fn ir_to_graph(ir: &IntermediateRepresentation) -> DiGraph<usize, usize> {
 fn unique_edges(ir: &IntermediateRepresentation) -> BTreeSet<(usize, usize)> {
     ir.dependencies
         .iter()
+        .filter(|dependency| !dependency.is_cross_file())
         .map(|dependency| (dependency.source_line, dependency.target_line))
         .collect()
 }
+
+/// How many distinct lines of other files each line depends on, keyed by source line.
+///
+/// Collapsed the same way as `unique_edges`: a line is counted once per line it reaches.
+fn cross_file_edge_counts(ir: &IntermediateRepresentation) -> HashMap<usize, usize> {
+    let targets: BTreeSet<(usize, &str, usize)> = ir
+        .dependencies
+        .iter()
+        .filter_map(|dependency| {
+            let target_file = dependency.target_file.as_deref()?;
+            Some((dependency.source_line, target_file, dependency.target_line))
+        })
+        .collect();
+
+    let mut counts = HashMap::new();
+    for (source_line, _, _) in targets {
+        *counts.entry(source_line).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// How many distinct lines each line depends on through each kind of dependency, keyed by source
+/// line and then by the kind's name. Lines of other files count, as they do toward
+/// `total_dependencies`.
+fn dependency_type_counts(
+    ir: &IntermediateRepresentation,
+) -> HashMap<usize, BTreeMap<String, usize>> {
+    let targets: BTreeSet<(usize, Option<&str>, usize, String)> = ir
+        .dependencies
+        .iter()
+        .map(|dependency| {
+            (
+                dependency.source_line,
+                dependency.target_file.as_deref(),
+                dependency.target_line,
+                dependency.dependency_type.to_string(),
+            )
+        })
+        .collect();
+
+    let mut counts: HashMap<usize, BTreeMap<String, usize>> = HashMap::new();
+    for (source_line, _, _, type_name) in targets {
+        *counts
+            .entry(source_line)
+            .or_default()
+            .entry(type_name)
+            .or_insert(0) += 1;
+    }
+    counts
+}
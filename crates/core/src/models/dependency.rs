@@ -1,5 +1,7 @@
+use super::Position;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum DependencyType {
@@ -18,18 +20,76 @@ pub enum DependencyType {
     Other(String),
 }
 
+/// The kinds of dependency a name can be given for, as a type is on the command line.
+const NAMED_TYPES: [DependencyType; 10] = [
+    DependencyType::FunctionCall,
+    DependencyType::VariableUse,
+    DependencyType::Import,
+    DependencyType::StructFieldAccess,
+    DependencyType::TypeReference,
+    DependencyType::ModuleReference,
+    DependencyType::MacroInvocation,
+    DependencyType::MacroVariable,
+    DependencyType::EnumVariantReference,
+    DependencyType::TraitImplementation,
+];
+
+/// The name of the variant, as `TypeReference`, or the name an `Other` kind was given.
+impl fmt::Display for DependencyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyType::Other(name) => write!(f, "{name}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+impl FromStr for DependencyType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        NAMED_TYPES
+            .into_iter()
+            .find(|dependency_type| dependency_type.to_string() == name)
+            .ok_or_else(|| format!("Unknown dependency type {name}"))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Dependency {
     pub source_line: usize,
     pub target_line: usize,
+    /// Where the usage is written: the identifier the edge starts from.
+    pub source_span: Position,
+    /// Where the definition it resolved to is written: the name it declares, in `target_file` if
+    /// that is set.
+    pub target_span: Position,
     pub symbol: String,
     pub dependency_type: DependencyType,
     pub context: Option<String>,
+    /// The file the target line is in, when it is not the file the edge starts in.
+    ///
+    /// Only project-level analysis reaches across files; an edge within one file leaves this unset,
+    /// and it is left out of the serialized form so single-file output is unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_file: Option<String>,
+}
+
+impl Dependency {
+    /// Whether the edge leaves the file it starts in.
+    pub fn is_cross_file(&self) -> bool {
+        self.target_file.is_some()
+    }
 }
 
+/// The spans are left out, the lines being enough to tell one edge from another.
 impl fmt::Debug for Dependency {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Dependency {{ source_line: {}, target_line: {}, symbol: {:?}, dependency_type: {:?}, context: {:?} }}",
-               self.source_line, self.target_line, self.symbol, self.dependency_type, self.context)
+        write!(f, "Dependency {{ source_line: {}, target_line: {}, symbol: {:?}, dependency_type: {:?}, context: {:?}",
+               self.source_line, self.target_line, self.symbol, self.dependency_type, self.context)?;
+        if let Some(target_file) = &self.target_file {
+            write!(f, ", target_file: {target_file:?}")?;
+        }
+        write!(f, " }}")
     }
 }
@@ -1,5 +1,6 @@
 use super::{Definition, Dependency, Usage};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AnalysisMetadata {
@@ -20,13 +21,45 @@ impl Default for AnalysisMetadata {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// An import whose module could not be found among the files analyzed with it.
+///
+/// The edges the import would have led to are missing from the dependencies, and this says so
+/// rather than leaving the file looking less dependent than it is.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UnresolvedModule {
+    /// The line of the import or re-export, where the name it takes from the module is written.
+    pub line: usize,
+    /// The module specifier as written, `./missing` or `@app/missing`.
+    pub specifier: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct IntermediateRepresentation {
     pub file_path: String,
     pub definitions: Vec<Definition>,
     pub dependencies: Vec<Dependency>,
     pub usage: Vec<Usage>,
     pub analysis_metadata: AnalysisMetadata,
+    /// Imports that should have named a file of the project but did not. Only analysis of a
+    /// project resolves modules at all, so for a single file this is always empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved_modules: Vec<UnresolvedModule>,
+}
+
+impl fmt::Debug for IntermediateRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("IntermediateRepresentation");
+        debug
+            .field("file_path", &self.file_path)
+            .field("definitions", &self.definitions)
+            .field("dependencies", &self.dependencies)
+            .field("usage", &self.usage)
+            .field("analysis_metadata", &self.analysis_metadata);
+        if !self.unresolved_modules.is_empty() {
+            debug.field("unresolved_modules", &self.unresolved_modules);
+        }
+        debug.finish()
+    }
 }
 
 impl IntermediateRepresentation {
@@ -51,6 +84,7 @@ impl IntermediateRepresentation {
             dependencies,
             usage,
             analysis_metadata,
+            unresolved_modules: Vec::new(),
         }
     }
 }
@@ -1,4 +1,6 @@
+use super::{DefinitionType, DependencyType};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LineMetrics {
@@ -8,13 +10,188 @@ pub struct LineMetrics {
     pub depth: usize,
     pub transitive_dependencies: usize,
     pub dependent_lines: Vec<usize>,
+    /// How many lines depend on this one directly.
+    pub fan_in: usize,
+    /// How many lines depend on this one, directly or through other lines: those a change to it
+    /// could reach.
+    pub blast_radius: usize,
+    /// How many lines this one depends on through each kind of dependency, by the kind's name. A
+    /// line depended on in two ways counts under both, so these can add up to more than
+    /// `total_dependencies`.
+    pub dependency_types: BTreeMap<String, usize>,
+}
+
+/// How much each metric of a line counts toward the overall complexity score.
+///
+/// A line scores the sum of its metrics, each times its weight, and a file the sum of its lines.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct ScoreWeights {
+    pub total_dependencies: f64,
+    pub dependency_distance_cost: f64,
+    pub depth: f64,
+    pub transitive_dependencies: f64,
+    /// Nothing by default: a line is scored for what it depends on, and what depends on it is
+    /// for those who want a change's impact to count.
+    pub fan_in: f64,
+    pub blast_radius: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            total_dependencies: 1.0,
+            dependency_distance_cost: 0.1,
+            depth: 1.0,
+            transitive_dependencies: 0.2,
+            fan_in: 0.0,
+            blast_radius: 0.0,
+        }
+    }
+}
+
+impl ScoreWeights {
+    pub fn line_score(&self, metrics: &LineMetrics) -> f64 {
+        metrics.total_dependencies as f64 * self.total_dependencies
+            + metrics.dependency_distance_cost * self.dependency_distance_cost
+            + metrics.depth as f64 * self.depth
+            + metrics.transitive_dependencies as f64 * self.transitive_dependencies
+            + metrics.fan_in as f64 * self.fan_in
+            + metrics.blast_radius as f64 * self.blast_radius
+    }
+
+    pub fn score(&self, line_metrics: &[LineMetrics]) -> f64 {
+        line_metrics
+            .iter()
+            .map(|metrics| self.line_score(metrics))
+            .sum()
+    }
+}
+
+/// What the nodes of the dependency graph are.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    /// Each line, as it is written.
+    #[default]
+    Line,
+    /// Each statement, reported against its first line, so that formatting does not change the
+    /// metrics.
+    Statement,
+}
+
+/// How a file's metrics are measured: by default line by line, through every kind of dependency,
+/// and scored with the default weights.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeasureOptions {
+    pub granularity: Granularity,
+    /// Kinds of dependency left out, as though the lines did not depend on each other that way.
+    pub excluded_dependency_types: Vec<DependencyType>,
+    pub weights: ScoreWeights,
+}
+
+impl MeasureOptions {
+    /// Whether these are the options every analysis is measured with to begin with.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The metrics of a function, method or class, from those of the lines it spans.
+///
+/// A class spans its methods, so their lines count toward both.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ItemMetrics {
+    /// The name, qualified with what the item is declared in, as `Parser::parse`.
+    pub name: String,
+    pub definition_type: DefinitionType,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// The dependencies of its lines, summed.
+    pub total_dependencies: usize,
+    /// The depth of its deepest line.
+    pub max_depth: usize,
+    /// The score of its lines, as a file's is of all of its lines.
+    pub complexity_score: f64,
+}
+
+impl ItemMetrics {
+    /// Gather the metrics of the lines the item spans, replacing those it had.
+    pub fn measure(&mut self, line_metrics: &[LineMetrics], weights: &ScoreWeights) {
+        self.total_dependencies = 0;
+        self.max_depth = 0;
+        self.complexity_score = 0.0;
+        for line in self.lines(line_metrics) {
+            self.total_dependencies += line.total_dependencies;
+            self.max_depth = self.max_depth.max(line.depth);
+            self.complexity_score += weights.line_score(line);
+        }
+    }
+
+    /// The metrics of the lines the item spans.
+    pub fn lines<'a>(
+        &self,
+        line_metrics: &'a [LineMetrics],
+    ) -> impl Iterator<Item = &'a LineMetrics> + 'a {
+        let lines = self.start_line..=self.end_line;
+        line_metrics
+            .iter()
+            .filter(move |metrics| lines.contains(&metrics.line_number))
+    }
+}
+
+/// Lines that depend on one another, each reaching the others through the rest.
+///
+/// The depth of a line stops where its chain of dependencies comes back on itself, so a cycle does
+/// not show in the line metrics and is reported on its own.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LineCycle {
+    /// In line order.
+    pub lines: Vec<usize>,
+    /// The symbols of the dependencies among the lines, in name order.
+    pub symbols: Vec<String>,
+}
+
+/// Items, such as functions, that depend on one another, as mutually recursive functions do, or a
+/// function that calls itself.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DefinitionCycle {
+    /// The qualified names of the items, in source order.
+    pub items: Vec<String>,
+    /// The symbols of the dependencies among the items, in name order.
+    pub symbols: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AnalysisResult {
     pub file_path: String,
     pub line_metrics: Vec<LineMetrics>,
+    /// The functions, methods and classes of the file, in source order.
+    pub item_metrics: Vec<ItemMetrics>,
+    /// In order of their first line.
+    pub line_cycles: Vec<LineCycle>,
+    /// In order of their first item.
+    pub definition_cycles: Vec<DefinitionCycle>,
+    /// How many cycles there are of lines and of items together.
+    pub cycle_count: usize,
+    /// Those of its lines, summed.
+    pub dependency_types: BTreeMap<String, usize>,
     pub overall_complexity_score: f64,
+    /// The weights of the score, where they are not those of the report holding the result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<ScoreWeights>,
+}
+
+impl AnalysisResult {
+    /// Score the file again from its line metrics, with other weights.
+    pub fn rescore(&mut self, weights: &ScoreWeights) {
+        self.overall_complexity_score = weights.score(&self.line_metrics);
+        for item in &mut self.item_metrics {
+            item.complexity_score = item
+                .lines(&self.line_metrics)
+                .map(|metrics| weights.line_score(metrics))
+                .sum();
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,4 +200,7 @@ pub struct OverallAnalysisReport {
     pub total_files_analyzed: usize,
     pub total_overall_complexity_score: f64,
     pub average_complexity_score: f64,
+    /// The weights the results were scored with.
+    #[serde(default)]
+    pub weights: ScoreWeights,
 }
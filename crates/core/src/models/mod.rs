@@ -18,9 +18,14 @@ pub use analysis_context::CodeAnalysisContext;
 pub use ast_traverser::{ASTScopeTraverser, NodeDefinitionExtractor, NodeUsageExtractor};
 pub use definition::{Accessibility, Definition, DefinitionType, ScopeId};
 pub use dependency::{Dependency, DependencyType};
-pub use intermediate_representation::{AnalysisMetadata, IntermediateRepresentation};
+pub use intermediate_representation::{
+    AnalysisMetadata, IntermediateRepresentation, UnresolvedModule,
+};
 pub use language::Language;
-pub use metrics::{AnalysisResult, LineMetrics, OverallAnalysisReport};
+pub use metrics::{
+    AnalysisResult, DefinitionCycle, Granularity, ItemMetrics, LineCycle, LineMetrics,
+    MeasureOptions, OverallAnalysisReport, ScoreWeights,
+};
 pub use module::{ImportInfo, ImportType, Module, ModuleId, ModuleTree, Visibility};
 pub use position::Position;
 pub use registry::{DefinitionRegistry, UsageRegistry};
@@ -0,0 +1,130 @@
+//! Analysis of files that import from one another.
+//!
+//! Analyzing a file alone stops at its imports: the name an import introduces is declared locally,
+//! and what it names lives elsewhere. Here the files of a project are analyzed together, and an
+//! import resolved to a declaration in another file becomes an edge into that file.
+
+pub mod rust_crate;
+pub mod typescript_workspace;
+
+use std::path::Path;
+
+use tree_sitter::Tree;
+
+use crate::definition_graph::{self, DefinitionGraph};
+use crate::dependency_resolver::base_resolver::dependency_type_of_definition;
+use crate::file_parser::FileParser;
+use crate::models::{
+    AnalysisResult, Definition, Dependency, DependencyType, IntermediateRepresentation, Position,
+};
+
+/// One file of a project, analyzed on its own and waiting for its imports to be linked.
+pub(crate) struct ProjectFile {
+    pub ir: IntermediateRepresentation,
+    pub content: String,
+    pub tree: Tree,
+    /// The file's items, found before its imports are linked, which add no items.
+    pub definitions: DefinitionGraph,
+}
+
+impl ProjectFile {
+    pub(crate) fn analyze(file_path: &Path) -> Result<Self, String> {
+        let file_path = file_path.to_string_lossy().into_owned();
+        let (content, language, tree) = FileParser::new(file_path.clone())?.parse()?;
+        let (ir, scopes) = crate::analyze_with_scopes(file_path, &content, language, &tree)?;
+        let definitions = definition_graph::build(&ir, &scopes, &content, tree.root_node());
+
+        Ok(Self {
+            ir,
+            content,
+            tree,
+            definitions,
+        })
+    }
+}
+
+/// An import resolved to the declaration it names in another file.
+#[derive(Debug, Clone)]
+pub struct ResolvedImport {
+    /// Where the name the import introduces is written.
+    pub position: Position,
+    /// The name the import introduces, which is what the file's usages refer to.
+    pub local_name: String,
+    pub target_file: String,
+    pub definition: Definition,
+}
+
+/// Add the edges a file's resolved imports lead to.
+///
+/// The import itself depends on the declaration, and so does every line whose usage resolved to
+/// the import: within the file that usage stops at the import, and here it carries on to what the
+/// import names.
+pub(crate) fn link_imports(ir: &mut IntermediateRepresentation, imports: &[ResolvedImport]) {
+    let mut cross_file = Vec::new();
+
+    for import in imports {
+        let target_line = import.definition.line_number();
+        let target_file = Some(import.target_file.clone());
+
+        cross_file.push(Dependency {
+            source_line: import.position.start_line,
+            target_line,
+            source_span: import.position,
+            target_span: import.definition.position,
+            symbol: import.local_name.clone(),
+            dependency_type: DependencyType::Import,
+            context: Some(format!(
+                "ImportDefinition:{}:{}",
+                import.position.start_line, import.position.start_column
+            )),
+            target_file: target_file.clone(),
+        });
+
+        let through_import = ir.dependencies.iter().filter(|dependency| {
+            !dependency.is_cross_file()
+                && dependency.target_line == import.position.start_line
+                && dependency.symbol == import.local_name
+        });
+        for dependency in through_import {
+            cross_file.push(Dependency {
+                source_line: dependency.source_line,
+                target_line,
+                source_span: dependency.source_span,
+                target_span: import.definition.position,
+                symbol: dependency.symbol.clone(),
+                dependency_type: reclassified(dependency, &import.definition),
+                context: dependency.context.clone(),
+                target_file: target_file.clone(),
+            });
+        }
+    }
+
+    ir.dependencies.extend(cross_file);
+}
+
+/// What a usage of an import is, now that the declaration it reaches is known.
+///
+/// Within the file an import says nothing about what it names, so the usage was classified by its
+/// syntax alone. A call stays a call, as it would had the declaration been local.
+fn reclassified(dependency: &Dependency, definition: &Definition) -> DependencyType {
+    match dependency.dependency_type {
+        DependencyType::FunctionCall => DependencyType::FunctionCall,
+        _ => dependency_type_of_definition(&definition.definition_type)
+            .unwrap_or_else(|| dependency.dependency_type.clone()),
+    }
+}
+
+/// Calculate each file's metrics once its imports are linked, in path order.
+pub(crate) fn measure_all(
+    mut files: Vec<ProjectFile>,
+) -> Result<Vec<(IntermediateRepresentation, AnalysisResult)>, String> {
+    files.sort_by(|a, b| a.ir.file_path.cmp(&b.ir.file_path));
+
+    files
+        .into_iter()
+        .map(|file| {
+            let result = crate::measure(&file.ir, &file.content, &file.definitions)?;
+            Ok((file.ir, result))
+        })
+        .collect()
+}
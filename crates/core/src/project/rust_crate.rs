@@ -0,0 +1,121 @@
+//! A Rust crate, analyzed from its root file through every module it declares.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::{link_imports, measure_all, ProjectFile, ResolvedImport};
+use crate::languages::rust::dependency_resolver::ModuleResolver;
+use crate::languages::rust::module_items;
+use crate::models::{AnalysisResult, IntermediateRepresentation, ModuleTree};
+
+/// The files of a crate, analyzed, and those that could not be.
+#[derive(Debug, Default)]
+pub struct CrateAnalysis {
+    /// One result per module file, in path order.
+    pub results: Vec<(IntermediateRepresentation, AnalysisResult)>,
+    /// Each module file that could not be read or parsed, with why, which the others are analyzed
+    /// without.
+    pub failed_files: Vec<(String, String)>,
+}
+
+/// Analyze the crate rooted at `root_file`, giving one result per module file, in path order.
+///
+/// A `mod name;` whose file cannot be found is kept in the module tree without one, so a `use`
+/// through it resolves to nothing rather than failing the crate, and so is one whose file cannot be
+/// analyzed, which is recorded. Only a root file that cannot be analyzed fails the crate.
+pub fn analyze(root_file: String) -> Result<CrateAnalysis, String> {
+    let (module_tree, mut files, failed_files) = load(root_file)?;
+    let resolver = ModuleResolver::from_module_tree(module_tree);
+
+    for file in &mut files {
+        let imports = resolved_imports(&resolver, &file.ir.file_path);
+        link_imports(&mut file.ir, &imports);
+    }
+
+    Ok(CrateAnalysis {
+        results: measure_all(files)?,
+        failed_files,
+    })
+}
+
+/// Analyze every file the crate's modules are declared in, building its module tree on the way,
+/// with each module file that could not be analyzed and why.
+#[allow(clippy::type_complexity)]
+pub(crate) fn load(
+    root_file: String,
+) -> Result<(ModuleTree, Vec<ProjectFile>, Vec<(String, String)>), String> {
+    let mut module_tree = ModuleTree::new();
+    let root_module = module_tree.root_module;
+    if let Some(root) = module_tree.modules.get_mut(&root_module) {
+        root.file_path = Some(root_file.clone());
+    }
+
+    let mut files = Vec::new();
+    let mut failed_files = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![(root_module, PathBuf::from(root_file), true)];
+
+    while let Some((module, path, is_crate_root)) = pending.pop() {
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+
+        let file = match ProjectFile::analyze(&path) {
+            Ok(file) => file,
+            Err(e) if !is_crate_root => {
+                failed_files.push((path.to_string_lossy().into_owned(), e));
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let declared = module_items::collect(
+            &mut module_tree,
+            module,
+            &module_items::module_dir(&path, is_crate_root),
+            file.tree.root_node(),
+            &file.content,
+            &file.ir.definitions,
+        );
+        pending.extend(
+            declared
+                .into_iter()
+                .filter_map(|declared| Some((declared.module, declared.file_path?, false))),
+        );
+        files.push(file);
+    }
+
+    Ok((module_tree, files, failed_files))
+}
+
+/// The imports written in one file that name an item declared in another.
+fn resolved_imports(resolver: &ModuleResolver, file_path: &str) -> Vec<ResolvedImport> {
+    let module_tree = resolver.get_module_tree();
+    let mut modules: Vec<_> = module_tree
+        .modules
+        .values()
+        .filter(|module| module.file_path.as_deref() == Some(file_path))
+        .collect();
+    modules.sort_by_key(|module| module.id);
+
+    modules
+        .into_iter()
+        .flat_map(|module| module.imports.iter().map(move |import| (module.id, import)))
+        .filter_map(|(module_id, import)| {
+            let (target_module, definition) = resolver.resolve_imported_item(import, module_id)?;
+            let target_file = module_tree.modules.get(&target_module)?.file_path.clone()?;
+            if target_file == file_path {
+                return None;
+            }
+
+            Some(ResolvedImport {
+                position: import.position,
+                local_name: import
+                    .alias
+                    .clone()
+                    .unwrap_or_else(|| import.imported_symbol.clone()),
+                target_file,
+                definition,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,251 @@
+//! TypeScript files analyzed together, each import followed to the file that declares its name.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::{link_imports, measure_all, ProjectFile, ResolvedImport};
+use crate::languages::typescript::dependency_resolver::module_resolver::normalize;
+use crate::languages::typescript::dependency_resolver::ModuleResolver;
+use crate::languages::typescript::module_items::{self, ModuleItems};
+use crate::languages::typescript::tsconfig::TsConfig;
+use crate::models::{
+    AnalysisResult, Definition, DefinitionType, ImportInfo, ImportType, IntermediateRepresentation,
+    UnresolvedModule,
+};
+
+/// How many `export ... from` a name is followed through before giving up on it.
+///
+/// Barrels re-export barrels, but not deeply; the bound only stops a cycle of them.
+const MAX_REEXPORT_HOPS: usize = 8;
+
+/// The files of a workspace, analyzed, and what could not be read along the way.
+#[derive(Debug, Default)]
+pub struct WorkspaceAnalysis {
+    /// One result per file, in path order.
+    pub results: Vec<(IntermediateRepresentation, AnalysisResult)>,
+    /// Each file that could not be read or parsed, with why, which the others are analyzed
+    /// without.
+    pub failed_files: Vec<(String, String)>,
+    /// Each `tsconfig.json` that could not be read, with why. The files below it are resolved as
+    /// though it set no `paths` or `baseUrl`.
+    pub config_errors: Vec<(String, String)>,
+}
+
+/// Analyze `file_paths` as one workspace, giving one result per file, in path order.
+///
+/// Specifiers are resolved as the `tsconfig.json` closest above each file says. An import of a
+/// package is left as single-file analysis leaves it, and one that should name a file of the
+/// workspace but does not is recorded in the file's `unresolved_modules`.
+pub fn analyze(file_paths: Vec<String>) -> Result<WorkspaceAnalysis, String> {
+    let mut files = Vec::new();
+    let mut failed_files = Vec::new();
+    for file_path in file_paths {
+        match ProjectFile::analyze(Path::new(&file_path)) {
+            Ok(file) => files.push(file),
+            Err(e) => failed_files.push((file_path, e)),
+        }
+    }
+    let failed_paths = failed_files
+        .iter()
+        .map(|(file_path, _)| Path::new(file_path));
+    let (workspace, config_errors) = Workspace::new(&files, failed_paths);
+
+    for (index, file) in files.iter_mut().enumerate() {
+        let imports = workspace.resolved_imports(index);
+        link_imports(&mut file.ir, &imports);
+        file.ir.unresolved_modules = workspace.unresolved_modules(index);
+    }
+
+    Ok(WorkspaceAnalysis {
+        results: measure_all(files)?,
+        failed_files,
+        config_errors,
+    })
+}
+
+struct Workspace {
+    resolver: ModuleResolver,
+    /// Each file's path as analyzed, its normalized path and what it imports and exports.
+    files: Vec<(String, PathBuf, ModuleItems)>,
+    by_path: HashMap<PathBuf, usize>,
+}
+
+impl Workspace {
+    /// The workspace of `files`, with each config that could not be read and why.
+    ///
+    /// The files that failed are still files of the workspace, so an import of one is not taken
+    /// for a missing module, though it resolves to nothing.
+    fn new<'a>(
+        files: &[ProjectFile],
+        failed_files: impl Iterator<Item = &'a Path>,
+    ) -> (Self, Vec<(String, String)>) {
+        let files: Vec<_> = files
+            .iter()
+            .map(|file| {
+                let items = module_items::collect(
+                    file.tree.root_node(),
+                    &file.content,
+                    &file.ir.definitions,
+                );
+                let path = normalize(Path::new(&file.ir.file_path));
+                (file.ir.file_path.clone(), path, items)
+            })
+            .collect();
+        let by_path = files
+            .iter()
+            .enumerate()
+            .map(|(index, (_, path, _))| (path.clone(), index))
+            .collect();
+        let config_files: BTreeSet<PathBuf> = files
+            .iter()
+            .filter_map(|(_, path, _)| TsConfig::find(path))
+            .collect();
+
+        let mut resolver = ModuleResolver::for_files(
+            files
+                .iter()
+                .map(|(_, path, _)| path.clone())
+                .chain(failed_files.map(normalize)),
+        );
+        let mut config_errors = Vec::new();
+        for config_file in config_files {
+            let config = TsConfig::load(&config_file).unwrap_or_else(|e| {
+                config_errors.push((config_file.to_string_lossy().into_owned(), e));
+                // Still the closest config for the files below it, so that none of a config
+                // further up applies to them either.
+                TsConfig {
+                    directory: config_file.parent().unwrap_or(Path::new("")).to_path_buf(),
+                    base_url: None,
+                    paths: Vec::new(),
+                }
+            });
+            resolver = resolver.with_config(config);
+        }
+
+        let workspace = Self {
+            resolver,
+            files,
+            by_path,
+        };
+        (workspace, config_errors)
+    }
+
+    /// The imports written in one file whose module should be a file of the workspace but is not.
+    fn unresolved_modules(&self, file: usize) -> Vec<UnresolvedModule> {
+        let (_, path, items) = &self.files[file];
+
+        let unresolved: BTreeSet<(usize, &str)> = items
+            .imports
+            .iter()
+            .chain(&items.reexports)
+            .filter(|import| {
+                self.resolver
+                    .resolve_file(path, &import.source_module)
+                    .is_none()
+                    && self
+                        .resolver
+                        .names_project_file(path, &import.source_module)
+            })
+            .map(|import| (import.position.start_line, import.source_module.as_str()))
+            .collect();
+
+        unresolved
+            .into_iter()
+            .map(|(line, specifier)| UnresolvedModule {
+                line,
+                specifier: specifier.to_string(),
+            })
+            .collect()
+    }
+
+    /// The imports and re-exports written in one file that name a declaration in another.
+    fn resolved_imports(&self, file: usize) -> Vec<ResolvedImport> {
+        let (file_path, _, items) = &self.files[file];
+
+        items
+            .imports
+            .iter()
+            .chain(&items.reexports)
+            .filter_map(|import| {
+                let (target, definition) = self.resolve_import(file, import, 0)?;
+                let target_file = self.files[target].0.clone();
+                if &target_file == file_path {
+                    return None;
+                }
+
+                Some(ResolvedImport {
+                    position: import.position,
+                    local_name: import
+                        .alias
+                        .clone()
+                        .unwrap_or_else(|| import.imported_symbol.clone()),
+                    target_file,
+                    definition,
+                })
+            })
+            .collect()
+    }
+
+    /// The file and declaration an import names. A namespace import names a whole module, and an
+    /// `export *` no one name, so neither resolves.
+    fn resolve_import(
+        &self,
+        file: usize,
+        import: &ImportInfo,
+        hops: usize,
+    ) -> Option<(usize, Definition)> {
+        let name = match &import.import_type {
+            ImportType::Named(name) => name.as_str(),
+            ImportType::Default => "default",
+            ImportType::Wildcard | ImportType::Module => return None,
+        };
+        let target = self.resolve_module(file, &import.source_module)?;
+        self.resolve_export(target, name, hops + 1)
+    }
+
+    /// Where the name a file exports is declared, following exported imports and re-exports.
+    fn resolve_export(&self, file: usize, name: &str, hops: usize) -> Option<(usize, Definition)> {
+        if hops > MAX_REEXPORT_HOPS {
+            return None;
+        }
+        let items = &self.files[file].2;
+
+        if let Some(definition) = items.exports.get(name) {
+            if definition.definition_type != DefinitionType::ImportDefinition {
+                return Some((file, definition.clone()));
+            }
+            let import = items.import_at(&definition.position)?;
+            return self.resolve_import(file, import, hops);
+        }
+
+        let named = items.reexports.iter().find(|reexport| {
+            matches!(reexport.import_type, ImportType::Named(_))
+                && reexport
+                    .alias
+                    .as_deref()
+                    .unwrap_or(&reexport.imported_symbol)
+                    == name
+        });
+        if let Some(reexport) = named {
+            return self.resolve_import(file, reexport, hops);
+        }
+
+        // `export *` passes on every name but `default`.
+        if name == "default" {
+            return None;
+        }
+        items
+            .reexports
+            .iter()
+            .filter(|reexport| reexport.import_type == ImportType::Wildcard)
+            .find_map(|reexport| {
+                let target = self.resolve_module(file, &reexport.source_module)?;
+                self.resolve_export(target, name, hops + 1)
+            })
+    }
+
+    fn resolve_module(&self, file: usize, specifier: &str) -> Option<usize> {
+        let path = self.resolver.resolve_file(&self.files[file].1, specifier)?;
+        self.by_path.get(&path).copied()
+    }
+}
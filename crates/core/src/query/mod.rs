@@ -6,8 +6,9 @@
 //! looking at without repeating the pattern in Rust.
 
 use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Node, Query, QueryCursor};
+use tree_sitter::{Language, Node, Query, QueryCursor};
 
 /// The role each captured node plays, keyed by node id.
 pub type Roles<T> = HashMap<usize, T>;
@@ -22,9 +23,7 @@ pub fn capture_roles<T: Clone>(
     root_node: Node,
     mapping: &[(&str, T)],
 ) -> Result<Roles<T>, String> {
-    let language = &*root_node.language();
-    let query = Query::new(language, query_source)
-        .map_err(|error| format!("Failed to create query: {error}"))?;
+    let query = compiled(&root_node.language(), query_source)?;
 
     let roles_by_index = indexed_roles(&query, mapping);
     let mut cursor = QueryCursor::new();
@@ -42,6 +41,32 @@ pub fn capture_roles<T: Clone>(
     Ok(roles)
 }
 
+/// Compiled queries, by the language and source they were compiled from.
+type CompiledQueries = HashMap<(Language, String), Arc<Query>>;
+
+/// A query compiled for a language.
+///
+/// Compiling a query takes far longer than running it over a file, and the same few queries run
+/// over every file analyzed, or over a file at every edit, so each is compiled once.
+fn compiled(language: &Language, query_source: &str) -> Result<Arc<Query>, String> {
+    static COMPILED: OnceLock<Mutex<CompiledQueries>> = OnceLock::new();
+
+    let key = (language.clone(), query_source.to_string());
+    let mut compiled = COMPILED
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(query) = compiled.get(&key) {
+        return Ok(query.clone());
+    }
+    let query = Arc::new(
+        Query::new(language, query_source)
+            .map_err(|error| format!("Failed to create query: {error}"))?,
+    );
+    compiled.insert(key, query.clone());
+    Ok(query)
+}
+
 /// Resolve the mapping's capture names to the indices the query assigned them, once, so the match
 /// loop compares integers rather than strings.
 fn indexed_roles<T: Clone>(query: &Query, mapping: &[(&str, T)]) -> HashMap<u32, T> {
@@ -183,9 +208,7 @@ pub fn map_pairs<T>(
     second: &str,
     mut map: impl FnMut(Node, Node) -> Option<T>,
 ) -> Result<Vec<T>, String> {
-    let language = &*root_node.language();
-    let query = Query::new(language, query_source)
-        .map_err(|error| format!("Failed to create query: {error}"))?;
+    let query = compiled(&root_node.language(), query_source)?;
 
     let first_index = query
         .capture_index_for_name(first)
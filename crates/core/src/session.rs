@@ -0,0 +1,574 @@
+//! A file kept analyzed while it is edited, for an editor or a watcher.
+//!
+//! An edit is applied to the syntax tree as well as the text, so that tree-sitter parses again only
+//! what changed. An edit inside the body of one function is analyzed there alone: that function is
+//! extracted again, and its usages resolved again, with those elsewhere naming something it declared
+//! before or after the edit. Everything else the last analysis extracted and resolved is kept, moved
+//! by the lines and columns the edit added or removed. Any other edit, such as one to a signature or
+//! across items, or one to a body declaring what is visible outside it, as an impl or a class is,
+//! extracts and resolves the whole file again from the new tree.
+//!
+//! Either way, the IR and metrics are those `analyze_content` gives for the text.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use tree_sitter::{InputEdit, Node, Point, Tree};
+
+use crate::definition_graph;
+use crate::file_parser::FileParser;
+use crate::languages::language_factory;
+use crate::models::{
+    AnalysisResult, CodeAnalysisContext, DefinitionRegistry, DefinitionType, Dependency,
+    IntermediateRepresentation, Language, Position, ScopeId, ScopeTree, ScopeType, Usage,
+    UsageRegistry,
+};
+
+/// The kinds of node a function body is analyzed apart from the rest of the file in, each of which
+/// creates a function scope.
+fn function_kinds(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &["function_item"],
+        Language::TypeScript | Language::TSX => &[
+            "function_declaration",
+            "method_definition",
+            "arrow_function",
+        ],
+    }
+}
+
+/// The kinds of scope whose declarations are visible outside the function they are written in.
+const ESCAPING_SCOPES: [ScopeType; 5] = [
+    ScopeType::Impl,
+    ScopeType::Trait,
+    ScopeType::Module,
+    ScopeType::Class,
+    ScopeType::Interface,
+];
+
+/// A replacement of some of a file's text, by byte offsets into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start_byte: usize,
+    /// Where the replaced text ends, before the edit.
+    pub old_end_byte: usize,
+    pub text: String,
+}
+
+/// The analysis of a file's text, kept up to date with edits to it.
+pub struct AnalysisSession {
+    content: String,
+    language: Language,
+    tree: Tree,
+    context: CodeAnalysisContext,
+    /// What each of the context's usages depends on, in order.
+    usage_dependencies: Vec<Vec<Dependency>>,
+    ir: IntermediateRepresentation,
+    result: AnalysisResult,
+}
+
+impl AnalysisSession {
+    /// Analyze a text as `analyze_content` would, keeping what an edit needs.
+    pub fn new(content: String, language: Language) -> Result<Self, String> {
+        let (content, language, tree) = FileParser::from_content(content, language).parse()?;
+        let context =
+            language_factory::analyze_code_unified(language.clone(), &content, tree.root_node())?;
+        let unresolved = vec![None; context.usages.get_all_usages().len()];
+        Self::resolve(content, language, tree, context, unresolved)
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn ir(&self) -> &IntermediateRepresentation {
+        &self.ir
+    }
+
+    pub fn result(&self) -> &AnalysisResult {
+        &self.result
+    }
+
+    /// Apply an edit and analyze the text again. An edit that cannot be applied, or whose text
+    /// cannot be analyzed, leaves the session as it was.
+    pub fn edit(&mut self, edit: &TextEdit) -> Result<(), String> {
+        let replaced = self
+            .content
+            .get(edit.start_byte..edit.old_end_byte)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid edit of bytes {}..{} of a text of {}",
+                    edit.start_byte,
+                    edit.old_end_byte,
+                    self.content.len()
+                )
+            })?;
+        let mut content =
+            String::with_capacity(self.content.len() - replaced.len() + edit.text.len());
+        content.push_str(&self.content[..edit.start_byte]);
+        content.push_str(&edit.text);
+        content.push_str(&self.content[edit.old_end_byte..]);
+
+        let new_end_byte = edit.start_byte + edit.text.len();
+        let input_edit = InputEdit {
+            start_byte: edit.start_byte,
+            old_end_byte: edit.old_end_byte,
+            new_end_byte,
+            start_position: point(&self.content, edit.start_byte),
+            old_end_position: point(&self.content, edit.old_end_byte),
+            new_end_position: point(&content, new_end_byte),
+        };
+        let mut edited = self.tree.clone();
+        edited.edit(&input_edit);
+        let tree =
+            FileParser::from_content(content.clone(), self.language.clone()).reparse(&edited)?;
+
+        let (context, usage_dependencies) =
+            match self.reanalyze_function(&edited, &tree, &content, &input_edit)? {
+                Some(reanalyzed) => reanalyzed,
+                None => {
+                    let context = language_factory::analyze_code_unified(
+                        self.language.clone(),
+                        &content,
+                        tree.root_node(),
+                    )?;
+                    let unresolved = vec![None; context.usages.get_all_usages().len()];
+                    (context, unresolved)
+                }
+            };
+        *self = Self::resolve(
+            content,
+            self.language.clone(),
+            tree,
+            context,
+            usage_dependencies,
+        )?;
+        Ok(())
+    }
+
+    /// Resolve the usages not yet resolved, and measure the file.
+    fn resolve(
+        content: String,
+        language: Language,
+        tree: Tree,
+        context: CodeAnalysisContext,
+        usage_dependencies: Vec<Option<Vec<Dependency>>>,
+    ) -> Result<Self, String> {
+        let root = tree.root_node();
+        let definitions = crate::sorted_definitions(&context);
+        let usages = context.usages.get_all_usages().clone();
+        let unresolved: Vec<Usage> = usages
+            .iter()
+            .zip(&usage_dependencies)
+            .filter(|(_, dependencies)| dependencies.is_none())
+            .map(|(usage, _)| usage.clone())
+            .collect();
+
+        let resolver =
+            language_factory::get_dependency_resolver(language.clone(), context.clone())?;
+        let mut resolved = resolver
+            .resolve_usages(&content, root, &unresolved, &usages, &definitions)
+            .map_err(|e| format!("Failed to resolve dependencies: {e}"))?
+            .into_iter();
+        let usage_dependencies: Vec<Vec<Dependency>> = usage_dependencies
+            .into_iter()
+            .map(|dependencies| dependencies.unwrap_or_else(|| resolved.next().unwrap_or_default()))
+            .collect();
+        let mut dependencies: Vec<Dependency> =
+            usage_dependencies.iter().flatten().cloned().collect();
+        dependencies.extend(
+            resolver
+                .resolve_structure(&content, root, &definitions)
+                .map_err(|e| format!("Failed to resolve dependencies: {e}"))?,
+        );
+
+        let ir = crate::intermediate_representation(
+            "<memory>".to_string(),
+            &content,
+            &language,
+            definitions,
+            usages,
+            dependencies,
+        );
+        let definitions = definition_graph::build(&ir, &context.scopes, &content, root);
+        let result = crate::measure(&ir, &content, &definitions)?;
+
+        Ok(Self {
+            content,
+            language,
+            tree,
+            context,
+            usage_dependencies,
+            ir,
+            result,
+        })
+    }
+
+    /// The context of the edited text with the one function the edit is inside extracted again, and
+    /// what each of its usages depends on where that is still known; or none where the edit is not
+    /// inside the body of a function, or declares something that would be visible outside it.
+    ///
+    /// `edited` is the tree before the edit, told of the edit, and `tree` the tree after it.
+    #[allow(clippy::type_complexity)]
+    fn reanalyze_function(
+        &self,
+        edited: &Tree,
+        tree: &Tree,
+        content: &str,
+        edit: &InputEdit,
+    ) -> Result<Option<(CodeAnalysisContext, Vec<Option<Vec<Dependency>>>)>, String> {
+        let kinds = function_kinds(&self.language);
+        let Some(old_function) = enclosing_function(
+            self.tree.root_node(),
+            kinds,
+            edit.start_byte,
+            edit.old_end_byte,
+        ) else {
+            return Ok(None);
+        };
+        let Some(function) =
+            enclosing_function(tree.root_node(), kinds, edit.start_byte, edit.new_end_byte)
+        else {
+            return Ok(None);
+        };
+        // Reparsing can change more than the edit did, as an unclosed brace does.
+        let body = function.child_by_field_name("body").unwrap_or(function);
+        let within_body = edited
+            .changed_ranges(tree)
+            .all(|range| body.start_byte() < range.start_byte && range.end_byte < body.end_byte());
+        if old_function.start_byte() != function.start_byte()
+            || old_function.kind() != function.kind()
+            || !within_body
+        {
+            return Ok(None);
+        }
+
+        let old_position = Position::from_node(&old_function);
+        let old_scopes = &self.context.scopes;
+        let Some(old_scope) = old_scopes.scopes.values().find(|scope| {
+            scope.position == old_position && scope.scope_type == ScopeType::Function
+        }) else {
+            return Ok(None);
+        };
+        let old_subtree = subtree(old_scopes, old_scope.id);
+
+        let fragment =
+            language_factory::analyze_code_unified(self.language.clone(), content, function)?;
+        let fragment_root = fragment.scopes.get_scope(fragment.scopes.root);
+        let Some(&[fragment_scope]) = fragment_root.map(|scope| scope.children.as_slice()) else {
+            return Ok(None);
+        };
+        let escapes = old_subtree
+            .iter()
+            .filter_map(|id| old_scopes.get_scope(*id))
+            .chain(fragment.scopes.scopes.values())
+            .any(|scope| ESCAPING_SCOPES.contains(&scope.scope_type));
+        let outside_function = |scope_id: Option<ScopeId>| scope_id == Some(fragment.scopes.root);
+        let declared_outside = fragment
+            .definitions
+            .get_all_definitions()
+            .values()
+            .flatten()
+            .any(|definition| outside_function(definition.scope_id));
+        let used_outside = fragment
+            .usages
+            .get_all_usages()
+            .iter()
+            .any(|usage| outside_function(usage.scope_id));
+        if escapes || declared_outside || used_outside {
+            return Ok(None);
+        }
+
+        // The scopes in the order a traversal of the whole file would create them, so they are
+        // numbered as they would be.
+        let mut scopes = ScopeTree::new();
+        let mut renumbered = HashMap::from([(old_scopes.root, scopes.root)]);
+        let mut fragment_renumbered = HashMap::new();
+        copy_scopes(
+            old_scopes,
+            old_scopes.root,
+            scopes.root,
+            &mut scopes,
+            &mut renumbered,
+            &mut |scopes, new_parent| {
+                fragment_renumbered.insert(fragment.scopes.root, new_parent);
+                graft_scopes(
+                    &fragment.scopes,
+                    fragment_scope,
+                    new_parent,
+                    scopes,
+                    &mut fragment_renumbered,
+                );
+            },
+            old_scope.id,
+            edit,
+        );
+
+        let in_function = |scope_id: Option<ScopeId>| {
+            scope_id.is_some_and(|scope_id| old_subtree.contains(&scope_id))
+        };
+        let before_function = |position: &Position| {
+            (position.start_line, position.start_column)
+                < (old_position.start_line, old_position.start_column)
+        };
+        let renumber = |scope_id: Option<ScopeId>, renumbered: &HashMap<ScopeId, ScopeId>| {
+            scope_id.and_then(|scope_id| renumbered.get(&scope_id).copied())
+        };
+
+        // The definitions the edit could have changed, with the positions they would have now.
+        let mut declared: BTreeMap<(String, DefinitionType, Position), isize> = BTreeMap::new();
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for definition in self
+            .context
+            .definitions
+            .get_all_definitions()
+            .values()
+            .flatten()
+        {
+            let mut definition = definition.clone();
+            definition.position = shift(&definition.position, edit);
+            if in_function(definition.scope_id) {
+                let key = (
+                    definition.name,
+                    definition.definition_type,
+                    definition.position,
+                );
+                *declared.entry(key).or_default() += 1;
+                continue;
+            }
+            definition.scope_id = renumber(definition.scope_id, &renumbered);
+            if before_function(&definition.position) {
+                before.push(definition);
+            } else {
+                after.push(definition);
+            }
+        }
+        let mut definitions = DefinitionRegistry::new();
+        for definition in before {
+            definitions.add_definition(definition.name.clone(), definition);
+        }
+        for definition in fragment
+            .definitions
+            .get_all_definitions()
+            .values()
+            .flatten()
+        {
+            let key = (
+                definition.name.clone(),
+                definition.definition_type.clone(),
+                definition.position,
+            );
+            *declared.entry(key).or_default() -= 1;
+            let mut definition = definition.clone();
+            definition.scope_id = renumber(definition.scope_id, &fragment_renumbered);
+            definitions.add_definition(definition.name.clone(), definition);
+        }
+        for definition in after {
+            definitions.add_definition(definition.name.clone(), definition);
+        }
+        // A usage resolves among the definitions sharing its name, so one named as none of these
+        // resolves as it did.
+        let redeclared: HashSet<String> = declared
+            .into_iter()
+            .filter(|(_, count)| *count != 0)
+            .map(|((name, _, _), _)| name)
+            .collect();
+
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        let old_usages = self.context.usages.get_all_usages();
+        for (usage, dependencies) in old_usages.iter().zip(&self.usage_dependencies) {
+            if in_function(usage.scope_id) {
+                continue;
+            }
+            let mut usage = usage.clone();
+            usage.position = shift(&usage.position, edit);
+            usage.scope_id = renumber(usage.scope_id, &renumbered);
+            let dependencies = (!redeclared.contains(&usage.name)).then(|| {
+                dependencies
+                    .iter()
+                    .map(|dependency| shift_dependency(dependency, edit))
+                    .collect()
+            });
+            if before_function(&usage.position) {
+                before.push((usage, dependencies));
+            } else {
+                after.push((usage, dependencies));
+            }
+        }
+        let fragment_usages = fragment.usages.get_all_usages().iter().map(|usage| {
+            let mut usage = usage.clone();
+            usage.scope_id = renumber(usage.scope_id, &fragment_renumbered);
+            (usage, None)
+        });
+
+        let mut usages = UsageRegistry::new();
+        let mut usage_dependencies = Vec::new();
+        for (usage, dependencies) in before.into_iter().chain(fragment_usages).chain(after) {
+            usages.add_usage(usage);
+            usage_dependencies.push(dependencies);
+        }
+
+        Ok(Some((
+            CodeAnalysisContext {
+                definitions,
+                usages,
+                scopes,
+            },
+            usage_dependencies,
+        )))
+    }
+}
+
+/// The innermost function whose body holds the bytes from `start_byte` to `end_byte`, short of the
+/// delimiters of the body.
+fn enclosing_function<'tree>(
+    root: Node<'tree>,
+    kinds: &[&str],
+    start_byte: usize,
+    end_byte: usize,
+) -> Option<Node<'tree>> {
+    let mut node = root.descendant_for_byte_range(start_byte, end_byte);
+    while let Some(current) = node {
+        if kinds.contains(&current.kind()) {
+            let holds_edit = current.child_by_field_name("body").is_some_and(|body| {
+                matches!(body.kind(), "block" | "statement_block")
+                    && body.start_byte() < start_byte
+                    && end_byte < body.end_byte()
+            });
+            if holds_edit {
+                return Some(current);
+            }
+        }
+        node = current.parent();
+    }
+    None
+}
+
+/// A scope and every scope inside it.
+fn subtree(scopes: &ScopeTree, scope_id: ScopeId) -> HashSet<ScopeId> {
+    let mut found = HashSet::from([scope_id]);
+    let mut pending = vec![scope_id];
+    while let Some(id) = pending.pop() {
+        for child in scopes
+            .get_scope(id)
+            .map_or(&[][..], |scope| &scope.children)
+        {
+            found.insert(*child);
+            pending.push(*child);
+        }
+    }
+    found
+}
+
+/// Copy the scopes under `old_id` to under `new_parent`, moved by the edit, with `graft` creating
+/// those of the function extracted again in place of the `replaced` scope's.
+#[allow(clippy::too_many_arguments)]
+fn copy_scopes(
+    old: &ScopeTree,
+    old_id: ScopeId,
+    new_parent: ScopeId,
+    scopes: &mut ScopeTree,
+    renumbered: &mut HashMap<ScopeId, ScopeId>,
+    graft: &mut impl FnMut(&mut ScopeTree, ScopeId),
+    replaced: ScopeId,
+    edit: &InputEdit,
+) {
+    let Some(scope) = old.get_scope(old_id) else {
+        return;
+    };
+    for &child in &scope.children {
+        if child == replaced {
+            graft(scopes, new_parent);
+            continue;
+        }
+        let Some(child_scope) = old.get_scope(child) else {
+            continue;
+        };
+        let new_id = scopes.create_scope(
+            Some(new_parent),
+            child_scope.scope_type.clone(),
+            shift(&child_scope.position, edit),
+        );
+        renumbered.insert(child, new_id);
+        copy_scopes(
+            old, child, new_id, scopes, renumbered, graft, replaced, edit,
+        );
+    }
+}
+
+/// Copy a scope and those under it to under `new_parent`.
+fn graft_scopes(
+    fragment: &ScopeTree,
+    fragment_id: ScopeId,
+    new_parent: ScopeId,
+    scopes: &mut ScopeTree,
+    renumbered: &mut HashMap<ScopeId, ScopeId>,
+) {
+    let Some(scope) = fragment.get_scope(fragment_id) else {
+        return;
+    };
+    let new_id = scopes.create_scope(Some(new_parent), scope.scope_type.clone(), scope.position);
+    renumbered.insert(fragment_id, new_id);
+    for &child in &scope.children {
+        graft_scopes(fragment, child, new_id, scopes, renumbered);
+    }
+}
+
+/// The row and column of a byte of a text.
+fn point(text: &str, byte: usize) -> Point {
+    let before = &text[..byte];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    Point::new(before.matches('\n').count(), byte - line_start)
+}
+
+/// Where a line and column, both from one, are after an edit that does not contain them.
+fn shift_point(line: usize, column: usize, edit: &InputEdit) -> (usize, usize) {
+    let (old_end, new_end) = (edit.old_end_position, edit.new_end_position);
+    let (row, column) = (line - 1, column - 1);
+    if (row, column) < (old_end.row, old_end.column) {
+        (line, column + 1)
+    } else if row == old_end.row {
+        (
+            new_end.row + 1,
+            new_end.column + column - old_end.column + 1,
+        )
+    } else {
+        (row - old_end.row + new_end.row + 1, column + 1)
+    }
+}
+
+fn shift(position: &Position, edit: &InputEdit) -> Position {
+    let (start_line, start_column) = shift_point(position.start_line, position.start_column, edit);
+    let (end_line, end_column) = shift_point(position.end_line, position.end_column, edit);
+    Position {
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    }
+}
+
+/// A dependency of a usage the edit does not contain, moved with it, along with the position its
+/// context names, as in `Identifier:12:5`.
+fn shift_dependency(dependency: &Dependency, edit: &InputEdit) -> Dependency {
+    let mut dependency = dependency.clone();
+    let Position {
+        start_line,
+        start_column,
+        ..
+    } = dependency.source_span;
+    dependency.source_span = shift(&dependency.source_span, edit);
+    if let Some(kind) = dependency
+        .context
+        .as_deref()
+        .and_then(|context| context.strip_suffix(&format!(":{start_line}:{start_column}")))
+    {
+        let span = dependency.source_span;
+        dependency.context = Some(format!("{kind}:{}:{}", span.start_line, span.start_column));
+    }
+    dependency.source_line = dependency.source_span.start_line;
+    dependency.target_span = shift(&dependency.target_span, edit);
+    dependency.target_line = dependency.target_span.start_line;
+    dependency
+}
@@ -0,0 +1,131 @@
+//! Statements as the nodes of the dependency graph in place of lines, so that formatting a file
+//! does not change its metrics.
+//!
+//! Split over eight lines, a builder chain is eight lines depending on each other; as one statement
+//! it is one node, however rustfmt or prettier lay it out. A statement is a node written directly in
+//! a file, a block or the body of an item, such as a `let`, an expression statement, a function or a
+//! match arm. Each line belongs to the innermost statement spanning it, and each statement is
+//! numbered in source order, so that the distance between two is counted in statements. The metrics
+//! of a statement are reported against its first line.
+
+use crate::models::{IntermediateRepresentation, Language};
+use std::collections::BTreeSet;
+use tree_sitter::Node;
+
+/// The kinds of node whose children are statements, besides the root.
+fn statement_containers(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &[
+            "block",
+            "declaration_list",
+            "field_declaration_list",
+            "enum_variant_list",
+            "match_block",
+        ],
+        Language::TypeScript | Language::TSX => &[
+            "statement_block",
+            "class_body",
+            "interface_body",
+            "object_type",
+            "enum_body",
+            "switch_body",
+            "switch_case",
+        ],
+    }
+}
+
+/// The statements of a file, by the lines they span.
+#[derive(Debug, Clone)]
+pub struct Statements {
+    /// For each line, from the first, the first line of the statement it is in, or its own where
+    /// it is in none, as a blank line is.
+    first_lines: Vec<usize>,
+    /// The first line of each statement, in source order; a statement's number is its index here,
+    /// plus one.
+    starts: Vec<usize>,
+}
+
+impl Statements {
+    /// The statements of a file of `line_count` lines from its syntax tree.
+    pub fn find(root: Node, language: &Language, line_count: usize) -> Self {
+        let mut first_lines: Vec<usize> = (1..=line_count).collect();
+        mark(root, statement_containers(language), &mut first_lines);
+        let starts: Vec<usize> = first_lines
+            .iter()
+            .copied()
+            .collect::<BTreeSet<usize>>()
+            .into_iter()
+            .collect();
+        Statements {
+            first_lines,
+            starts,
+        }
+    }
+
+    /// The first line of the statement a line is in.
+    pub fn first_line(&self, line: usize) -> usize {
+        line.checked_sub(1)
+            .and_then(|index| self.first_lines.get(index))
+            .copied()
+            .unwrap_or(line)
+    }
+
+    /// The first line of a statement, by number.
+    pub fn start(&self, statement: usize) -> usize {
+        self.starts[statement - 1]
+    }
+
+    /// The number of the statement a line is in.
+    fn number(&self, line: usize) -> usize {
+        let first_line = self.first_line(line);
+        self.starts
+            .binary_search(&first_line)
+            .map_or(first_line, |index| index + 1)
+    }
+
+    /// The IR with each statement in place of its lines, numbered as a line would be, so that the
+    /// line graph built from it is one of statements.
+    ///
+    /// What a statement depends on within itself is not a dependency, unless a line depended on
+    /// itself already, as a recursive call written on one line does. Lines of other files are left
+    /// as they are.
+    pub fn collapse(&self, ir: &IntermediateRepresentation) -> IntermediateRepresentation {
+        let mut collapsed = ir.clone();
+        collapsed.analysis_metadata.total_lines = self.starts.len();
+        collapsed.dependencies = ir
+            .dependencies
+            .iter()
+            .filter_map(|dependency| {
+                let mut dependency = dependency.clone();
+                let source = self.number(dependency.source_line);
+                if !dependency.is_cross_file() {
+                    let target = self.number(dependency.target_line);
+                    if source == target && dependency.source_line != dependency.target_line {
+                        return None;
+                    }
+                    dependency.target_line = target;
+                }
+                dependency.source_line = source;
+                Some(dependency)
+            })
+            .collect();
+        collapsed
+    }
+}
+
+/// Note the first line of every statement at or under `node` against the lines it spans, inner
+/// statements after the ones they are in.
+fn mark(node: Node, containers: &[&str], first_lines: &mut [usize]) {
+    let is_container = node.parent().is_none() || containers.contains(&node.kind());
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_container && child.is_named() && !child.is_extra() {
+            let first_line = child.start_position().row + 1;
+            let last_line = child.end_position().row + 1;
+            for line in first_line..=last_line.min(first_lines.len()) {
+                first_lines[line - 1] = first_line;
+            }
+        }
+        mark(child, containers, first_lines);
+    }
+}
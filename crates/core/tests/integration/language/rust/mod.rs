@@ -1,6 +1,5 @@
 use insta::assert_snapshot;
 use lintric_core::analyze_code;
-use std::env;
 use std::fs;
 
 macro_rules! test_rust_analysis {
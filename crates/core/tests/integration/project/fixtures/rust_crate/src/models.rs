@@ -0,0 +1,8 @@
+mod role;
+
+pub use role::*;
+
+pub struct User {
+    pub name: String,
+    pub role: Role,
+}
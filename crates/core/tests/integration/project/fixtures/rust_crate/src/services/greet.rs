@@ -0,0 +1,15 @@
+use super::super::models::default_role;
+use crate::models::{self, Role};
+use crate::User as Person;
+
+pub fn build_user(name: &str) -> Person {
+    let role: Role = default_role();
+    Person {
+        name: name.to_string(),
+        role,
+    }
+}
+
+pub fn is_admin(user: &models::User) -> bool {
+    matches!(user.role, Role::Admin)
+}
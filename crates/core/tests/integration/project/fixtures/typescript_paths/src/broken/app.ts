@@ -0,0 +1,4 @@
+import { format } from "../lib/format";
+import { settings } from "@config";
+
+export const label = format(settings.name);
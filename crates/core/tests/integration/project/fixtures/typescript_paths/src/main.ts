@@ -0,0 +1,11 @@
+import { User } from "@app/models/user";
+import { settings } from "@config";
+import { format } from "lib/format";
+import { gone } from "@missing/thing";
+import { alsoGone } from "./nowhere";
+import { parse } from "some-package";
+
+export function main(): string {
+  const user = new User();
+  return format(settings.name + user.name + gone + alsoGone + parse());
+}
@@ -0,0 +1,8 @@
+export enum Role {
+  Admin,
+  Member,
+}
+
+const DEFAULT_ROLE = Role.Member;
+
+export { DEFAULT_ROLE as defaultRole };
@@ -0,0 +1,10 @@
+import { Role } from "./role";
+
+export interface User {
+  name: string;
+  role: Role;
+}
+
+export function createUser(name: string, role: Role): User {
+  return { name, role };
+}
@@ -0,0 +1,10 @@
+import { createUser, User } from "../models";
+import { fallbackRole as role } from "../models/index";
+import Logger from "./logger.js";
+import { readFileSync } from "fs";
+
+export function greet(name: string): User {
+  const user = createUser(name, role);
+  new Logger().log(readFileSync(name, "utf8"));
+  return user;
+}
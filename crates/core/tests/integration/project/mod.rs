@@ -0,0 +1,3 @@
+pub mod rust_crate_tests;
+pub mod typescript_paths_tests;
+pub mod typescript_workspace_tests;
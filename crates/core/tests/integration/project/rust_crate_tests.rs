@@ -0,0 +1,146 @@
+use lintric_core::models::{Dependency, DependencyType};
+use lintric_core::{analyze_crate, AnalysisResult, IntermediateRepresentation};
+
+const ROOT: &str = "tests/integration/project/fixtures/rust_crate/src";
+
+fn analyze() -> Vec<(IntermediateRepresentation, AnalysisResult)> {
+    analyze_crate(format!("{ROOT}/lib.rs")).unwrap().results
+}
+
+fn cross_file_edges<'a>(
+    analyzed: &'a [(IntermediateRepresentation, AnalysisResult)],
+    file: &str,
+) -> Vec<&'a Dependency> {
+    let (ir, _) = analyzed
+        .iter()
+        .find(|(ir, _)| ir.file_path == format!("{ROOT}/{file}"))
+        .unwrap_or_else(|| panic!("{file} was not analyzed"));
+
+    ir.dependencies
+        .iter()
+        .filter(|dependency| dependency.is_cross_file())
+        .collect()
+}
+
+#[test]
+fn analyzes_every_file_reached_through_mod_declarations() {
+    let files: Vec<String> = analyze()
+        .into_iter()
+        .map(|(ir, _)| ir.file_path.trim_start_matches(ROOT).to_string())
+        .collect();
+
+    assert_eq!(
+        files,
+        vec![
+            "/lib.rs",
+            "/models.rs",
+            "/models/role.rs",
+            "/services/greet.rs",
+            "/services/mod.rs",
+        ]
+    );
+}
+
+#[test]
+fn links_a_use_to_the_file_declaring_the_item() {
+    let analyzed = analyze();
+    let edges = cross_file_edges(&analyzed, "services/greet.rs");
+
+    // `use super::super::models::default_role;` on line 1 reaches `fn default_role` on line 6.
+    assert!(
+        edges.iter().any(|edge| edge.source_line == 1
+            && edge.target_line == 6
+            && edge.dependency_type == DependencyType::Import
+            && edge.target_file.as_deref() == Some(&format!("{ROOT}/models/role.rs"))),
+        "Missing edge from the import to role.rs. Found: {edges:?}"
+    );
+}
+
+#[test]
+fn follows_re_exports_to_where_the_item_is_declared() {
+    let analyzed = analyze();
+    let edges = cross_file_edges(&analyzed, "services/greet.rs");
+
+    // `Role` is imported from `models`, which re-exports it from `role` with a glob.
+    assert!(
+        edges.iter().any(|edge| edge.symbol == "Role"
+            && edge.source_line == 2
+            && edge.target_line == 1
+            && edge.target_file.as_deref() == Some(&format!("{ROOT}/models/role.rs"))),
+        "Missing edge through the glob re-export. Found: {edges:?}"
+    );
+}
+
+#[test]
+fn links_usages_of_an_alias_to_the_declaration() {
+    let analyzed = analyze();
+    let edges = cross_file_edges(&analyzed, "services/greet.rs");
+
+    // `use crate::User as Person;` goes through `pub use models::User;` in lib.rs.
+    let person_usages: Vec<_> = edges
+        .iter()
+        .filter(|edge| edge.symbol == "Person" && edge.source_line != 3)
+        .collect();
+
+    assert!(!person_usages.is_empty());
+    assert!(person_usages.iter().all(|edge| edge.target_line == 5
+        && edge.dependency_type == DependencyType::TypeReference
+        && edge.target_file.as_deref() == Some(&format!("{ROOT}/models.rs"))));
+}
+
+#[test]
+fn counts_edges_into_other_files_in_the_metrics() {
+    let analyzed = analyze();
+    let (_, result) = analyzed
+        .iter()
+        .find(|(ir, _)| ir.file_path.ends_with("services/greet.rs"))
+        .unwrap();
+
+    // Line 1 is an import and depends on nothing within its own file.
+    let import_line = result
+        .line_metrics
+        .iter()
+        .find(|metrics| metrics.line_number == 1)
+        .unwrap();
+    assert_eq!(import_line.total_dependencies, 1);
+    assert_eq!(import_line.dependency_distance_cost, 1.0);
+}
+
+#[test]
+fn analyzes_the_other_modules_when_one_cannot_be_read() {
+    let dir = std::env::temp_dir().join(format!("lintric-broken-module-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("lib.rs"),
+        "mod broken;\nmod helpers;\n\nuse helpers::helper;\n\npub fn run() -> u32 {\n    helper()\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("helpers.rs"),
+        "pub fn helper() -> u32 {\n    1\n}\n",
+    )
+    .unwrap();
+    // Not UTF-8, so it cannot be read.
+    std::fs::write(dir.join("broken.rs"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let analyzed = analyze_crate(dir.join("lib.rs").to_string_lossy().into_owned());
+    std::fs::remove_dir_all(&dir).unwrap();
+    let analyzed = analyzed.unwrap();
+
+    assert_eq!(analyzed.failed_files.len(), 1);
+    assert!(analyzed.failed_files[0].0.ends_with("broken.rs"));
+    let files: Vec<&str> = analyzed
+        .results
+        .iter()
+        .map(|(ir, _)| ir.file_path.rsplit('/').next().unwrap())
+        .collect();
+    assert_eq!(files, vec!["helpers.rs", "lib.rs"]);
+    let (lib, _) = &analyzed.results[1];
+    assert!(
+        lib.dependencies
+            .iter()
+            .any(|dependency| dependency.is_cross_file() && dependency.symbol == "helper"),
+        "Missing edge into helpers.rs. Found: {:?}",
+        lib.dependencies
+    );
+}
@@ -0,0 +1,115 @@
+use lintric_core::analyze_workspace;
+use lintric_core::models::{Dependency, UnresolvedModule};
+
+const ROOT: &str = "tests/integration/project/fixtures/typescript_paths/src";
+
+const FILES: [&str; 4] = [
+    "config/index.ts",
+    "lib/format.ts",
+    "main.ts",
+    "models/user.ts",
+];
+
+fn analyze_main() -> lintric_core::IntermediateRepresentation {
+    let file_paths: Vec<String> = FILES.iter().map(|file| format!("{ROOT}/{file}")).collect();
+
+    analyze_workspace(file_paths)
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|(ir, _)| ir)
+        .find(|ir| ir.file_path.ends_with("main.ts"))
+        .unwrap()
+}
+
+fn import_edge(dependencies: &[Dependency], line: usize) -> Option<&Dependency> {
+    dependencies
+        .iter()
+        .find(|dependency| dependency.is_cross_file() && dependency.source_line == line)
+}
+
+#[test]
+fn maps_a_wildcard_paths_alias_through_an_extended_config() {
+    let ir = analyze_main();
+
+    // `@app/*` is `src/*`, set in `tsconfig.base.json`, which `tsconfig.json` extends.
+    let edge = import_edge(&ir.dependencies, 1).expect("`@app/models/user` was not resolved");
+    assert_eq!(
+        edge.target_file.as_deref(),
+        Some(&format!("{ROOT}/models/user.ts")[..])
+    );
+}
+
+#[test]
+fn maps_an_exact_paths_alias() {
+    let ir = analyze_main();
+
+    let edge = import_edge(&ir.dependencies, 2).expect("`@config` was not resolved");
+    assert_eq!(
+        edge.target_file.as_deref(),
+        Some(&format!("{ROOT}/config/index.ts")[..])
+    );
+}
+
+#[test]
+fn looks_for_a_bare_specifier_under_the_base_url() {
+    let ir = analyze_main();
+
+    let edge = import_edge(&ir.dependencies, 3).expect("`lib/format` was not resolved");
+    assert_eq!(
+        edge.target_file.as_deref(),
+        Some(&format!("{ROOT}/lib/format.ts")[..])
+    );
+}
+
+#[test]
+fn records_modules_that_should_be_project_files_but_are_not() {
+    let ir = analyze_main();
+
+    // `some-package` on line 6 is a package, which is not expected among the files.
+    assert_eq!(
+        ir.unresolved_modules,
+        vec![
+            UnresolvedModule {
+                line: 4,
+                specifier: "@missing/thing".to_string(),
+            },
+            UnresolvedModule {
+                line: 5,
+                specifier: "./nowhere".to_string(),
+            },
+        ]
+    );
+    assert!(import_edge(&ir.dependencies, 4).is_none());
+}
+
+#[test]
+fn resolves_below_an_unreadable_config_without_its_mappings() {
+    let mut file_paths: Vec<String> = FILES.iter().map(|file| format!("{ROOT}/{file}")).collect();
+    file_paths.push(format!("{ROOT}/broken/app.ts"));
+
+    let analyzed = analyze_workspace(file_paths).unwrap();
+
+    // `src/broken/tsconfig.json` is cut off, which leaves the other files as they were.
+    assert_eq!(analyzed.config_errors.len(), 1);
+    assert!(analyzed.config_errors[0]
+        .0
+        .ends_with("src/broken/tsconfig.json"));
+    let ir = |file: &str| {
+        analyzed
+            .results
+            .iter()
+            .map(|(ir, _)| ir)
+            .find(|ir| ir.file_path.ends_with(file))
+            .unwrap()
+    };
+    assert!(import_edge(&ir("main.ts").dependencies, 2).is_some());
+
+    let app = ir("broken/app.ts");
+    let edge = import_edge(&app.dependencies, 1).expect("`../lib/format` was not resolved");
+    assert_eq!(
+        edge.target_file.as_deref(),
+        Some(&format!("{ROOT}/lib/format.ts")[..])
+    );
+    assert!(import_edge(&app.dependencies, 2).is_none());
+}
@@ -0,0 +1,143 @@
+use lintric_core::models::{Dependency, DependencyType};
+use lintric_core::{analyze_workspace, AnalysisResult, IntermediateRepresentation};
+
+const ROOT: &str = "tests/integration/project/fixtures/typescript_workspace/src";
+
+const FILES: [&str; 5] = [
+    "models/index.ts",
+    "models/role.ts",
+    "models/user.ts",
+    "services/greet.ts",
+    "services/logger.ts",
+];
+
+fn analyze() -> Vec<(IntermediateRepresentation, AnalysisResult)> {
+    // Given out of order, to show the results come back in path order regardless.
+    let mut file_paths: Vec<String> = FILES.iter().map(|file| format!("{ROOT}/{file}")).collect();
+    file_paths.reverse();
+
+    analyze_workspace(file_paths).unwrap().results
+}
+
+fn cross_file_edges<'a>(
+    analyzed: &'a [(IntermediateRepresentation, AnalysisResult)],
+    file: &str,
+) -> Vec<&'a Dependency> {
+    let (ir, _) = analyzed
+        .iter()
+        .find(|(ir, _)| ir.file_path == format!("{ROOT}/{file}"))
+        .unwrap_or_else(|| panic!("{file} was not analyzed"));
+
+    ir.dependencies
+        .iter()
+        .filter(|dependency| dependency.is_cross_file())
+        .collect()
+}
+
+fn has_edge(
+    edges: &[&Dependency],
+    symbol: &str,
+    source_line: usize,
+    target: (&str, usize),
+) -> bool {
+    edges.iter().any(|edge| {
+        edge.symbol == symbol
+            && edge.source_line == source_line
+            && edge.target_line == target.1
+            && edge.target_file.as_deref() == Some(&format!("{ROOT}/{}", target.0))
+    })
+}
+
+#[test]
+fn analyzes_every_file_in_path_order() {
+    let files: Vec<String> = analyze()
+        .into_iter()
+        .map(|(ir, _)| {
+            ir.file_path
+                .trim_start_matches(&format!("{ROOT}/"))
+                .to_string()
+        })
+        .collect();
+
+    assert_eq!(files, FILES);
+}
+
+#[test]
+fn follows_an_import_through_an_index_barrel_and_export_star() {
+    let analyzed = analyze();
+    let edges = cross_file_edges(&analyzed, "services/greet.ts");
+
+    // `from "../models"` is `models/index.ts`, which has `export * from "./user"`.
+    assert!(
+        has_edge(&edges, "createUser", 1, ("models/user.ts", 8)),
+        "Missing edge from the import to user.ts. Found: {edges:?}"
+    );
+    assert!(
+        has_edge(&edges, "User", 1, ("models/user.ts", 3)),
+        "Missing edge from the import to user.ts. Found: {edges:?}"
+    );
+}
+
+#[test]
+fn follows_renaming_re_exports_to_the_declaration() {
+    let analyzed = analyze();
+    let edges = cross_file_edges(&analyzed, "services/greet.ts");
+
+    // `fallbackRole` is `export { defaultRole as fallbackRole } from "./role"`, and `defaultRole`
+    // is `export { DEFAULT_ROLE as defaultRole }` of the constant on line 6.
+    assert!(
+        has_edge(&edges, "role", 2, ("models/role.ts", 6)),
+        "Missing edge through the renaming re-exports. Found: {edges:?}"
+    );
+
+    let barrel = cross_file_edges(&analyzed, "models/index.ts");
+    assert!(
+        has_edge(&barrel, "Role", 2, ("models/role.ts", 1)),
+        "Missing edge from the re-export to role.ts. Found: {barrel:?}"
+    );
+}
+
+#[test]
+fn links_usages_of_imports_to_the_declaration() {
+    let analyzed = analyze();
+    let edges = cross_file_edges(&analyzed, "services/greet.ts");
+
+    assert!(edges.iter().any(|edge| edge.symbol == "createUser"
+        && edge.source_line == 7
+        && edge.dependency_type == DependencyType::FunctionCall
+        && edge.target_file.as_deref() == Some(&format!("{ROOT}/models/user.ts"))));
+
+    // A default import, written with the `.js` extension the compiled file will have.
+    assert!(
+        has_edge(&edges, "Logger", 8, ("services/logger.ts", 1)),
+        "Missing edge from the default import's usage. Found: {edges:?}"
+    );
+}
+
+#[test]
+fn leaves_imports_from_packages_within_the_file() {
+    let analyzed = analyze();
+    let edges = cross_file_edges(&analyzed, "services/greet.ts");
+
+    assert!(edges.iter().all(|edge| edge.symbol != "readFileSync"));
+}
+
+#[test]
+fn analyzes_the_other_files_when_one_cannot_be_read() {
+    let mut file_paths: Vec<String> = FILES.iter().map(|file| format!("{ROOT}/{file}")).collect();
+    file_paths.push(format!("{ROOT}/services/missing.ts"));
+
+    let analyzed = analyze_workspace(file_paths).unwrap();
+
+    assert_eq!(analyzed.failed_files.len(), 1);
+    assert_eq!(
+        analyzed.failed_files[0].0,
+        format!("{ROOT}/services/missing.ts")
+    );
+    assert_eq!(analyzed.results.len(), FILES.len());
+    let edges = cross_file_edges(&analyzed.results, "services/greet.ts");
+    assert!(
+        has_edge(&edges, "Logger", 8, ("services/logger.ts", 1)),
+        "Missing edge from the default import's usage. Found: {edges:?}"
+    );
+}
@@ -0,0 +1,98 @@
+use crate::unit::helpers::dependency;
+use lintric_core::cycles::find_line_cycles;
+use lintric_core::models::{DefinitionCycle, IntermediateRepresentation, LineCycle};
+use lintric_core::{analyze_content, Language};
+
+fn cycle(items: &[&str], symbols: &[&str]) -> DefinitionCycle {
+    DefinitionCycle {
+        items: items.iter().map(|item| item.to_string()).collect(),
+        symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_line_cycles_are_strongly_connected_lines() {
+    let ir = IntermediateRepresentation::new(
+        "test.rs".to_string(),
+        vec![],
+        vec![
+            dependency(2, 1, "a"),
+            dependency(3, 4, "c"),
+            dependency(4, 5, "d"),
+            dependency(5, 3, "b"),
+            dependency(6, 6, "e"),
+            dependency(6, 5, "d"),
+        ],
+        vec![],
+        "Rust".to_string(),
+        6,
+    );
+
+    assert_eq!(
+        find_line_cycles(&ir),
+        vec![
+            LineCycle {
+                lines: vec![3, 4, 5],
+                symbols: vec!["b".to_string(), "c".to_string(), "d".to_string()],
+            },
+            LineCycle {
+                lines: vec![6],
+                symbols: vec!["e".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_mutual_and_direct_recursion_are_definition_cycles() {
+    let code = r#"fn is_even(n: u32) -> bool {
+    if n == 0 { true } else { is_odd(n - 1) }
+}
+
+fn is_odd(n: u32) -> bool {
+    if n == 0 { false } else { is_even(n - 1) }
+}
+
+fn factorial(n: u64) -> u64 {
+    if n == 0 { 1 } else { n * factorial(n - 1) }
+}
+
+fn main() {
+    println!("{}", is_even(factorial(3) as u32));
+}
+"#;
+    let (_, result) = analyze_content(code.to_string(), Language::Rust).unwrap();
+
+    assert_eq!(
+        result.definition_cycles,
+        vec![
+            cycle(&["is_even", "is_odd"], &["is_even", "is_odd"]),
+            cycle(&["factorial"], &["factorial"]),
+        ]
+    );
+    assert!(result.line_cycles.is_empty());
+    assert_eq!(result.cycle_count, 2);
+}
+
+#[test]
+fn test_typescript_mutual_recursion_is_a_definition_cycle() {
+    let code = r#"function ping(n: number): number {
+  return n > 0 ? pong(n - 1) : 0;
+}
+
+function pong(n: number): number {
+  return n > 0 ? ping(n - 1) : 1;
+}
+
+function start(): number {
+  return ping(3);
+}
+"#;
+    let (_, result) = analyze_content(code.to_string(), Language::TypeScript).unwrap();
+
+    assert_eq!(
+        result.definition_cycles,
+        vec![cycle(&["ping", "pong"], &["ping", "pong"])]
+    );
+    assert_eq!(result.cycle_count, 1);
+}
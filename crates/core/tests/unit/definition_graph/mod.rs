@@ -0,0 +1,145 @@
+use lintric_core::{get_definition_graph_from_content, DefinitionGraph, Language};
+
+fn names<'a>(graph: &'a DefinitionGraph, name: &str, dependents: bool) -> Vec<&'a str> {
+    let item = graph.item(name).expect("item should be found");
+    let items = if dependents {
+        graph.dependents(item)
+    } else {
+        graph.dependencies(item)
+    };
+    items
+        .into_iter()
+        .map(|item| item.qualified_name.as_str())
+        .collect()
+}
+
+const RUST_CODE: &str = r#"const LIMIT: usize = 10;
+
+struct Token {
+    text: String,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Parser { tokens }
+    }
+
+    fn parse(&self) -> usize {
+        let count = self.tokens.len();
+        limit(count)
+    }
+}
+
+fn limit(count: usize) -> usize {
+    count.min(LIMIT)
+}
+
+mod helpers {
+    pub fn run() -> usize {
+        let parser = super::Parser::new(vec![]);
+        parser.parse()
+    }
+}
+"#;
+
+const TYPESCRIPT_CODE: &str = r#"const LIMIT = 10;
+
+interface Token {
+  text: string;
+}
+
+class Parser {
+  tokens: Token[];
+
+  constructor(tokens: Token[]) {
+    this.tokens = tokens;
+  }
+
+  parse(): number {
+    return limit(this.tokens.length);
+  }
+}
+
+const limit = (count: number): number => {
+  return Math.min(count, LIMIT);
+};
+
+function run(): number {
+  const parser = new Parser([]);
+  return parser.parse();
+}
+"#;
+
+#[test]
+fn test_rust_items_are_qualified_by_impl_and_module() {
+    let graph = get_definition_graph_from_content(RUST_CODE.to_string(), Language::Rust).unwrap();
+    let items: Vec<(&str, usize, usize)> = graph
+        .items
+        .iter()
+        .map(|item| (item.qualified_name.as_str(), item.start_line, item.end_line))
+        .collect();
+    assert_eq!(
+        items,
+        vec![
+            ("LIMIT", 1, 1),
+            ("Token", 3, 5),
+            ("Parser", 7, 9),
+            ("Parser::new", 12, 14),
+            ("Parser::parse", 16, 19),
+            ("limit", 22, 24),
+            ("helpers", 26, 31),
+            ("helpers::run", 27, 30),
+        ]
+    );
+    let run = graph.item("run").unwrap();
+    assert_eq!(graph.items[run].parent, graph.item("helpers"));
+}
+
+#[test]
+fn test_rust_dependencies_are_lifted_to_items() {
+    let graph = get_definition_graph_from_content(RUST_CODE.to_string(), Language::Rust).unwrap();
+    assert_eq!(
+        names(&graph, "Parser::parse", false),
+        vec!["Parser", "limit"]
+    );
+    assert_eq!(names(&graph, "limit", false), vec!["LIMIT"]);
+    assert_eq!(
+        names(&graph, "helpers::run", false),
+        vec!["Parser", "Parser::new", "Parser::parse"]
+    );
+    assert_eq!(
+        names(&graph, "Parser", true),
+        vec!["Parser::new", "Parser::parse", "helpers::run"]
+    );
+
+    let parser = &graph.items[graph.item("Parser").unwrap()];
+    assert_eq!((parser.fan_in, parser.fan_out), (3, 1));
+    let edge = graph
+        .edges
+        .iter()
+        .find(|edge| {
+            graph.items[edge.source].qualified_name == "Parser::new"
+                && graph.items[edge.target].name == "Parser"
+        })
+        .unwrap();
+    assert_eq!(edge.symbols, vec!["Parser", "tokens"]);
+}
+
+#[test]
+fn test_typescript_arrow_functions_take_their_variable_name() {
+    let graph =
+        get_definition_graph_from_content(TYPESCRIPT_CODE.to_string(), Language::TypeScript)
+            .unwrap();
+    let limit = &graph.items[graph.item("limit").unwrap()];
+    assert_eq!((limit.start_line, limit.end_line), (19, 21));
+    assert_eq!(
+        names(&graph, "Parser.parse", false),
+        vec!["Parser", "limit"]
+    );
+    assert_eq!(names(&graph, "run", false), vec!["Parser", "Parser.parse"]);
+    assert_eq!(names(&graph, "LIMIT", true), vec!["limit"]);
+}
@@ -0,0 +1,56 @@
+use crate::unit::helpers::dependency_of_type;
+use lintric_core::graph_export::{export_graph, GraphFormat, GraphKind};
+use lintric_core::models::{DependencyType, IntermediateRepresentation};
+
+fn sample() -> (IntermediateRepresentation, &'static str) {
+    let code = "fn f() {}\nlet a = \"x\";\nlet b = f() + a + a;\n";
+    let ir = IntermediateRepresentation::new(
+        "test.rs".to_string(),
+        vec![],
+        vec![
+            dependency_of_type(3, 1, "f", DependencyType::FunctionCall),
+            dependency_of_type(3, 2, "a", DependencyType::VariableUse),
+            dependency_of_type(3, 2, "a", DependencyType::VariableUse),
+        ],
+        vec![],
+        "Rust".to_string(),
+        3,
+    );
+    (ir, code)
+}
+
+#[test]
+fn test_line_graph_as_dot() {
+    let (ir, code) = sample();
+    assert_eq!(
+        export_graph(&ir, code, GraphKind::Lines, GraphFormat::Dot),
+        "digraph dependencies {\n    node [shape=box, fontname=\"monospace\"];\n    L1 [label=\"1: fn f() {}\"];\n    L2 [label=\"2: let a = \\\"x\\\";\"];\n    L3 [label=\"3: let b = f() + a + a;\"];\n    L3 -> L1;\n    L3 -> L2;\n}\n"
+    );
+}
+
+#[test]
+fn test_symbol_graph_as_mermaid() {
+    let (ir, code) = sample();
+    assert_eq!(
+        export_graph(&ir, code, GraphKind::Symbols, GraphFormat::Mermaid),
+        "flowchart TD\n    L1[\"1: fn f() {}\"]\n    L2[\"2: let a = #quot;x#quot;;\"]\n    L3[\"3: let b = f() + a + a;\"]\n    L3 -->|\"f: FunctionCall\"| L1\n    L3 -->|\"a: VariableUse\"| L2\n    linkStyle 0 stroke:#1f77b4,color:#1f77b4\n    linkStyle 1 stroke:#2ca02c,color:#2ca02c\n"
+    );
+}
+
+#[test]
+fn test_cross_file_edges_are_not_drawn() {
+    let mut cross_file = dependency_of_type(1, 4, "g", DependencyType::Import);
+    cross_file.target_file = Some("other.rs".to_string());
+    let ir = IntermediateRepresentation::new(
+        "test.rs".to_string(),
+        vec![],
+        vec![cross_file],
+        vec![],
+        "Rust".to_string(),
+        1,
+    );
+    assert_eq!(
+        export_graph(&ir, "use other::g;\n", GraphKind::Symbols, GraphFormat::Dot),
+        "digraph dependencies {\n    node [shape=box, fontname=\"monospace\"];\n}\n"
+    );
+}
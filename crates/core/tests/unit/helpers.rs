@@ -0,0 +1,41 @@
+//! Builders shared by the unit tests, for dependencies written by hand.
+
+use lintric_core::models::{Dependency, DependencyType, Position};
+
+/// A one-character span on a line.
+pub fn span(line: usize) -> Position {
+    Position {
+        start_line: line,
+        start_column: 1,
+        end_line: line,
+        end_column: 2,
+    }
+}
+
+/// A variable use of `symbol` on one line, defined on another of the same file.
+pub fn dependency(source_line: usize, target_line: usize, symbol: &str) -> Dependency {
+    dependency_of_type(
+        source_line,
+        target_line,
+        symbol,
+        DependencyType::VariableUse,
+    )
+}
+
+pub fn dependency_of_type(
+    source_line: usize,
+    target_line: usize,
+    symbol: &str,
+    dependency_type: DependencyType,
+) -> Dependency {
+    Dependency {
+        source_line,
+        target_line,
+        source_span: span(source_line),
+        target_span: span(target_line),
+        symbol: symbol.to_string(),
+        dependency_type,
+        context: None,
+        target_file: None,
+    }
+}
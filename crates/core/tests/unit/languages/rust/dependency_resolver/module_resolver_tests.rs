@@ -175,6 +175,106 @@ fn test_module_path_resolution() {
     );
 }
 
+#[test]
+fn resolves_use_paths_relative_to_the_module_they_are_written_in() {
+    let mut module_tree = ModuleTree::new();
+    let models = module_tree.add_module("models".to_string(), Some(0), None);
+    let services = module_tree.add_module("services".to_string(), Some(0), None);
+    let greet = module_tree.add_module("greet".to_string(), Some(services), None);
+    let resolver = ImportResolver::new(module_tree);
+
+    assert_eq!(
+        resolver.resolve_module_path("crate::models", greet),
+        Some(models)
+    );
+    assert_eq!(
+        resolver.resolve_module_path("super::super::models", greet),
+        Some(models)
+    );
+    assert_eq!(resolver.resolve_module_path("self", greet), Some(greet));
+    // A bare path starts among the current module's children, then at the crate root.
+    assert_eq!(resolver.resolve_module_path("greet", services), Some(greet));
+    assert_eq!(resolver.resolve_module_path("models", greet), Some(models));
+    assert_eq!(
+        resolver.resolve_module_path("std::collections", greet),
+        None
+    );
+}
+
+#[test]
+fn follows_re_exports_to_the_declaring_module() {
+    let mut module_tree = ModuleTree::new();
+    let models = module_tree.add_module("models".to_string(), Some(0), None);
+    let role = module_tree.add_module("role".to_string(), Some(models), None);
+    let user = module_tree.add_module("user".to_string(), Some(models), None);
+    let role_def = Definition::new_simple(
+        "Role".to_string(),
+        DefinitionType::EnumDefinition,
+        position(1),
+    );
+    let user_def = Definition::new_simple(
+        "User".to_string(),
+        DefinitionType::StructDefinition,
+        position(3),
+    );
+    module_tree.add_export(role, "Role".to_string(), role_def.clone());
+    module_tree.add_export(user, "User".to_string(), user_def.clone());
+    // `pub use role::*;` and `pub use user::User as Account;` in `models`.
+    module_tree.add_import(models, import("*", "role", None, ImportType::Wildcard));
+    module_tree.add_import(
+        models,
+        import(
+            "User",
+            "user",
+            Some("Account"),
+            ImportType::Named("User".to_string()),
+        ),
+    );
+    let resolver = ModuleResolver::from_module_tree(module_tree);
+
+    let use_role = import(
+        "Role",
+        "crate::models",
+        None,
+        ImportType::Named("Role".to_string()),
+    );
+    let use_account = import(
+        "Account",
+        "crate::models",
+        None,
+        ImportType::Named("Account".to_string()),
+    );
+
+    assert_eq!(
+        resolver.resolve_imported_item(&use_role, 0),
+        Some((role, role_def))
+    );
+    assert_eq!(
+        resolver.resolve_imported_item(&use_account, 0),
+        Some((user, user_def))
+    );
+}
+
+fn import(symbol: &str, source: &str, alias: Option<&str>, import_type: ImportType) -> ImportInfo {
+    ImportInfo {
+        imported_symbol: symbol.to_string(),
+        source_module: source.to_string(),
+        alias: alias.map(str::to_string),
+        import_type,
+        visibility: Visibility::Public,
+        position: position(1),
+    }
+}
+
+fn position(line: usize) -> Position {
+    Position {
+        start_line: line,
+        start_column: 1,
+        end_line: line,
+        end_column: 1,
+    }
+}
+
 #[test]
 fn test_module_exports() {
     let mut module_tree = ModuleTree::new();
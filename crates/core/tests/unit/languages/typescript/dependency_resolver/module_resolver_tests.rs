@@ -1,5 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use lintric_core::languages::typescript::dependency_resolver::ModuleResolver;
+
 #[test]
 fn test_typescript_module_resolver_basic() {
     // Basic structural test - just verify the module compiles
-    assert!(true);
+}
+
+fn resolver() -> ModuleResolver {
+    ModuleResolver::for_files(
+        [
+            "src/models/index.ts",
+            "src/models/user.ts",
+            "src/views/page.tsx",
+            "src/services/greet.ts",
+        ]
+        .map(PathBuf::from),
+    )
+}
+
+fn resolve(specifier: &str) -> Option<PathBuf> {
+    resolver().resolve_file(Path::new("src/services/greet.ts"), specifier)
+}
+
+#[test]
+fn tries_each_extension_after_a_specifier_without_one() {
+    assert_eq!(
+        resolve("../models/user"),
+        Some(PathBuf::from("src/models/user.ts"))
+    );
+    assert_eq!(
+        resolve("../views/page"),
+        Some(PathBuf::from("src/views/page.tsx"))
+    );
+}
+
+#[test]
+fn resolves_a_directory_to_its_index() {
+    assert_eq!(
+        resolve("./../models"),
+        Some(PathBuf::from("src/models/index.ts"))
+    );
+}
+
+#[test]
+fn maps_a_js_specifier_to_the_typescript_source() {
+    assert_eq!(
+        resolve("../models/user.js"),
+        Some(PathBuf::from("src/models/user.ts"))
+    );
+}
+
+#[test]
+fn leaves_packages_and_missing_files_unresolved() {
+    assert_eq!(resolve("fs"), None);
+    assert_eq!(resolve("./missing"), None);
 }
@@ -1,5 +1,4 @@
 #[test]
 fn test_typescript_dependency_resolver_basic() {
     // Basic structural test - just verify the module compiles
-    assert!(true);
 }
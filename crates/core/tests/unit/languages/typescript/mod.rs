@@ -5,3 +5,4 @@ pub mod interface_implementation_tests;
 pub mod object_shape_tests;
 pub mod parameter_property_tests;
 pub mod receiver_narrowing_tests;
+pub mod tsconfig_tests;
@@ -75,9 +75,9 @@ fn a_shorthand_property_reads_the_binding_it_names() {
     let source = "const x = 1;\nconst b = { x };\n";
 
     assert!(
-        dependencies(&source).contains(&(2, 1, "x".to_string())),
+        dependencies(source).contains(&(2, 1, "x".to_string())),
         "{:?}",
-        dependencies(&source)
+        dependencies(source)
     );
 }
 
@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use lintric_core::languages::typescript::tsconfig::{PathMapping, TsConfig};
+
+const FIXTURE: &str = "tests/integration/project/fixtures/typescript_paths";
+
+#[test]
+fn takes_options_from_the_config_it_extends() {
+    let config = TsConfig::load(Path::new(&format!("{FIXTURE}/tsconfig.json"))).unwrap();
+
+    assert_eq!(config.directory, PathBuf::from(FIXTURE));
+    assert_eq!(
+        config.base_url,
+        Some(PathBuf::from(format!("{FIXTURE}/src")))
+    );
+    assert_eq!(
+        config.matching_paths("@config"),
+        Some(vec![PathBuf::from(format!(
+            "{FIXTURE}/src/config/index.ts"
+        ))])
+    );
+}
+
+#[test]
+fn finds_the_closest_config_above_a_file() {
+    assert_eq!(
+        TsConfig::find(Path::new(&format!("{FIXTURE}/src/models/user.ts"))),
+        Some(PathBuf::from(format!("{FIXTURE}/tsconfig.json")))
+    );
+}
+
+#[test]
+fn prefers_the_longest_pattern_prefix() {
+    let config = TsConfig {
+        directory: PathBuf::new(),
+        base_url: None,
+        paths: vec![
+            PathMapping {
+                pattern: "@app/*".to_string(),
+                substitutions: vec![PathBuf::from("src/*")],
+            },
+            PathMapping {
+                pattern: "@app/ui/*".to_string(),
+                substitutions: vec![PathBuf::from("packages/ui/*")],
+            },
+        ],
+    };
+
+    assert_eq!(
+        config.matching_paths("@app/ui/button"),
+        Some(vec![PathBuf::from("packages/ui/button")])
+    );
+    assert_eq!(
+        config.matching_paths("@app/models"),
+        Some(vec![PathBuf::from("src/models")])
+    );
+    assert_eq!(config.matching_paths("react"), None);
+}
@@ -1,14 +1,19 @@
+use crate::unit::helpers::{dependency, span};
 use lintric_core::metric_calculator::calculate_metrics;
 use lintric_core::models::{Dependency, DependencyType, IntermediateRepresentation};
+use std::collections::BTreeMap;
 
 #[test]
 fn test_simple_dependency() {
     let dependencies = vec![Dependency {
         source_line: 2,
         target_line: 1,
+        source_span: span(2),
+        target_span: span(1),
         symbol: "a".to_string(),
         dependency_type: DependencyType::VariableUse,
         context: None,
+        target_file: None,
     }];
     let ir = IntermediateRepresentation::new(
         "test.rs".to_string(),
@@ -51,16 +56,22 @@ fn test_multiple_dependencies() {
         Dependency {
             source_line: 3,
             target_line: 1,
+            source_span: span(3),
+            target_span: span(1),
             symbol: "a".to_string(),
             dependency_type: DependencyType::VariableUse,
             context: None,
+            target_file: None,
         },
         Dependency {
             source_line: 3,
             target_line: 2,
+            source_span: span(3),
+            target_span: span(2),
             symbol: "b".to_string(),
             dependency_type: DependencyType::VariableUse,
             context: None,
+            target_file: None,
         },
     ];
     let ir = IntermediateRepresentation::new(
@@ -89,16 +100,22 @@ fn test_transitive_dependencies() {
         Dependency {
             source_line: 2,
             target_line: 1,
+            source_span: span(2),
+            target_span: span(1),
             symbol: "a".to_string(),
             dependency_type: DependencyType::VariableUse,
             context: None,
+            target_file: None,
         },
         Dependency {
             source_line: 3,
             target_line: 2,
+            source_span: span(3),
+            target_span: span(2),
             symbol: "b".to_string(),
             dependency_type: DependencyType::VariableUse,
             context: None,
+            target_file: None,
         },
     ];
     let ir = IntermediateRepresentation::new(
@@ -128,16 +145,22 @@ fn test_cyclic_dependencies() {
         Dependency {
             source_line: 1,
             target_line: 2,
+            source_span: span(1),
+            target_span: span(2),
             symbol: "b".to_string(),
             dependency_type: DependencyType::FunctionCall,
             context: None,
+            target_file: None,
         },
         Dependency {
             source_line: 2,
             target_line: 1,
+            source_span: span(2),
+            target_span: span(1),
             symbol: "a".to_string(),
             dependency_type: DependencyType::FunctionCall,
             context: None,
+            target_file: None,
         },
     ];
     let ir = IntermediateRepresentation::new(
@@ -231,14 +254,157 @@ fn counts_a_repeated_reference_once_even_when_the_symbols_differ() {
     assert_eq!(metrics.total_dependencies, 1);
 }
 
-fn dependency(source_line: usize, target_line: usize, symbol: &str) -> Dependency {
-    Dependency {
-        source_line,
-        target_line,
-        symbol: symbol.to_string(),
-        dependency_type: DependencyType::VariableUse,
-        context: None,
-    }
+#[test]
+fn counts_an_edge_into_another_file_as_a_dependency_at_full_distance() {
+    let mut into_other_file = dependency(2, 7, "User");
+    into_other_file.target_file = Some("models.rs".to_string());
+    let ir = ir_with(vec![dependency(2, 1, "x"), into_other_file], 2);
+    let metrics = line_metrics(&ir, "a\nb", 2);
+
+    assert_eq!(metrics.total_dependencies, 2);
+    assert_eq!(metrics.dependency_distance_cost, 0.5 + 1.0);
+    // The other file's lines are not in this file's graph, so nothing is reached through them.
+    assert_eq!(metrics.transitive_dependencies, 1);
+    assert_eq!(metrics.dependent_lines, vec![1]);
+}
+
+#[test]
+fn counts_the_lines_that_depend_on_a_line_directly_and_through_others() {
+    let ir = ir_with(
+        vec![
+            dependency(2, 1, "a"),
+            dependency(3, 2, "b"),
+            dependency(4, 2, "b"),
+            dependency(4, 1, "a"),
+        ],
+        4,
+    );
+    let code = "a\nb\nc\nd";
+
+    let first = line_metrics(&ir, code, 1);
+    assert_eq!((first.fan_in, first.blast_radius), (2, 3));
+    let second = line_metrics(&ir, code, 2);
+    assert_eq!((second.fan_in, second.blast_radius), (2, 2));
+    let last = line_metrics(&ir, code, 4);
+    assert_eq!((last.fan_in, last.blast_radius), (0, 0));
+}
+
+#[test]
+fn counts_the_dependencies_of_each_line_by_type() {
+    let mut type_reference = dependency(3, 1, "Id");
+    type_reference.dependency_type = DependencyType::TypeReference;
+    let mut call = dependency(3, 2, "parse");
+    call.dependency_type = DependencyType::FunctionCall;
+    let ir = ir_with(
+        vec![
+            dependency(3, 1, "id"),
+            type_reference,
+            call,
+            dependency(2, 1, "id"),
+        ],
+        3,
+    );
+    let code = "a\nb\nc";
+
+    let metrics = line_metrics(&ir, code, 3);
+    // Line 1 is depended on in two ways, and counts under each.
+    assert_eq!(metrics.total_dependencies, 2);
+    assert_eq!(
+        metrics.dependency_types,
+        BTreeMap::from([
+            ("FunctionCall".to_string(), 1),
+            ("TypeReference".to_string(), 1),
+            ("VariableUse".to_string(), 1),
+        ])
+    );
+    assert_eq!(
+        calculate_metrics(&ir, code).unwrap().dependency_types,
+        BTreeMap::from([
+            ("FunctionCall".to_string(), 1),
+            ("TypeReference".to_string(), 1),
+            ("VariableUse".to_string(), 2),
+        ])
+    );
+}
+
+#[test]
+fn test_excluded_dependency_types_are_left_out_of_the_metrics() {
+    let code = r#"struct Point {
+    x: i32,
+}
+
+fn shift(point: Point, by: i32) -> Point {
+    let x = point.x + by;
+    Point { x }
+}
+"#;
+    let (ir, result) =
+        lintric_core::analyze_content(code.to_string(), lintric_core::Language::Rust).unwrap();
+    assert!(result.dependency_types.contains_key("TypeReference"));
+
+    let options = lintric_core::MeasureOptions {
+        excluded_dependency_types: vec![DependencyType::TypeReference],
+        ..Default::default()
+    };
+    let measured =
+        lintric_core::remeasure(&ir, code, lintric_core::Language::Rust, &result, &options)
+            .unwrap();
+    assert!(!measured.dependency_types.contains_key("TypeReference"));
+    assert_eq!(
+        measured.dependency_types,
+        result
+            .dependency_types
+            .iter()
+            .filter(|(type_name, _)| *type_name != "TypeReference")
+            .map(|(type_name, count)| (type_name.clone(), *count))
+            .collect::<BTreeMap<_, _>>()
+    );
+    assert!(measured.overall_complexity_score < result.overall_complexity_score);
+    assert_eq!(
+        measured
+            .item_metrics
+            .iter()
+            .map(|item| &item.name)
+            .collect::<Vec<_>>(),
+        vec!["shift"]
+    );
+    assert!(measured.item_metrics[0].complexity_score < result.item_metrics[0].complexity_score);
+}
+
+#[test]
+fn test_remeasure_scores_with_the_weights_of_its_options() {
+    let code = r#"fn shift(x: i32, by: i32) -> i32 {
+    let moved = x + by;
+    moved * 2
+}
+"#;
+    let (ir, result) =
+        lintric_core::analyze_content(code.to_string(), lintric_core::Language::Rust).unwrap();
+
+    let weights = lintric_core::ScoreWeights {
+        depth: 3.0,
+        fan_in: 0.5,
+        ..Default::default()
+    };
+    let options = lintric_core::MeasureOptions {
+        weights,
+        ..Default::default()
+    };
+    let measured =
+        lintric_core::remeasure(&ir, code, lintric_core::Language::Rust, &result, &options)
+            .unwrap();
+
+    let mut rescored = result.clone();
+    rescored.rescore(&weights);
+    assert_eq!(
+        measured.overall_complexity_score,
+        rescored.overall_complexity_score
+    );
+    assert_eq!(
+        measured.item_metrics[0].complexity_score,
+        rescored.item_metrics[0].complexity_score
+    );
+    assert!(measured.overall_complexity_score > result.overall_complexity_score);
 }
 
 fn ir_with(dependencies: Vec<Dependency>, total_lines: usize) -> IntermediateRepresentation {
@@ -264,3 +430,55 @@ fn line_metrics(
         .find(|metrics| metrics.line_number == line)
         .unwrap()
 }
+
+#[test]
+fn test_item_metrics_gather_the_lines_of_each_function_and_class() {
+    let code = r#"class Counter {
+  count = 0;
+
+  increment(step: number): number {
+    const next = this.count + step;
+    this.count = next;
+    return next;
+  }
+}
+
+function run(): number {
+  const counter = new Counter();
+  return counter.increment(1);
+}
+"#;
+    let (_, result) =
+        lintric_core::analyze_content(code.to_string(), lintric_core::Language::TypeScript)
+            .unwrap();
+    let items: Vec<(&str, usize, usize, usize, usize)> = result
+        .item_metrics
+        .iter()
+        .map(|item| {
+            (
+                item.name.as_str(),
+                item.start_line,
+                item.end_line,
+                item.total_dependencies,
+                item.max_depth,
+            )
+        })
+        .collect();
+    assert_eq!(
+        items,
+        vec![
+            ("Counter", 1, 9, 5, 2),
+            ("Counter.increment", 4, 8, 5, 2),
+            ("run", 11, 14, 3, 2),
+        ]
+    );
+
+    // The scores of a file's items add up to the file's, less what its classes count twice.
+    let scores: Vec<f64> = result
+        .item_metrics
+        .iter()
+        .map(|item| item.complexity_score)
+        .collect();
+    assert_eq!(scores[0], scores[1]);
+    assert!((scores[1] + scores[2] - result.overall_complexity_score).abs() < 1e-9);
+}
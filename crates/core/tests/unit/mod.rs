@@ -1,4 +1,10 @@
+pub mod cycles;
+pub mod definition_graph;
+pub mod graph_export;
+pub mod helpers;
 pub mod languages;
 pub mod metric_calculator;
 pub mod models;
 pub mod query;
+pub mod session;
+pub mod statements;
@@ -11,7 +11,7 @@ fn test_definition_creation() {
 
     let definition = Definition {
         name: "test_function".to_string(),
-        position: position.clone(),
+        position,
         definition_type: DefinitionType::FunctionDefinition,
         scope_id: None,
         accessibility: None,
@@ -37,7 +37,7 @@ fn test_definition_types() {
 
     let function_def = Definition {
         name: "func".to_string(),
-        position: position.clone(),
+        position,
         definition_type: DefinitionType::FunctionDefinition,
         scope_id: None,
         accessibility: None,
@@ -46,7 +46,7 @@ fn test_definition_types() {
 
     let variable_def = Definition {
         name: "var".to_string(),
-        position: position.clone(),
+        position,
         definition_type: DefinitionType::VariableDefinition,
         scope_id: None,
         accessibility: None,
@@ -55,7 +55,7 @@ fn test_definition_types() {
 
     let struct_def = Definition {
         name: "Struct".to_string(),
-        position: position.clone(),
+        position,
         definition_type: DefinitionType::StructDefinition,
         scope_id: None,
         accessibility: None,
@@ -88,7 +88,7 @@ fn test_definition_new_simple() {
     let definition = Definition::new_simple(
         "test_var".to_string(),
         DefinitionType::VariableDefinition,
-        position.clone(),
+        position,
     );
 
     assert_eq!(definition.name, "test_var");
@@ -110,7 +110,7 @@ fn test_definition_equality() {
 
     let def1 = Definition {
         name: "test".to_string(),
-        position: position.clone(),
+        position,
         definition_type: DefinitionType::FunctionDefinition,
         scope_id: None,
         accessibility: None,
@@ -119,7 +119,7 @@ fn test_definition_equality() {
 
     let def2 = Definition {
         name: "test".to_string(),
-        position: position.clone(),
+        position,
         definition_type: DefinitionType::FunctionDefinition,
         scope_id: None,
         accessibility: None,
@@ -128,7 +128,7 @@ fn test_definition_equality() {
 
     let def3 = Definition {
         name: "different".to_string(),
-        position: position.clone(),
+        position,
         definition_type: DefinitionType::FunctionDefinition,
         scope_id: None,
         accessibility: None,
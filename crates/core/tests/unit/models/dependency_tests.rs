@@ -1,13 +1,17 @@
-use lintric_core::models::{Dependency, DependencyType};
+use crate::unit::helpers::span;
+use lintric_core::models::{Dependency, DependencyType, Position};
 
 #[test]
 fn test_dependency_creation() {
     let dependency = Dependency {
         source_line: 1,
         target_line: 5,
+        source_span: span(1),
+        target_span: span(5),
         symbol: "function_call".to_string(),
         dependency_type: DependencyType::FunctionCall,
         context: Some("test context".to_string()),
+        target_file: None,
     };
 
     assert_eq!(dependency.source_line, 1);
@@ -22,25 +26,34 @@ fn test_dependency_types() {
     let function_dep = Dependency {
         source_line: 1,
         target_line: 5,
+        source_span: span(1),
+        target_span: span(5),
         symbol: "caller".to_string(),
         dependency_type: DependencyType::FunctionCall,
         context: None,
+        target_file: None,
     };
 
     let variable_dep = Dependency {
         source_line: 2,
         target_line: 6,
+        source_span: span(2),
+        target_span: span(6),
         symbol: "variable".to_string(),
         dependency_type: DependencyType::VariableUse,
         context: None,
+        target_file: None,
     };
 
     let type_dep = Dependency {
         source_line: 3,
         target_line: 7,
+        source_span: span(3),
+        target_span: span(7),
         symbol: "Type".to_string(),
         dependency_type: DependencyType::TypeReference,
         context: None,
+        target_file: None,
     };
 
     assert!(matches!(
@@ -62,9 +75,12 @@ fn test_dependency_clone() {
     let original = Dependency {
         source_line: 1,
         target_line: 5,
+        source_span: span(1),
+        target_span: span(5),
         symbol: "test_symbol".to_string(),
         dependency_type: DependencyType::VariableUse,
         context: Some("context".to_string()),
+        target_file: None,
     };
 
     let cloned = original.clone();
@@ -81,9 +97,12 @@ fn test_dependency_debug() {
     let dependency = Dependency {
         source_line: 10,
         target_line: 20,
+        source_span: span(10),
+        target_span: span(20),
         symbol: "debug_test".to_string(),
         dependency_type: DependencyType::Import,
         context: None,
+        target_file: None,
     };
 
     let debug_str = format!("{:?}", dependency);
@@ -91,3 +110,35 @@ fn test_dependency_debug() {
     assert!(debug_str.contains("10"));
     assert!(debug_str.contains("20"));
 }
+
+#[test]
+fn test_dependencies_carry_the_spans_of_their_usage_and_definition() {
+    let code = "fn main() {\n    let total = 1;\n    println!(\"{}\", total + total);\n}\n";
+    let (ir, _) =
+        lintric_core::analyze_content(code.to_string(), lintric_core::Language::Rust).unwrap();
+
+    let mut spans: Vec<(Position, Position)> = ir
+        .dependencies
+        .iter()
+        .filter(|dependency| dependency.symbol == "total")
+        .map(|dependency| (dependency.source_span, dependency.target_span))
+        .collect();
+    spans.sort();
+    let declaration = Position {
+        start_line: 2,
+        start_column: 9,
+        end_line: 2,
+        end_column: 14,
+    };
+    let usage = |start_column: usize| Position {
+        start_line: 3,
+        start_column,
+        end_line: 3,
+        end_column: start_column + 5,
+    };
+    // Each usage is an edge of its own, down to the column.
+    assert_eq!(
+        spans,
+        vec![(usage(20), declaration), (usage(28), declaration)]
+    );
+}
@@ -1,4 +1,7 @@
-use lintric_core::models::{AnalysisResult, LineMetrics, OverallAnalysisReport};
+use lintric_core::models::{
+    AnalysisResult, DefinitionType, ItemMetrics, LineMetrics, OverallAnalysisReport, ScoreWeights,
+};
+use std::collections::BTreeMap;
 
 #[test]
 fn test_line_metrics_creation() {
@@ -9,6 +12,9 @@ fn test_line_metrics_creation() {
         depth: 2,
         transitive_dependencies: 8,
         dependent_lines: vec![1, 3, 7, 15],
+        fan_in: 0,
+        blast_radius: 0,
+        dependency_types: BTreeMap::new(),
     };
 
     assert_eq!(metrics.line_number, 10);
@@ -29,6 +35,9 @@ fn test_analysis_result_creation() {
             depth: 1,
             transitive_dependencies: 3,
             dependent_lines: vec![5, 10],
+            fan_in: 0,
+            blast_radius: 0,
+            dependency_types: BTreeMap::new(),
         },
         LineMetrics {
             line_number: 5,
@@ -37,13 +46,22 @@ fn test_analysis_result_creation() {
             depth: 0,
             transitive_dependencies: 1,
             dependent_lines: vec![],
+            fan_in: 0,
+            blast_radius: 0,
+            dependency_types: BTreeMap::new(),
         },
     ];
 
     let result = AnalysisResult {
         file_path: "src/main.rs".to_string(),
         line_metrics: line_metrics.clone(),
+        item_metrics: vec![],
+        line_cycles: vec![],
+        definition_cycles: vec![],
+        cycle_count: 0,
+        dependency_types: BTreeMap::new(),
         overall_complexity_score: 7.5,
+        weights: None,
     };
 
     assert_eq!(result.file_path, "src/main.rs");
@@ -56,7 +74,13 @@ fn test_overall_analysis_report_creation() {
     let analysis_result = AnalysisResult {
         file_path: "test.rs".to_string(),
         line_metrics: vec![],
+        item_metrics: vec![],
+        line_cycles: vec![],
+        definition_cycles: vec![],
+        cycle_count: 0,
+        dependency_types: BTreeMap::new(),
         overall_complexity_score: 10.0,
+        weights: None,
     };
 
     let report = OverallAnalysisReport {
@@ -64,6 +88,7 @@ fn test_overall_analysis_report_creation() {
         total_files_analyzed: 1,
         total_overall_complexity_score: 10.0,
         average_complexity_score: 10.0,
+        weights: ScoreWeights::default(),
     };
 
     assert_eq!(report.results.len(), 1);
@@ -81,6 +106,9 @@ fn test_line_metrics_clone() {
         depth: 2,
         transitive_dependencies: 5,
         dependent_lines: vec![2, 8, 12],
+        fan_in: 0,
+        blast_radius: 0,
+        dependency_types: BTreeMap::new(),
     };
 
     let cloned = original.clone();
@@ -105,7 +133,13 @@ fn test_analysis_result_clone() {
     let original = AnalysisResult {
         file_path: "src/lib.rs".to_string(),
         line_metrics: vec![],
+        item_metrics: vec![],
+        line_cycles: vec![],
+        definition_cycles: vec![],
+        cycle_count: 0,
+        dependency_types: BTreeMap::new(),
         overall_complexity_score: 5.5,
+        weights: None,
     };
 
     let cloned = original.clone();
@@ -127,9 +161,115 @@ fn test_metrics_debug() {
         depth: 0,
         transitive_dependencies: 1,
         dependent_lines: vec![25],
+        fan_in: 0,
+        blast_radius: 0,
+        dependency_types: BTreeMap::new(),
     };
 
     let debug_str = format!("{:?}", metrics);
     assert!(debug_str.contains("20"));
     assert!(debug_str.contains("3.0"));
 }
+
+#[test]
+fn test_default_score_weights() {
+    let metrics = LineMetrics {
+        line_number: 3,
+        total_dependencies: 2,
+        dependency_distance_cost: 1.5,
+        depth: 3,
+        transitive_dependencies: 4,
+        dependent_lines: vec![1, 2],
+        fan_in: 0,
+        blast_radius: 0,
+        dependency_types: BTreeMap::new(),
+    };
+
+    // total + distance / 10 + depth + transitive / 5
+    assert_eq!(
+        ScoreWeights::default().line_score(&metrics),
+        2.0 + 1.5 / 10.0 + 3.0 + 4.0 / 5.0
+    );
+}
+
+#[test]
+fn test_rescore_with_other_weights() {
+    let mut result = AnalysisResult {
+        file_path: "src/lib.rs".to_string(),
+        line_metrics: vec![
+            LineMetrics {
+                line_number: 2,
+                total_dependencies: 1,
+                dependency_distance_cost: 0.5,
+                depth: 1,
+                transitive_dependencies: 1,
+                dependent_lines: vec![1],
+                fan_in: 0,
+                blast_radius: 0,
+                dependency_types: BTreeMap::new(),
+            },
+            LineMetrics {
+                line_number: 3,
+                total_dependencies: 1,
+                dependency_distance_cost: 0.25,
+                depth: 2,
+                transitive_dependencies: 2,
+                dependent_lines: vec![2],
+                fan_in: 0,
+                blast_radius: 0,
+                dependency_types: BTreeMap::new(),
+            },
+        ],
+        item_metrics: vec![ItemMetrics {
+            name: "f".to_string(),
+            definition_type: DefinitionType::FunctionDefinition,
+            start_line: 3,
+            end_line: 4,
+            total_dependencies: 1,
+            max_depth: 2,
+            complexity_score: 0.0,
+        }],
+        line_cycles: vec![],
+        definition_cycles: vec![],
+        cycle_count: 0,
+        dependency_types: BTreeMap::new(),
+        overall_complexity_score: 0.0,
+        weights: None,
+    };
+
+    let depth_only = ScoreWeights {
+        total_dependencies: 0.0,
+        dependency_distance_cost: 0.0,
+        depth: 2.0,
+        transitive_dependencies: 0.0,
+        fan_in: 0.0,
+        blast_radius: 0.0,
+    };
+    result.rescore(&depth_only);
+
+    assert_eq!(result.overall_complexity_score, 6.0);
+    assert_eq!(result.item_metrics[0].complexity_score, 4.0);
+}
+
+#[test]
+fn test_impact_counts_only_when_weighted() {
+    let metrics = LineMetrics {
+        line_number: 1,
+        total_dependencies: 0,
+        dependency_distance_cost: 0.0,
+        depth: 0,
+        transitive_dependencies: 0,
+        dependent_lines: vec![],
+        fan_in: 3,
+        blast_radius: 5,
+        dependency_types: BTreeMap::new(),
+    };
+    assert_eq!(ScoreWeights::default().line_score(&metrics), 0.0);
+
+    let impact = ScoreWeights {
+        fan_in: 1.0,
+        blast_radius: 0.5,
+        ..ScoreWeights::default()
+    };
+    assert_eq!(impact.line_score(&metrics), 3.0 + 2.5);
+}
@@ -76,7 +76,7 @@ fn test_position_clone() {
         end_column: 20,
     };
 
-    let cloned = original.clone();
+    let cloned = original;
 
     assert_eq!(original.start_line, cloned.start_line);
     assert_eq!(original.start_column, cloned.start_column);
@@ -21,7 +21,7 @@ fn test_usage_creation() {
     let usage = Usage {
         name: "variable_name".to_string(),
         kind: UsageKind::Identifier,
-        position: position.clone(),
+        position,
         context: None,
         scope_id: None,
     };
@@ -43,7 +43,7 @@ fn test_usage_kinds() {
     let identifier_usage = Usage {
         name: "var".to_string(),
         kind: UsageKind::Identifier,
-        position: position.clone(),
+        position,
         context: None,
         scope_id: None,
     };
@@ -51,7 +51,7 @@ fn test_usage_kinds() {
     let call_usage = Usage {
         name: "function_call".to_string(),
         kind: UsageKind::CallExpression,
-        position: position.clone(),
+        position,
         context: None,
         scope_id: None,
     };
@@ -59,7 +59,7 @@ fn test_usage_kinds() {
     let field_usage = Usage {
         name: "field".to_string(),
         kind: UsageKind::FieldExpression,
-        position: position.clone(),
+        position,
         context: None,
         scope_id: None,
     };
@@ -102,7 +102,7 @@ fn test_usage_new_simple() {
         end_column: 20,
     };
 
-    let usage = Usage::new_simple("test_usage".to_string(), position.clone(), UsageKind::Read);
+    let usage = Usage::new_simple("test_usage".to_string(), position, UsageKind::Read);
 
     assert_eq!(usage.name, "test_usage");
     assert_eq!(usage.position.start_line, position.start_line);
@@ -142,7 +142,7 @@ fn test_usage_fields_access() {
     let usage = Usage {
         name: "test".to_string(),
         kind: UsageKind::Identifier,
-        position: position.clone(),
+        position,
         context: None,
         scope_id: None,
     };
@@ -0,0 +1,186 @@
+use lintric_core::{analyze_content, AnalysisSession, Language, TextEdit};
+
+const RUST: &str = r#"const LIMIT: usize = 10;
+
+struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    fn increment(&mut self) -> usize {
+        let next = self.count + 1;
+        self.count = next.min(LIMIT);
+        self.count
+    }
+}
+
+fn total(counter: &mut Counter) -> usize {
+    let first = counter.increment();
+    let second = counter.increment();
+    first + second
+}
+
+fn main() {
+    let mut counter = Counter { count: 0 };
+    let sum = total(&mut counter);
+    println!("{}", sum);
+}
+"#;
+
+const TYPESCRIPT: &str = r#"const LIMIT = 10;
+
+class Counter {
+  count = 0;
+
+  increment(): number {
+    const next = this.count + 1;
+    this.count = Math.min(next, LIMIT);
+    return this.count;
+  }
+}
+
+function total(counter: Counter): number {
+  const first = counter.increment();
+  const second = counter.increment();
+  return first + second;
+}
+
+const main = () => {
+  const counter = new Counter();
+  const sum = total(counter);
+  console.log(sum);
+};
+"#;
+
+/// Apply each edit, replacing `old` at its first occurrence with `new`, and check the session
+/// against an analysis of the edited text from scratch.
+fn assert_edits_analyze_as_from_scratch(content: &str, language: Language, edits: &[(&str, &str)]) {
+    let mut session = AnalysisSession::new(content.to_string(), language.clone()).unwrap();
+    let mut text = content.to_string();
+
+    for (old, new) in edits {
+        let start_byte = text.find(old).unwrap();
+        let edit = TextEdit {
+            start_byte,
+            old_end_byte: start_byte + old.len(),
+            text: new.to_string(),
+        };
+        text.replace_range(edit.start_byte..edit.old_end_byte, new);
+        session.edit(&edit).unwrap();
+
+        let (ir, result) = analyze_content(text.clone(), language.clone()).unwrap();
+        assert_eq!(session.content(), text);
+        assert_eq!(
+            serde_json::to_value(session.ir()).unwrap(),
+            serde_json::to_value(&ir).unwrap(),
+            "IR after replacing {old:?} with {new:?}"
+        );
+        assert_eq!(
+            serde_json::to_value(session.result()).unwrap(),
+            serde_json::to_value(&result).unwrap(),
+            "metrics after replacing {old:?} with {new:?}"
+        );
+    }
+}
+
+#[test]
+fn test_rust_edits_inside_function_bodies_analyze_as_from_scratch() {
+    assert_edits_analyze_as_from_scratch(
+        RUST,
+        Language::Rust,
+        &[
+            // A line added, moving every item below it.
+            (
+                "    first + second\n",
+                "    let third = first * 2;\n    first + second + third\n",
+            ),
+            // A binding renamed, and its usage with it.
+            ("let first", "let initial"),
+            ("first + second + third", "initial + second + third"),
+            // A usage of something declared elsewhere, and in a method.
+            ("let sum = total", "let sum = LIMIT + total"),
+            ("self.count + 1", "self.count + LIMIT"),
+            // A line removed.
+            ("    let third = first * 2;\n", ""),
+            ("initial + second + third", "initial + second"),
+        ],
+    );
+}
+
+#[test]
+fn test_typescript_edits_inside_function_bodies_analyze_as_from_scratch() {
+    assert_edits_analyze_as_from_scratch(
+        TYPESCRIPT,
+        Language::TypeScript,
+        &[
+            (
+                "  return first + second;\n",
+                "  const third = first * 2;\n  return first + second + third;\n",
+            ),
+            ("const first", "const initial"),
+            ("return first", "return initial"),
+            ("const sum = total", "const sum = LIMIT + total"),
+            ("this.count + 1", "this.count + LIMIT"),
+            ("  const third = first * 2;\n", ""),
+        ],
+    );
+}
+
+#[test]
+fn test_edits_outside_function_bodies_analyze_as_from_scratch() {
+    assert_edits_analyze_as_from_scratch(
+        RUST,
+        Language::Rust,
+        &[
+            // A signature, an item and a declaration visible outside the function it is in.
+            (
+                "fn total(counter: &mut Counter)",
+                "fn total(counter: &mut Counter, extra: usize)",
+            ),
+            ("first + second\n", "first + second + extra\n"),
+            (
+                "fn main() {",
+                "fn helper() -> usize {\n    LIMIT\n}\n\nfn main() {",
+            ),
+            (
+                "    println!",
+                "    impl Counter {\n        fn reset(&mut self) {}\n    }\n    println!",
+            ),
+            // An unclosed brace, which changes how the rest of the file parses.
+            ("let sum = total", "{ let sum = total"),
+            ("{ let sum = total", "let sum = total"),
+        ],
+    );
+}
+
+#[test]
+fn test_every_line_broken_in_turn_analyzes_as_from_scratch() {
+    let line_breaks: Vec<(&str, String)> = RUST
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.len() > 1)
+        .map(|line| {
+            let (head, tail) = line.split_at(line.len() / 2);
+            (line, format!("{head}\n{tail}"))
+        })
+        .collect();
+
+    for (line, broken) in &line_breaks {
+        assert_edits_analyze_as_from_scratch(RUST, Language::Rust, &[(line, broken)]);
+    }
+}
+
+#[test]
+fn test_an_edit_out_of_range_is_refused() {
+    let mut session = AnalysisSession::new(RUST.to_string(), Language::Rust).unwrap();
+    let before = serde_json::to_value(session.ir()).unwrap();
+
+    let edit = TextEdit {
+        start_byte: RUST.len(),
+        old_end_byte: RUST.len() + 1,
+        text: "x".to_string(),
+    };
+    assert!(session.edit(&edit).is_err());
+    assert_eq!(session.content(), RUST);
+    assert_eq!(serde_json::to_value(session.ir()).unwrap(), before);
+}
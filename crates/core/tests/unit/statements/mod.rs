@@ -0,0 +1,129 @@
+use lintric_core::statements::Statements;
+use lintric_core::FileParser;
+use lintric_core::{analyze_content, remeasure, Granularity, Language, MeasureOptions};
+
+const ONE_LINE: &str = r#"struct Builder {
+    size: usize,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Builder { size: 0 }
+    }
+
+    fn size(self, size: usize) -> Self {
+        Builder { size }
+    }
+}
+
+fn build(width: usize, height: usize) -> Builder {
+    let area = width * height;
+    let builder = Builder::new().size(area).size(width).size(height);
+    builder
+}
+"#;
+
+const SPLIT: &str = r#"struct Builder {
+    size: usize,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Builder { size: 0 }
+    }
+
+    fn size(self, size: usize) -> Self {
+        Builder { size }
+    }
+}
+
+fn build(
+    width: usize,
+    height: usize,
+) -> Builder {
+    let area = width
+        * height;
+    let builder = Builder::new()
+        .size(area)
+        .size(width)
+        .size(height);
+    builder
+}
+"#;
+
+fn statement_metrics(code: &str) -> lintric_core::AnalysisResult {
+    let (ir, result) = analyze_content(code.to_string(), Language::Rust).unwrap();
+    let options = MeasureOptions {
+        granularity: Granularity::Statement,
+        ..Default::default()
+    };
+    remeasure(&ir, code, Language::Rust, &result, &options).unwrap()
+}
+
+#[test]
+fn test_each_line_belongs_to_the_innermost_statement_spanning_it() {
+    let (_, language, tree) = FileParser::from_content(SPLIT.to_string(), Language::Rust)
+        .parse()
+        .unwrap();
+    let statements = Statements::find(tree.root_node(), &language, SPLIT.lines().count());
+
+    let first_lines: Vec<usize> = (15..=25).map(|line| statements.first_line(line)).collect();
+    // The signature is the function's, the body's lines their statements', and the closing brace
+    // the function's again.
+    assert_eq!(
+        first_lines,
+        vec![15, 15, 15, 15, 19, 19, 21, 21, 21, 21, 25]
+    );
+    assert_eq!(statements.first_line(26), 15);
+    assert_eq!(statements.first_line(4), 4);
+}
+
+#[test]
+fn test_statement_metrics_do_not_change_with_formatting() {
+    let one_line = statement_metrics(ONE_LINE);
+    let split = statement_metrics(SPLIT);
+
+    assert_eq!(
+        one_line.overall_complexity_score,
+        split.overall_complexity_score
+    );
+    let shape = |result: &lintric_core::AnalysisResult| -> Vec<(usize, usize, usize)> {
+        result
+            .line_metrics
+            .iter()
+            .map(|metrics| {
+                (
+                    metrics.total_dependencies,
+                    metrics.depth,
+                    metrics.transitive_dependencies,
+                )
+            })
+            .collect()
+    };
+    assert_eq!(shape(&one_line), shape(&split));
+
+    // Each statement is reported against its first line.
+    let lines: Vec<usize> = split
+        .line_metrics
+        .iter()
+        .map(|metrics| metrics.line_number)
+        .collect();
+    assert_eq!(lines, vec![1, 2, 5, 6, 7, 10, 11, 15, 19, 21, 25]);
+    let builder = split
+        .line_metrics
+        .iter()
+        .find(|metrics| metrics.line_number == 25)
+        .unwrap();
+    assert_eq!(builder.dependent_lines, vec![21]);
+}
+
+#[test]
+fn test_line_metrics_do_change_with_formatting() {
+    let (_, one_line) = analyze_content(ONE_LINE.to_string(), Language::Rust).unwrap();
+    let (_, split) = analyze_content(SPLIT.to_string(), Language::Rust).unwrap();
+
+    assert_ne!(
+        one_line.overall_complexity_score,
+        split.overall_complexity_score
+    );
+}
@@ -169,8 +169,6 @@ fn {}() {{
             r#"// Generated tests for {} node types
 // This file is auto-generated. Do not edit manually.
 
-use lintric_core;
-
 // Excluded node types (could not generate snippets):
 {}
 
@@ -1,8 +1,6 @@
 // Generated tests for TypeScript node types
 // This file is auto-generated. Do not edit manually.
 
-use lintric_core;
-
 // Excluded node types (could not generate snippets):
 // !
 // !=